@@ -6,26 +6,346 @@
 //! - Calculation of price changes after tweets
 //! - Statistical correlation analysis
 
-use crate::models::{AnalysisResult, PricePoint, Tweet, TweetImpact};
+use crate::models::{
+    preprocess_tweet_text, AnalysisResult, CalibrationBucket, CausalComponents, CorrelationDetail, CorrelationPair, CoverageReason, DailyPoint, PriceBasis, PricePoint, RawFetch,
+    AttributionMode, RepeatMatching, ResultStatus, ReturnType, RunConfig, RiseMatrix, SentimentMatrix, SurpriseComponents, TextOptions, Tweet,
+    TweetImpact,
+};
+use crate::lexicon::Lexicon;
+use crate::news::NewsEvent;
 use anyhow::Result;
-use chrono::{Duration, Utc};
-use std::collections::HashMap;
+use serde::Serialize;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Forward windows (days after the tweet) that price changes are measured over
+pub const LOOKAHEAD_DAYS: [i64; 2] = [1, 3];
+
+/// Default forward window (days) of the engagement vs absolute move correlation
+pub const ENGAGEMENT_WINDOW_DAYS: i64 = 1;
+
+/// A positive tweet counts as a "rise" when the price gains more than this (%)
+pub const RISE_THRESHOLD_PCT: f64 = 3.0;
+
+/// Trading days averaged for the "normal" volume baseline before a tweet
+pub const VOLUME_BASELINE_DAYS: usize = 20;
+
+/// Minimum trading days of history needed before a volume baseline is trusted
+const MIN_VOLUME_BASELINE_DAYS: usize = 5;
+
+/// Next-day volume at or above this multiple of the baseline counts as a spike
+pub const VOLUME_SPIKE_RATIO: f64 = 1.5;
+
+/// Trading days of returns used to estimate the volatility regime before a tweet
+pub const VOLATILITY_BASELINE_DAYS: usize = 20;
+
+/// Weights of the surprise score components (sentiment, price z, volume, engagement)
+pub const SURPRISE_WEIGHTS: [f64; 4] = [0.25, 0.35, 0.20, 0.20];
+
+/// Default calendar days a performance start price may sit before its target
+/// day (covers a weekend plus a holiday)
+pub const PERFORMANCE_TOLERANCE_DAYS: u32 = 4;
+
+/// |sentiment| from which a calibration bucket counts as "strong"
+///
+/// One keyword hit scores 1/19 ≈ 0.053, so this is three or more net hits.
+pub const STRONG_SENTIMENT: f64 = 0.15;
+
+/// Calibration buckets as (label, lower bound, upper bound), most negative first
+const CALIBRATION_BUCKETS: [(&str, f64, f64); 5] = [
+    ("strong negative", -1.0, -STRONG_SENTIMENT),
+    ("negative", -STRONG_SENTIMENT, 0.0),
+    ("neutral", 0.0, 0.0),
+    ("positive", 0.0, STRONG_SENTIMENT),
+    ("strong positive", STRONG_SENTIMENT, 1.0),
+];
+
+/// Trading days of pre-tweet trend checked by the causal score's momentum factor
+pub const MOMENTUM_DAYS: usize = 5;
+
+/// A day with at least this multiple of the baseline daily tweet rate is an activity spike
+pub const ACTIVITY_SPIKE_RATIO: f64 = 3.0;
+
+/// Exchange timezone and regular session hours (US listings)
+const MARKET_TIMEZONE: Tz = chrono_tz::America::New_York;
+const MARKET_OPEN: (u32, u32) = (9, 30);
+const MARKET_CLOSE: (u32, u32) = (16, 0);
+
+/// Normal quantile for the 95% Wilson score interval around success rates
+const WILSON_Z: f64 = 1.96;
+
+/// Two-sided normal critical value for correlation significance at the 5% level
+const Z_CRITICAL_95_TWO_SIDED: f64 = 1.96;
+
+/// Variance below this share of the squared mean (or of 1, near zero) counts
+/// as none, so float noise in a flat series doesn't yield a correlation
+const MIN_RELATIVE_VARIANCE: f64 = 1e-12;
+
+/// Smaller side of a mixed tweet's hits must be at least this share of the larger
+const MIXED_MIN_BALANCE: f64 = 0.5;
+
+/// Tunable settings for an analysis run
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// Daily price used for tweet impact windows and period performance
+    pub price_basis: PriceBasis,
+    
+    /// Simple or log returns for tweet impacts and period performance
+    pub return_type: ReturnType,
+    
+    /// How many calendar days a forward price may sit from its target day when
+    /// the target itself has no price (0 = exact days only)
+    pub price_tolerance_days: u32,
+    
+    /// How many calendar days before its target day a 1w/1m/3m performance
+    /// start price may be (None = `PERFORMANCE_TOLERANCE_DAYS`)
+    pub performance_tolerance_days: Option<u32>,
+    
+    /// Whether repeated tweet text is collapsed into a single impact
+    pub repeat_matching: RepeatMatching,
+    
+    /// Which tweet keeps the forward changes when several fall inside one
+    /// lookahead window
+    pub attribution: AttributionMode,
+    
+    /// Forward windows (days) for the rise success matrix; empty = `LOOKAHEAD_DAYS`
+    pub rise_windows: Vec<i64>,
+    
+    /// Rise thresholds (%) for the success matrix; empty = `RISE_THRESHOLD_PCT`
+    pub rise_thresholds: Vec<f64>,
+    
+    /// Known news events; impacts whose window overlaps one get `news_nearby`
+    pub news_events: Vec<NewsEvent>,
+    
+    /// Leave news-adjacent tweets out of the sentiment/price correlations
+    pub exclude_news_from_correlation: bool,
+    
+    /// Leave mixed-signal tweets out of the sentiment/price correlations
+    pub exclude_mixed_from_correlation: bool,
+    
+    /// Leave tweets with |sentiment| <= `neutral_band` out of the
+    /// sentiment/price correlations
+    pub exclude_neutral_from_correlation: bool,
+    
+    /// Largest |sentiment| still counted as neutral (0 = exactly zero only)
+    pub neutral_band: f64,
+    
+    /// Forward window (days) engagement is correlated with the absolute move
+    /// over; None = `ENGAGEMENT_WINDOW_DAYS`
+    pub engagement_window: Option<i64>,
+    
+    /// Drop tweets with fewer words than this before analysis (0 = keep all)
+    pub min_words: usize,
+    
+    /// Drop tweets posted more than this many days before now, however the
+    /// fetch paginated (None = keep all)
+    pub max_tweet_age_days: Option<u32>,
+    
+    /// Shares outstanding, for each tweet's 1-day market cap change in USD
+    /// (None = report percent moves only)
+    pub shares_outstanding: Option<u64>,
+    
+    /// Weights of the causal score factors
+    pub causal_weights: CausalWeights,
+    
+    /// Statistics computed for each lookahead window; empty = `PearsonStatistic`
+    pub statistics: Vec<Arc<dyn Statistic>>,
+    
+    /// Lexicon tweets are scored with (the CEO's domain lexicon, or the built-in one)
+    pub lexicon: Arc<Lexicon>,
+}
+
+/// A sentiment-vs-price statistic the analysis computes per window
+///
+/// Implement this to add e.g. Kendall's tau without touching the pipeline;
+/// results land in `AnalysisResult::statistics` as "<name>_<window>d".
+pub trait Statistic: std::fmt::Debug + Send + Sync {
+    /// Short key prefix, e.g. "pearson"
+    fn name(&self) -> &str;
+    
+    /// Value over `impacts`, where `change` gives an impact's price change for
+    /// the window (None = leave the impact out); None when undefined
+    fn compute(&self, impacts: &[TweetImpact], change: &dyn Fn(&TweetImpact) -> Option<f64>) -> Option<f64>;
+}
+
+/// Pearson correlation between sentiment and the window's change
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PearsonStatistic;
+
+impl Statistic for PearsonStatistic {
+    fn name(&self) -> &str {
+        "pearson"
+    }
+    
+    fn compute(&self, impacts: &[TweetImpact], change: &dyn Fn(&TweetImpact) -> Option<f64>) -> Option<f64> {
+        calculate_correlation(impacts, change)
+    }
+}
+
+/// Weights of the factors in a tweet's causal score
+///
+/// The score is the weighted mean of the factors that have data, on a 0-100
+/// scale; missing factors drop out of both the sum and the total weight.
+/// Defaults favor how abnormal the move is, then whether it matches the
+/// sentiment, with the confounder checks sharing the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CausalWeights {
+    pub abnormal_move: f64,
+    pub direction: f64,
+    pub no_momentum: f64,
+    pub no_news: f64,
+    pub volume: f64,
+}
+
+impl Default for CausalWeights {
+    fn default() -> Self {
+        Self { abnormal_move: 0.35, direction: 0.20, no_momentum: 0.15, no_news: 0.15, volume: 0.15 }
+    }
+}
+
+impl CausalWeights {
+    /// Parse weights given in field order (abnormal_move, direction,
+    /// no_momentum, no_news, volume)
+    pub fn from_list(values: &[f64]) -> Result<Self> {
+        let [abnormal_move, direction, no_momentum, no_news, volume] = values else {
+            anyhow::bail!("Causal weights need 5 values (move,direction,momentum,news,volume), got {}", values.len());
+        };
+        if values.iter().any(|w| !w.is_finite() || *w < 0.0) || values.iter().sum::<f64>() <= 0.0 {
+            anyhow::bail!("Causal weights must be non-negative with a positive total");
+        }
+        Ok(Self {
+            abnormal_move: *abnormal_move,
+            direction: *direction,
+            no_momentum: *no_momentum,
+            no_news: *no_news,
+            volume: *volume,
+        })
+    }
+    
+    /// Each available factor's share of the score, in score points
+    ///
+    /// The contributions sum to the causal score. None when no weighted factor
+    /// has data.
+    pub fn contributions(&self, components: &CausalComponents) -> Option<CausalComponents> {
+        let parts = [
+            (components.abnormal_move, self.abnormal_move),
+            (components.direction, self.direction),
+            (components.no_momentum, self.no_momentum),
+            (components.no_news, self.no_news),
+            (components.volume, self.volume),
+        ];
+        let total_weight: f64 = parts.iter().filter(|(c, _)| c.is_some()).map(|(_, w)| w).sum();
+        if total_weight <= 0.0 {
+            return None;
+        }
+        let points = |(component, weight): (Option<f64>, f64)| component.map(|c| c * weight / total_weight * 100.0);
+        Some(CausalComponents {
+            abnormal_move: points(parts[0]),
+            direction: points(parts[1]),
+            no_momentum: points(parts[2]),
+            no_news: points(parts[3]),
+            volume: points(parts[4]),
+        })
+    }
+    
+    /// 0-100 causal score for the components, see `contributions`
+    pub fn score(&self, components: &CausalComponents) -> Option<f64> {
+        let c = self.contributions(components)?;
+        Some([c.abnormal_move, c.direction, c.no_momentum, c.no_news, c.volume].iter().flatten().sum())
+    }
+}
+
+impl AnalysisOptions {
+    /// Configured performance tolerance, or the default
+    pub fn performance_tolerance_days(&self) -> u32 {
+        self.performance_tolerance_days.unwrap_or(PERFORMANCE_TOLERANCE_DAYS)
+    }
+    
+    /// Configured statistics, or the built-in Pearson correlation
+    pub fn statistics(&self) -> Vec<Arc<dyn Statistic>> {
+        if self.statistics.is_empty() {
+            vec![Arc::new(PearsonStatistic)]
+        } else {
+            self.statistics.clone()
+        }
+    }
+    
+    /// Configured rise windows, or the default lookahead windows
+    pub fn rise_windows(&self) -> Vec<i64> {
+        if self.rise_windows.is_empty() {
+            LOOKAHEAD_DAYS.to_vec()
+        } else {
+            self.rise_windows.clone()
+        }
+    }
+    
+    /// Configured engagement window, or the default
+    pub fn engagement_window(&self) -> i64 {
+        self.engagement_window.unwrap_or(ENGAGEMENT_WINDOW_DAYS)
+    }
+    
+    /// Configured rise thresholds, or the default threshold
+    pub fn rise_thresholds(&self) -> Vec<f64> {
+        if self.rise_thresholds.is_empty() {
+            vec![RISE_THRESHOLD_PCT]
+        } else {
+            self.rise_thresholds.clone()
+        }
+    }
+}
 
 /// Perform complete analysis of tweets and stock prices
+///
+/// When no tweets are given, or the options filter out every one, the result
+/// is a `NoData` placeholder saying why instead of an all-empty analysis.
 pub fn analyze(
     ceo_handle: &str,
     ticker: &str,
     mut tweets: Vec<Tweet>,
     prices: Vec<PricePoint>,
+    options: &AnalysisOptions,
     verbose: bool,
 ) -> Result<AnalysisResult> {
     if verbose {
         println!("  → Calculating sentiment for {} tweets...", tweets.len());
     }
     
+    if tweets.is_empty() {
+        return Ok(no_data_result(ceo_handle, ticker, "no tweets in the fetched window", &options.lexicon));
+    }
+    
+    let mut tweets_past_max_age = 0;
+    if let Some(max_age) = options.max_tweet_age_days {
+        let cutoff = Utc::now() - Duration::days(max_age as i64);
+        let before = tweets.len();
+        tweets.retain(|t| t.created_at >= cutoff);
+        tweets_past_max_age = before - tweets.len();
+        if verbose {
+            println!("  → Dropped {} tweets older than {} days", tweets_past_max_age, max_age);
+        }
+        if tweets.is_empty() {
+            let reason = format!("all {} tweets older than --max-tweet-age-days {}", before, max_age);
+            return Ok(no_data_result(ceo_handle, ticker, &reason, &options.lexicon));
+        }
+    }
+    
+    if options.min_words > 0 {
+        let before = tweets.len();
+        tweets.retain(|t| t.word_count() >= options.min_words);
+        if verbose {
+            println!("  → Dropped {} tweets under {} words", before - tweets.len(), options.min_words);
+        }
+        if tweets.is_empty() {
+            let reason = format!("all {} tweets filtered by --min-words {}", before, options.min_words);
+            return Ok(no_data_result(ceo_handle, ticker, &reason, &options.lexicon));
+        }
+    }
+    
     // Step 1: Calculate sentiment for all tweets
     for tweet in &mut tweets {
-        tweet.sentiment = Some(calculate_sentiment(&tweet.text));
+        score_tweet(tweet, &options.lexicon);
     }
     
     if verbose {
@@ -35,280 +355,2544 @@ pub fn analyze(
     // Step 2: Create price lookup map by date
     let price_map = create_price_map(&prices);
     
-    // Step 3: Calculate impacts for each tweet
-    let mut impacts = Vec::new();
-    let mut tweets_with_data = 0;
+    let (tweets, repeat_counts) = collapse_repeats(tweets, options.repeat_matching);
+    if verbose && tweets.len() < repeat_counts.iter().sum::<u32>() as usize {
+        println!("  → Collapsed repeated tweets into {} unique posts", tweets.len());
+    }
     
-    for tweet in &tweets {
-        let impact = calculate_tweet_impact(tweet, &price_map);
-        
-        if impact.price_at_tweet.is_some() {
-            tweets_with_data += 1;
-        }
-        
-        impacts.push(impact);
+    // Step 3: Calculate impacts for each tweet (each tweet moves into its impact)
+    let start_date = tweets.iter().map(|t| t.created_at).min().unwrap_or(Utc::now());
+    let end_date = tweets.iter().map(|t| t.created_at).max().unwrap_or(Utc::now());
+    let mut impacts: Vec<TweetImpact> = tweets
+        .into_iter()
+        .zip(repeat_counts)
+        .map(|(tweet, repeat_count)| {
+            let tweet_day = tweet.created_at.date_naive();
+            let mut impact = calculate_tweet_impact(tweet, &price_map, options);
+            impact.repeat_count = repeat_count;
+            impact.volume_ratio_1d = calculate_volume_ratio(tweet_day, &prices);
+            impact.range_ratio_1d = calculate_range_ratio(tweet_day, &prices);
+            impact.news_nearby = has_news_in_window(tweet_day, &options.news_events);
+            score_surprise(&mut impact, &prices, options.price_basis);
+            impact.market_cap_change_1d = options.shares_outstanding
+                .and_then(|shares| market_cap_change(&impact, shares, options.return_type));
+            impact
+        })
+        .collect();
+    
+    let suppressed = suppress_overlapping_attribution(&mut impacts, options.attribution);
+    if verbose && suppressed > 0 {
+        println!("  → Cleared forward changes of {} tweets inside an earlier tweet's window", suppressed);
     }
     
     if verbose {
-        println!("  → Calculating correlations...");
+        report_coverage(&impacts);
     }
     
-    // Step 4: Calculate correlations
-    let correlation_1d = calculate_correlation(&impacts, |i| i.change_1d);
-    let correlation_3d = calculate_correlation(&impacts, |i| i.change_3d);
-    
-    // Step 5: Calculate positive tweet success rates
-    let (pos_rise_1d, pos_rise_3d) = calculate_positive_tweet_stats(&impacts);
-
-    // Step 6: Calculate tweet counts
-    let positive_tweets = tweets.iter().filter(|t| t.sentiment.unwrap_or(0.0) > 0.0).count();
-    let negative_tweets = tweets.iter().filter(|t| t.sentiment.unwrap_or(0.0) < 0.0).count();
-    let neutral_tweets = tweets.iter().filter(|t| t.sentiment.unwrap_or(0.0) == 0.0).count();
-    
-    // Step 7: Calculate stock performance
-    let performance_1w = calculate_period_performance(&prices, 7);
-    let performance_1m = calculate_period_performance(&prices, 30);
-    let performance_3m = calculate_period_performance(&prices, 90);
-    
-    // Step 8: Build result
-    let start_date = tweets.iter().map(|t| t.created_at).min().unwrap_or(Utc::now());
-    let end_date = tweets.iter().map(|t| t.created_at).max().unwrap_or(Utc::now());
-    
+    // Step 4: Build result
     let mut result = AnalysisResult::new(
         ceo_handle.to_string(),
         ticker.to_string(),
         start_date,
         end_date,
     );
-    
     result.impacts = impacts;
-    result.correlation_1d = correlation_1d;
-    result.correlation_3d = correlation_3d;
-    result.positive_tweets_with_rise_1d = pos_rise_1d;
-    result.positive_tweets_with_rise_3d = pos_rise_3d;
-    result.performance_1w = performance_1w;
-    result.performance_1m = performance_1m;
-    result.performance_3m = performance_3m;
-    result.positive_tweets = positive_tweets;
-    result.negative_tweets = negative_tweets;
-    result.neutral_tweets = neutral_tweets;
-    result.total_tweets = tweets.len();
-    result.tweets_with_price_data = tweets_with_data;
+    stamp_lexicon(&mut result, &options.lexicon);
+    result.tweets_past_max_age = tweets_past_max_age;
+    
+    if verbose {
+        println!("  → Calculating correlations...");
+    }
+    
+    // Step 5: Correlations, success rates, counts and performance
+    recompute_statistics(&mut result, &prices, options);
     
     Ok(result)
 }
 
-/// Calculate stock performance over a specific period of days
-fn calculate_period_performance(prices: &[PricePoint], days: i64) -> Option<f64> {
-    if prices.is_empty() {
-        return None;
-    }
+/// Whether news, mixed signals or a stale price stretch keep an impact out
+/// of the sentiment/price correlations (neutral tweets are handled apart)
+fn excluded_from_correlation(impact: &TweetImpact, options: &AnalysisOptions, stale_ranges: &[(NaiveDate, NaiveDate)]) -> bool {
+    (options.exclude_news_from_correlation && impact.news_nearby)
+        || (options.exclude_mixed_from_correlation && impact.tweet.mixed)
+        || in_ranges(impact.tweet.created_at.date_naive(), stale_ranges)
+}
 
-    // Find latest price (end of period)
-    let latest = prices.iter().max_by_key(|p| p.date)?;
-    
-    // Target date in the past
-    let target_date = latest.date - Duration::days(days);
+/// Clear the forward changes of tweets that share a lookahead window with
+/// the tweet credited for the move, returning how many were cleared
+///
+/// Tweets are clustered in posting order: a cluster runs from its first tweet
+/// to the end of that tweet's longest lookahead (calendar days). The first or
+/// most-engaged tweet of each cluster keeps its changes; the rest are marked
+/// `attribution_suppressed` and drop out of correlations and success rates.
+fn suppress_overlapping_attribution(impacts: &mut [TweetImpact], mode: AttributionMode) -> usize {
+    if mode == AttributionMode::All {
+        return 0;
+    }
+    let horizon = LOOKAHEAD_DAYS.iter().copied().max().unwrap_or(0);
+    let mut order: Vec<usize> = (0..impacts.len()).collect();
+    order.sort_by_key(|&i| impacts[i].tweet.created_at);
     
-    // Find closest price at or before target date
-    // We want the price roughly 'days' ago. If exact date missing, use closest previous.
-    let past_price = prices.iter()
-        .filter(|p| p.date <= target_date)
-        .max_by_key(|p| p.date);
+    let mut suppressed = 0;
+    let mut start = 0;
+    while start < order.len() {
+        let window_end = impacts[order[start]].tweet.created_at.date_naive() + Duration::days(horizon);
+        let len = order[start..]
+            .iter()
+            .take_while(|&&i| impacts[i].tweet.created_at.date_naive() <= window_end)
+            .count();
+        let cluster = &order[start..start + len];
+        let credited = match mode {
+            AttributionMode::MostEngaged => cluster
+                .iter()
+                .copied()
+                .rev()
+                .max_by_key(|&i| u64::from(impacts[i].tweet.retweet_count) + u64::from(impacts[i].tweet.like_count))
+                .unwrap_or(cluster[0]),
+            _ => cluster[0],
+        };
+        for &i in cluster.iter().filter(|&&i| i != credited) {
+            let impact = &mut impacts[i];
+            impact.change_0d = None;
+            impact.change_1d = None;
+            impact.change_3d = None;
+            impact.market_cap_change_1d = None;
+            impact.attribution_suppressed = true;
+            suppressed += 1;
+        }
+        start += len;
+    }
+    suppressed
+}
 
-    match past_price {
-        Some(past) => {
-            if past.close == 0.0 {
-                None
-            } else {
-                Some(((latest.close - past.close) / past.close) * 100.0)
-            }
-        },
-        None => None // Not enough data history
+/// Dollar market cap change over an impact's 1-day window
+///
+/// The 1-day move applied to the tweet-day price, times the share count.
+/// None without a tweet-day price or a 1-day change.
+pub fn market_cap_change(impact: &TweetImpact, shares_outstanding: u64, return_type: ReturnType) -> Option<f64> {
+    let price = impact.price_at_tweet?;
+    let change = impact.change_1d?;
+    Some(price * return_type.fraction(change) * shares_outstanding as f64)
+}
+
+/// Placeholder result for a run with nothing to analyze
+fn no_data_result(ceo_handle: &str, ticker: &str, reason: &str, lexicon: &Lexicon) -> AnalysisResult {
+    let now = Utc::now();
+    let mut result = AnalysisResult::new(ceo_handle.to_string(), ticker.to_string(), now, now);
+    result.status = ResultStatus::NoData;
+    result.status_reason = Some(reason.to_string());
+    stamp_lexicon(&mut result, lexicon);
+    result
+}
+
+/// Print how many tweets lack forward price windows, grouped by reason
+fn report_coverage(impacts: &[TweetImpact]) {
+    let mut counts: Vec<(CoverageReason, usize)> = Vec::new();
+    for impact in impacts.iter().filter(|i| i.coverage_reason != CoverageReason::FullCoverage) {
+        match counts.iter_mut().find(|(reason, _)| *reason == impact.coverage_reason) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((impact.coverage_reason, 1)),
+        }
+    }
+    for (reason, count) in counts {
+        println!("  → {} tweets with incomplete price windows: {}", count, reason.describe());
     }
 }
 
-/// Calculate sentiment score for tweet text using keyword-based approach
+/// Recompute every aggregate statistic from the result's impacts and prices
 ///
-/// Returns a score between -1.0 (very negative) and 1.0 (very positive)
-fn calculate_sentiment(text: &str) -> f64 {
-    let text_lower = text.to_lowercase();
+/// Called at the end of `analyze` and again whenever stored impacts change
+/// (e.g. after replaying forward prices), so the headline numbers always
+/// match the impact detail.
+pub fn recompute_statistics(result: &mut AnalysisResult, prices: &[PricePoint], options: &AnalysisOptions) {
+    let activity_spikes = flag_activity_spikes(&mut result.impacts);
+    standardize_impacts(&mut result.impacts, prices, options.price_basis);
+    score_causality(&mut result.impacts, prices, options);
     
-    // Simple keyword lists (can be expanded)
-    let positive_words = [
-        "great", "excellent", "amazing", "good", "success", "win", "winning",
-        "growth", "profit", "record", "best", "excited", "love", "fantastic",
-        "incredible", "revolutionary", "breakthrough", "proud", "happy",
-    ];
-    
-    let negative_words = [
-        "bad", "terrible", "awful", "poor", "loss", "losing", "fail", "failure",
-        "worst", "sad", "disappointed", "concern", "problem", "issue", "difficult",
-        "challenge", "unfortunate", "regret", "sorry",
-    ];
-    
-    let mut score = 0.0;
-    
-    for word in &positive_words {
-        if text_lower.contains(word) {
-            score += 1.0;
+    // Correlations (optionally without tweets that coincide with news), never
+    // over stretches where the feed repeated one close
+    let stale_ranges = stale_price_ranges(prices);
+    for impact in &mut result.impacts {
+        impact.excluded_from_correlation = excluded_from_correlation(impact, options, &stale_ranges);
+    }
+    // Neutral tweets go last, so the 1d correlation can also be reported with them
+    let mut correlation_1d_with_neutral = None;
+    let mut neutral_excluded = 0;
+    if options.exclude_neutral_from_correlation {
+        let with_neutral = sentiment_change_pairs(&result.impacts, |i| i.change_1d.filter(|_| !i.excluded_from_correlation));
+        correlation_1d_with_neutral = pearson(&with_neutral);
+        for impact in result.impacts.iter_mut().filter(|i| !i.excluded_from_correlation) {
+            if impact.tweet.sentiment.unwrap_or(0.0).abs() <= options.neutral_band {
+                impact.excluded_from_correlation = true;
+                neutral_excluded += 1;
+            }
         }
     }
+    let impacts = &result.impacts;
+    let include = |i: &TweetImpact| !i.excluded_from_correlation;
+    let pairs_1d = sentiment_change_pairs(impacts, |i| i.change_1d.filter(|_| include(i)));
+    let correlation_1d = pearson(&pairs_1d);
+    let correlation_3d = calculate_correlation(impacts, |i| i.change_3d.filter(|_| include(i)));
+    let correlation_0d = calculate_correlation(impacts, |i| i.change_0d.filter(|_| include(i)));
+    let regression_1d = linear_regression(&pairs_1d);
+    // Why the correlations and fits above are None, when a series is flat
+    let warnings: Vec<String> = degenerate_inputs(&pairs_1d).into_iter().map(str::to_string).collect();
+    let sentiment_trend = sentiment_trend_slope(impacts);
+    let streaks = sentiment_streaks(impacts);
+    
+    // Positive tweet success rates, per configured window and threshold
+    let price_map = create_price_map(prices);
+    let change_over = |impact: &TweetImpact, window: i64| {
+        window_change(impact, window, &price_map, options)
+    };
+    let windows = options.rise_windows();
+    let thresholds = options.rise_thresholds();
+    let rise_counts = |window: i64, threshold: f64| {
+        positive_rise_counts(impacts, |i| change_over(i, window), threshold)
+    };
+    let counts: Vec<Vec<(usize, usize)>> = windows
+        .iter()
+        .map(|&w| thresholds.iter().map(|&t| rise_counts(w, t)).collect())
+        .collect();
+    let rise_matrix = RiseMatrix {
+        rates: counts.iter().map(|row| row.iter().map(|&c| success_rate(c)).collect()).collect(),
+        intervals: counts.iter().map(|row| row.iter().map(|&c| wilson_interval(c)).collect()).collect(),
+        windows,
+        thresholds_pct: thresholds,
+    };
+    let rise_1d = rise_counts(LOOKAHEAD_DAYS[0], RISE_THRESHOLD_PCT);
+    let rise_3d = rise_counts(LOOKAHEAD_DAYS[1], RISE_THRESHOLD_PCT);
     
-    for word in &negative_words {
-        if text_lower.contains(word) {
-            score -= 1.0;
+    // Pluggable statistics, per lookahead window
+    let mut statistics = BTreeMap::new();
+    for statistic in options.statistics() {
+        for window in LOOKAHEAD_DAYS {
+            let change = |i: &TweetImpact| change_over(i, window).filter(|_| include(i));
+            // Non-finite values would be written as null and break reading the result back
+            if let Some(value) = statistic.compute(impacts, &change).filter(|v| v.is_finite()) {
+                statistics.insert(format!("{}_{}d", statistic.name(), window), value);
+            }
         }
     }
     
-    // Normalize to [-1, 1] range
-    let max_score = positive_words.len().max(negative_words.len()) as f64;
-    if max_score > 0.0 {
-        score = score / max_score;
-    }
+    // Tweet counts
+    let sentiments = || impacts.iter().map(|i| i.tweet.sentiment.unwrap_or(0.0));
+    let positive_tweets = sentiments().filter(|s| *s > 0.0).count();
+    let negative_tweets = sentiments().filter(|s| *s < 0.0).count();
+    let neutral_tweets = sentiments().filter(|s| *s == 0.0).count();
+    let tweets_with_data = impacts.iter().filter(|i| i.price_at_tweet.is_some()).count();
+    let tweets_near_news = impacts.iter().filter(|i| i.news_nearby).count();
+    let (tweets_older_than_prices, tweets_newer_than_prices) = outside_price_range(impacts, prices);
+    let mixed_tweets = impacts.iter().filter(|i| i.tweet.mixed).count();
+    let attribution_suppressed = impacts.iter().filter(|i| i.attribution_suppressed).count();
+    let repeated_tweets_collapsed = impacts.iter().map(|i| i.repeat_count.saturating_sub(1) as usize).sum();
     
-    score.clamp(-1.0, 1.0)
-}
-
-/// Create a hashmap of prices indexed by date (YYYY-MM-DD)
-fn create_price_map(prices: &[PricePoint]) -> HashMap<String, &PricePoint> {
-    prices
+    // Attention: does tweet intensity line up with abnormal next-day volume?
+    let volume_pairs: Vec<(f64, f64)> = impacts
         .iter()
-        .map(|p| (p.date.format("%Y-%m-%d").to_string(), p))
-        .collect()
-}
-
-/// Calculate the impact of a single tweet on stock prices
-fn calculate_tweet_impact(tweet: &Tweet, price_map: &HashMap<String, &PricePoint>) -> TweetImpact {
-    let tweet_date = tweet.created_at.format("%Y-%m-%d").to_string();
+        .filter_map(|i| Some((i.tweet.sentiment?.abs(), i.volume_ratio_1d?)))
+        .collect();
+    let volume_correlation = pearson(&volume_pairs);
     
-    // Get price at tweet date
-    let price_at_tweet = price_map.get(&tweet_date).map(|p| p.close);
+    // Volatility: does tweet intensity widen the next day's trading range?
+    let range_pairs: Vec<(f64, f64)> = impacts
+        .iter()
+        .filter_map(|i| Some((i.tweet.sentiment?.abs(), i.range_ratio_1d?)))
+        .collect();
+    let volatility_impact = pearson(&range_pairs);
     
-    // Calculate 1-day change
-    let date_1d = (tweet.created_at + Duration::days(1)).format("%Y-%m-%d").to_string();
-    let change_1d = if let (Some(base_price), Some(future_price)) = 
-        (price_map.get(&tweet_date), price_map.get(&date_1d)) {
-        Some(((future_price.close - base_price.close) / base_price.close) * 100.0)
-    } else {
+    // Do longer tweets move the stock more?
+    let length_pairs: Vec<(f64, f64)> = impacts
+        .iter()
+        .filter_map(|i| Some((i.tweet.word_count() as f64, i.change_1d?)))
+        .collect();
+    let length_correlation_1d = pearson(&length_pairs);
+    
+    // Virality: do the tweets people notice move the stock, whichever way?
+    let engagement_window = options.engagement_window();
+    let engagement_pairs: Vec<(f64, f64)> = impacts
+        .iter()
+        .filter_map(|i| {
+            let engagement = i.tweet.like_count as f64 + i.tweet.retweet_count as f64;
+            Some((engagement.ln_1p(), change_over(i, engagement_window)?.abs()))
+        })
+        .collect();
+    let engagement_move_correlation = pearson(&engagement_pairs);
+    let volume_spike_rate = if volume_pairs.is_empty() {
         None
+    } else {
+        let spikes = volume_pairs.iter().filter(|(_, ratio)| *ratio >= VOLUME_SPIKE_RATIO).count();
+        Some(spikes as f64 / volume_pairs.len() as f64 * 100.0)
+    };
+    
+    // Do busy posting days line up with bigger moves?
+    let mean_move = |spike_day: bool| {
+        let moves: Vec<f64> = impacts.iter()
+            .filter(|i| i.high_activity_day == spike_day)
+            .filter_map(|i| i.change_1d.map(f64::abs))
+            .collect();
+        (!moves.is_empty()).then(|| moves.iter().sum::<f64>() / moves.len() as f64)
+    };
+    let activity_spike_move_ratio = match (mean_move(true), mean_move(false)) {
+        (Some(spike), Some(normal)) if normal > 0.0 => Some(spike / normal),
+        _ => None,
+    };
+    
+    // Do tweets the market can't react to until the open produce bigger gaps?
+    let mean_gap = |off_hours: bool| {
+        let gaps: Vec<f64> = impacts.iter()
+            .filter(|i| (market_session(i.tweet.created_at) != MarketSession::Regular) == off_hours)
+            .filter_map(|i| reaction_gap(i, prices).map(f64::abs))
+            .collect();
+        (!gaps.is_empty()).then(|| gaps.iter().sum::<f64>() / gaps.len() as f64)
+    };
+    let off_hours_gap_ratio = match (mean_gap(true), mean_gap(false)) {
+        (Some(off), Some(regular)) if regular > 0.0 => Some(off / regular),
+        _ => None,
     };
     
-    // Calculate 3-day change
-    let date_3d = (tweet.created_at + Duration::days(3)).format("%Y-%m-%d").to_string();
-    let change_3d = if let (Some(base_price), Some(future_price)) = 
-        (price_map.get(&tweet_date), price_map.get(&date_3d)) {
-        Some(((future_price.close - base_price.close) / base_price.close) * 100.0)
+    result.correlation_1d = correlation_1d;
+    result.correlation_3d = correlation_3d;
+    result.correlation_0d = correlation_0d;
+    result.correlation_1d_with_neutral = correlation_1d_with_neutral;
+    result.correlations = correlation_details(&result.impacts);
+    result.neutral_excluded_from_correlation = neutral_excluded;
+    result.slope_1d = regression_1d.map(|r| r.slope);
+    result.intercept_1d = regression_1d.map(|r| r.intercept);
+    result.r_squared_1d = regression_1d.map(|r| r.r_squared);
+    result.sentiment_trend_slope = sentiment_trend;
+    result.attribution_suppressed = attribution_suppressed;
+    result.longest_positive_streak = streaks.longest_positive;
+    result.longest_negative_streak = streaks.longest_negative;
+    result.streak_reversal_rate = streaks.reversal_rate;
+    result.warnings = warnings;
+    result.stale_price_ranges = stale_ranges;
+    result.positive_tweets_with_rise_1d = success_rate(rise_1d);
+    result.positive_tweets_with_rise_3d = success_rate(rise_3d);
+    result.rise_1d_interval = wilson_interval(rise_1d);
+    result.rise_3d_interval = wilson_interval(rise_3d);
+    result.rise_matrix = Some(rise_matrix);
+    result.calibration = sentiment_calibration(impacts);
+    result.daily_timeseries = daily_timeseries(impacts, prices);
+    result.statistics = statistics;
+    result.positive_tweets = positive_tweets;
+    result.negative_tweets = negative_tweets;
+    result.neutral_tweets = neutral_tweets;
+    result.total_tweets = result.impacts.len();
+    result.tweets_with_price_data = tweets_with_data;
+    result.tweets_near_news = tweets_near_news;
+    result.tweets_older_than_prices = tweets_older_than_prices;
+    result.tweets_newer_than_prices = tweets_newer_than_prices;
+    result.mixed_tweets = mixed_tweets;
+    result.repeated_tweets_collapsed = repeated_tweets_collapsed;
+    result.volume_correlation = volume_correlation;
+    result.volatility_impact = volatility_impact;
+    result.length_correlation_1d = length_correlation_1d;
+    result.engagement_move_correlation = engagement_move_correlation;
+    result.engagement_window_days = Some(engagement_window);
+    result.volume_spike_rate = volume_spike_rate;
+    result.activity_spikes = activity_spikes;
+    result.activity_spike_move_ratio = activity_spike_move_ratio;
+    result.off_hours_gap_ratio = off_hours_gap_ratio;
+    
+    // Stock performance
+    let mut notes = Vec::new();
+    let mut performance = |label: &str, days: i64| {
+        calculate_period_performance(prices, days, options)
+            .map_err(|reason| notes.push(format!("{}: {}", label, reason)))
+            .ok()
+    };
+    result.performance_1w = performance("1w", 7);
+    result.performance_1m = performance("1m", 30);
+    result.performance_3m = performance("3m", 90);
+    result.performance_notes = notes;
+    result.last_close = prices.iter().max_by_key(|p| p.date).map(|p| p.close);
+}
+
+/// Fill in each impact's move_z, sentiment_z and cross-stock impact index
+///
+/// Standardization, so indexes from different CEOs/stocks share one scale:
+/// - move_z = change_1d / sample std dev of the 20 daily % returns before the
+///   tweet (`VOLATILITY_BASELINE_DAYS`, same price basis); None without
+///   enough history or a 1-day change
+/// - sentiment_z = (sentiment - mean) / sample std dev over this result's
+///   tweets; 0 when every tweet has the same sentiment
+/// - impact_index = (|move_z| + |sentiment_z|) / 2; None when move_z is None
+pub fn standardize_impacts(impacts: &mut [TweetImpact], prices: &[PricePoint], basis: PriceBasis) {
+    let sentiments: Vec<f64> = impacts.iter().filter_map(|i| i.tweet.sentiment).collect();
+    let mean = sentiments.iter().sum::<f64>() / sentiments.len().max(1) as f64;
+    let std_dev = if sentiments.len() > 1 {
+        (sentiments.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (sentiments.len() - 1) as f64).sqrt()
     } else {
-        None
+        0.0
     };
     
-    TweetImpact {
-        tweet: tweet.clone(),
-        price_at_tweet,
-        change_1d,
-        change_3d,
-        is_impactful: false, // Will be set by Prolog rules
+    for impact in impacts.iter_mut() {
+        impact.sentiment_z = impact.tweet.sentiment.map(|s| if std_dev > 0.0 { (s - mean) / std_dev } else { 0.0 });
+        impact.move_z = impact.change_1d
+            .zip(calculate_return_volatility(impact.tweet.created_at.date_naive(), prices, basis))
+            .map(|(change, vol)| change / vol);
+        impact.impact_index = impact.move_z
+            .map(|z| (z.abs() + impact.sentiment_z.unwrap_or(0.0).abs()) / 2.0);
     }
 }
 
-/// Calculate Pearson correlation between sentiment and price changes
-fn calculate_correlation<F>(impacts: &[TweetImpact], get_change: F) -> Option<f64>
-where
-    F: Fn(&TweetImpact) -> Option<f64>,
-{
-    // Collect pairs of (sentiment, price_change) where both are available
-    let pairs: Vec<(f64, f64)> = impacts
+/// Bucket tweets by sentiment and average what the stock did after each bucket
+///
+/// Exactly 0 is neutral and |sentiment| ≥ `STRONG_SENTIMENT` is strong.
+/// Tweets without a sentiment are left out.
+pub fn sentiment_calibration(impacts: &[TweetImpact]) -> Vec<CalibrationBucket> {
+    let mean = |values: Vec<f64>| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    let mut assigned = vec![Vec::new(); CALIBRATION_BUCKETS.len()];
+    for impact in impacts {
+        let bucket = match impact.tweet.sentiment {
+            None => continue,
+            Some(s) if s <= -STRONG_SENTIMENT => 0,
+            Some(s) if s < 0.0 => 1,
+            Some(0.0) => 2,
+            Some(s) if s < STRONG_SENTIMENT => 3,
+            Some(_) => 4,
+        };
+        assigned[bucket].push(impact);
+    }
+    
+    CALIBRATION_BUCKETS
         .iter()
-        .filter_map(|impact| {
-            let sentiment = impact.tweet.sentiment?;
-            let change = get_change(impact)?;
-            Some((sentiment, change))
+        .zip(assigned)
+        .map(|((label, min, max), members)| {
+            let changes_1d: Vec<f64> = members.iter().filter_map(|i| i.change_1d).collect();
+            let ups = changes_1d.iter().filter(|c| **c > 0.0).count();
+            CalibrationBucket {
+                label: label.to_string(),
+                min_sentiment: *min,
+                max_sentiment: *max,
+                tweets: members.len(),
+                tweets_with_change: changes_1d.len(),
+                up_share_1d: (!changes_1d.is_empty()).then(|| ups as f64 / changes_1d.len() as f64 * 100.0),
+                avg_change_1d: mean(changes_1d),
+                avg_change_3d: mean(members.iter().filter_map(|i| i.change_3d).collect()),
+            }
         })
+        .collect()
+}
+
+/// Fill in each impact's causal components and score
+///
+/// Needs `move_z` from `standardize_impacts`. Impacts without a 1-day change
+/// have nothing to explain and get no score. There is no market benchmark,
+/// so a sector- or market-wide move isn't discounted.
+fn score_causality(impacts: &mut [TweetImpact], prices: &[PricePoint], options: &AnalysisOptions) {
+    for impact in impacts.iter_mut() {
+        let Some(change) = impact.change_1d else {
+            impact.causal_components = CausalComponents::default();
+            impact.causal_score = None;
+            continue;
+        };
+        let tweet_date = impact.tweet.created_at.date_naive();
+        let sentiment = impact.tweet.sentiment.filter(|s| *s != 0.0);
+        
+        impact.causal_components = CausalComponents {
+            abnormal_move: impact.move_z.map(|z| (z.abs() / 3.0).min(1.0)),
+            direction: sentiment
+                .filter(|_| change != 0.0)
+                .map(|s| if (s > 0.0) == (change > 0.0) { 1.0 } else { 0.0 }),
+            no_momentum: pre_tweet_trend_z(tweet_date, prices, options.price_basis).map(|trend_z| {
+                if (trend_z > 0.0) == (change > 0.0) {
+                    (1.0 - trend_z.abs() / 3.0).max(0.0)
+                } else {
+                    1.0
+                }
+            }),
+            no_news: (!options.news_events.is_empty()).then_some(if impact.news_nearby { 0.0 } else { 1.0 }),
+            volume: impact.volume_ratio_1d.map(|r| ((r - 1.0) / 2.0).clamp(0.0, 1.0)),
+        };
+        impact.causal_score = options.causal_weights.score(&impact.causal_components);
+    }
+}
+
+/// Return over the `MOMENTUM_DAYS` trading days before the tweet, in units
+/// of the volatility expected over that many days
+fn pre_tweet_trend_z(tweet_date: NaiveDate, prices: &[PricePoint], basis: PriceBasis) -> Option<f64> {
+    let mut history: Vec<&PricePoint> = prices
+        .iter()
+        .filter(|p| p.date.date_naive() < tweet_date)
         .collect();
-    
-    if pairs.len() < 2 {
+    history.sort_by_key(|p| p.date);
+    if history.len() <= MOMENTUM_DAYS {
         return None;
     }
     
-    // Calculate means
-    let n = pairs.len() as f64;
-    let mean_x: f64 = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
-    let mean_y: f64 = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
-    
-    // Calculate correlation
-    let mut numerator = 0.0;
-    let mut sum_sq_x = 0.0;
-    let mut sum_sq_y = 0.0;
-    
-    for (x, y) in &pairs {
-        let dx = x - mean_x;
-        let dy = y - mean_y;
-        numerator += dx * dy;
-        sum_sq_x += dx * dx;
-        sum_sq_y += dy * dy;
+    let start = history[history.len() - 1 - MOMENTUM_DAYS].price(basis);
+    let end = history[history.len() - 1].price(basis);
+    let vol = calculate_return_volatility(tweet_date, prices, basis)?;
+    (start > 0.0).then(|| (end - start) / start * 100.0 / (vol * (MOMENTUM_DAYS as f64).sqrt()))
+}
+
+/// Find days with unusually many posts and flag the impacts posted on them
+///
+/// The baseline is the mean daily post count over every calendar day from the
+/// first to the last tweet (quiet days count as zero). Repeats collapsed into
+/// one impact still count as separate posts. Returns the spike days in order.
+fn flag_activity_spikes(impacts: &mut [TweetImpact]) -> Vec<(NaiveDate, usize)> {
+    let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for impact in impacts.iter() {
+        *per_day.entry(impact.tweet.created_at.date_naive()).or_default() += impact.repeat_count.max(1) as usize;
     }
     
-    let denominator = (sum_sq_x * sum_sq_y).sqrt();
+    let (Some(first), Some(last)) = (per_day.keys().next(), per_day.keys().next_back()) else {
+        return Vec::new();
+    };
+    let span_days = (*last - *first).num_days() + 1;
+    let baseline = per_day.values().sum::<usize>() as f64 / span_days as f64;
     
-    if denominator == 0.0 {
-        return None;
+    let spikes: Vec<(NaiveDate, usize)> = per_day
+        .into_iter()
+        .filter(|(_, count)| *count > 1 && *count as f64 >= baseline * ACTIVITY_SPIKE_RATIO)
+        .collect();
+    
+    for impact in impacts.iter_mut() {
+        let day = impact.tweet.created_at.date_naive();
+        impact.high_activity_day = spikes.iter().any(|(spike_day, _)| *spike_day == day);
     }
     
-    Some(numerator / denominator)
+    spikes
 }
 
-/// Calculate percentage of positive tweets followed by >3% rise
-fn calculate_positive_tweet_stats(impacts: &[TweetImpact]) -> (f64, f64) {
-    let positive_tweets: Vec<_> = impacts
-        .iter()
-        .filter(|i| i.tweet.sentiment.unwrap_or(0.0) > 0.0)
-        .collect();
+/// Fill in forward price changes that weren't available when a result was stored
+///
+/// Each stored impact is re-aligned (by its tweet's date) against a fresh
+/// price history. Only missing values are filled; existing ones are kept,
+/// and tweets whose changes were credited to another tweet stay empty.
+/// Returns how many impacts gained data, after recomputing the statistics
+/// (unless impacts were trimmed, in which case the stored ones are kept).
+pub fn replay_forward_prices(
+    result: &mut AnalysisResult,
+    prices: &[PricePoint],
+    options: &AnalysisOptions,
+) -> usize {
+    let price_map = create_price_map(prices);
+    let mut updated = 0;
     
-    if positive_tweets.is_empty() {
-        return (0.0, 0.0);
+    for impact in &mut result.impacts {
+        if impact.attribution_suppressed {
+            continue;
+        }
+        let exact_1d = impact.change_1d.is_some() && !impact.change_1d_approximated;
+        let exact_3d = impact.change_3d.is_some() && !impact.change_3d_approximated;
+        if exact_1d && exact_3d {
+            continue;
+        }
+        
+        let fresh = calculate_tweet_impact(impact.tweet.clone(), &price_map, options);
+        let before = (impact.price_at_tweet, impact.change_1d, impact.change_3d);
+        
+        impact.price_at_tweet = impact.price_at_tweet.or(fresh.price_at_tweet);
+        impact.change_0d = impact.change_0d.or(fresh.change_0d);
+        impact.gap_1d = impact.gap_1d.or(fresh.gap_1d);
+        // Missing values are filled; approximated ones are replaced once the exact day exists
+        if !exact_1d && fresh.change_1d.is_some() && (impact.change_1d.is_none() || !fresh.change_1d_approximated) {
+            impact.change_1d = fresh.change_1d;
+            impact.change_1d_approximated = fresh.change_1d_approximated;
+        }
+        if !exact_3d && fresh.change_3d.is_some() && (impact.change_3d.is_none() || !fresh.change_3d_approximated) {
+            impact.change_3d = fresh.change_3d;
+            impact.change_3d_approximated = fresh.change_3d_approximated;
+        }
+        if (impact.price_at_tweet, impact.change_1d) != (before.0, before.1) {
+            impact.market_cap_change_1d = options.shares_outstanding
+                .and_then(|shares| market_cap_change(impact, shares, options.return_type));
+        }
+        impact.coverage_reason = if impact.change_1d.is_some() && impact.change_3d.is_some() {
+            CoverageReason::FullCoverage
+        } else {
+            fresh.coverage_reason
+        };
+        impact.volume_ratio_1d = impact.volume_ratio_1d
+            .or_else(|| calculate_volume_ratio(impact.tweet.created_at.date_naive(), prices));
+        impact.range_ratio_1d = impact.range_ratio_1d
+            .or_else(|| calculate_range_ratio(impact.tweet.created_at.date_naive(), prices));
+        score_surprise(impact, prices, options.price_basis);
+        
+        if before != (impact.price_at_tweet, impact.change_1d, impact.change_3d) {
+            updated += 1;
+        }
     }
     
-    let count_1d = positive_tweets
-        .iter()
-        .filter(|i| i.change_1d.unwrap_or(0.0) > 3.0)
-        .count();
-    
-    let count_3d = positive_tweets
-        .iter()
-        .filter(|i| i.change_3d.unwrap_or(0.0) > 3.0)
-        .count();
-    
-    let total = positive_tweets.len() as f64;
-    
-    (
-        (count_1d as f64 / total) * 100.0,
-        (count_3d as f64 / total) * 100.0,
-    )
+    // Statistics of a trimmed result cover tweets no longer stored; keep them
+    if result.impacts_trimmed == 0 {
+        recompute_statistics(result, prices, options);
+    }
+    updated
 }
 
-#[cfg(test)]
-mod tests {
+/// Re-score stored tweets with `options.lexicon`, keeping their price changes
+///
+/// Sentiment, hit counts and the mixed flag are recomputed for every impact,
+/// then surprise scores and the statistics are re-derived against `prices`
+/// (the stored raw fetch, so nothing is refetched). Apply the Prolog rules
+/// afterwards, since impact levels depend on sentiment. Returns how many
+/// tweets changed score.
+pub fn rescore_sentiment(result: &mut AnalysisResult, prices: &[PricePoint], options: &AnalysisOptions) -> usize {
+    let mut changed = 0;
+    for impact in &mut result.impacts {
+        let before = impact.tweet.sentiment;
+        score_tweet(&mut impact.tweet, &options.lexicon);
+        if impact.tweet.sentiment != before {
+            changed += 1;
+        }
+        score_surprise(impact, prices, options.price_basis);
+    }
+    
+    recompute_statistics(result, prices, options);
+    stamp_lexicon(result, &options.lexicon);
+    if let Some(config) = &mut result.run_config {
+        config.lexicon_hash = options.lexicon.hash();
+    }
+    changed
+}
+
+/// Re-score a stored result from its raw fetch and re-apply the Prolog rules
+///
+/// Untrimmed results keep their price changes (see `rescore_sentiment`).
+/// A result stored with trimmed impacts can't have its statistics
+/// recomputed from the impacts left, so it is analyzed again from `raw`,
+/// keeping its follower count and run settings, and trimmed back to the
+/// same number of impacts. Returns how many stored tweets changed score.
+pub fn rescore_stored(
+    result: &mut AnalysisResult,
+    raw: RawFetch,
+    options: &AnalysisOptions,
+    facts_filter: &crate::prolog::FactsFilter,
+) -> Result<usize> {
+    if result.impacts_trimmed == 0 {
+        let changed = rescore_sentiment(result, &raw.prices, options);
+        crate::prolog::apply_rules(result, None, facts_filter)?;
+        return Ok(changed);
+    }
+    
+    let kept = result.impacts.len();
+    let before: HashMap<String, Option<f64>> =
+        result.impacts.iter().map(|i| (i.tweet.id.clone(), i.tweet.sentiment)).collect();
+    let mut fresh = analyze(&result.ceo_handle, &result.ticker, raw.tweets, raw.prices, options, false)?;
+    fresh.follower_count = result.follower_count.or(raw.follower_count);
+    fresh.run_config = result.run_config.take().map(|mut config| {
+        config.lexicon_hash = options.lexicon.hash();
+        config
+    });
+    crate::prolog::apply_rules(&mut fresh, None, facts_filter)?;
+    fresh.trim_impacts(kept);
+    
+    let changed = fresh.impacts.iter()
+        .filter(|i| before.get(&i.tweet.id).is_some_and(|s| *s != i.tweet.sentiment))
+        .count();
+    *result = fresh;
+    Ok(changed)
+}
+
+/// Calculate stock performance over a specific period of days
+///
+/// The start price is the closest one at or before `days` before the latest
+/// price, and must be within `options.performance_tolerance_days()` of that
+/// target; otherwise the change would cover a longer period than its label.
+/// The error explains why no value could be given.
+fn calculate_period_performance(
+    prices: &[PricePoint],
+    days: i64,
+    options: &AnalysisOptions,
+) -> std::result::Result<f64, String> {
+    // Find latest price (end of period)
+    let latest = prices.iter().max_by_key(|p| p.date).ok_or("no prices")?;
+    
+    // Target date in the past
+    let target_date = latest.date - Duration::days(days);
+    
+    // Closest price at or before the target date
+    let past = prices.iter()
+        .filter(|p| p.date <= target_date)
+        .max_by_key(|p| p.date)
+        .ok_or_else(|| format!("price history starts after {}", target_date.format("%Y-%m-%d")))?;
+    
+    let gap_days = (target_date.date_naive() - past.date.date_naive()).num_days();
+    let tolerance = options.performance_tolerance_days();
+    if gap_days > i64::from(tolerance) {
+        return Err(format!(
+            "nearest earlier price ({}) is {} days before {}, more than the {}-day tolerance",
+            past.date.format("%Y-%m-%d"),
+            gap_days,
+            target_date.format("%Y-%m-%d"),
+            tolerance
+        ));
+    }
+    
+    let basis = options.price_basis;
+    options.return_type
+        .change(past.price(basis), latest.price(basis))
+        .ok_or_else(|| format!("non-positive price on {}", past.date.format("%Y-%m-%d")))
+}
+
+/// Score a tweet's sentiment and record its lexicon hits
+fn score_tweet(tweet: &mut Tweet, lexicon: &Lexicon) {
+    tweet.sentiment = Some(lexicon.score(&tweet.text));
+    let (pos_hits, neg_hits) = lexicon.hits(&tweet.text);
+    tweet.pos_hits = pos_hits;
+    tweet.neg_hits = neg_hits;
+    tweet.mixed = is_mixed(pos_hits, neg_hits);
+}
+
+/// Record which lexicon scored `result`'s tweets
+fn stamp_lexicon(result: &mut AnalysisResult, lexicon: &Lexicon) {
+    result.lexicon = Some(lexicon.name.clone());
+    result.lexicon_hash = Some(lexicon.hash());
+}
+
+/// Whether both sides have hits and neither dominates the other
+fn is_mixed(pos_hits: u32, neg_hits: u32) -> bool {
+    let (low, high) = (pos_hits.min(neg_hits), pos_hits.max(neg_hits));
+    low > 0 && low as f64 >= high as f64 * MIXED_MIN_BALANCE
+}
+
+/// Whether `result` was scored with a different lexicon than `lexicon`
+///
+/// Results saved before the hash was recorded count as unknown, not stale.
+pub fn scored_with_other_lexicon(result: &AnalysisResult, lexicon: &Lexicon) -> bool {
+    result.lexicon_hash.as_deref().is_some_and(|hash| hash != lexicon.hash())
+}
+
+/// Snapshot of the settings that produced a result, for reproducibility
+///
+/// `tweet_source` and `stock_provider` describe where the data came from;
+/// everything else is read from the options and module constants in effect.
+pub fn run_config(
+    options: &AnalysisOptions,
+    days: u32,
+    price_buffer_days: u32,
+    tweet_source: &str,
+    stock_provider: &str,
+) -> RunConfig {
+    RunConfig {
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: Utc::now(),
+        days,
+        price_buffer_days,
+        price_basis: options.price_basis,
+        return_type: options.return_type,
+        price_tolerance_days: options.price_tolerance_days,
+        performance_tolerance_days: options.performance_tolerance_days(),
+        repeat_matching: options.repeat_matching,
+        attribution: options.attribution,
+        min_words: options.min_words,
+        exclude_news_from_correlation: options.exclude_news_from_correlation,
+        exclude_mixed_from_correlation: options.exclude_mixed_from_correlation,
+        max_tweet_age_days: options.max_tweet_age_days,
+        shares_outstanding: options.shares_outstanding,
+        lookahead_days: LOOKAHEAD_DAYS.to_vec(),
+        rise_threshold_pct: RISE_THRESHOLD_PCT,
+        rise_windows: options.rise_windows(),
+        rise_thresholds_pct: options.rise_thresholds(),
+        impact_sentiment_threshold: crate::prolog::IMPACT_SENTIMENT_THRESHOLD,
+        impact_move_threshold_pct: crate::prolog::IMPACT_MOVE_THRESHOLD,
+        lexicon_hash: options.lexicon.hash(),
+        tweet_source: tweet_source.to_string(),
+        stock_provider: stock_provider.to_string(),
+    }
+}
+
+/// Options that reproduce the settings a stored result was made with
+///
+/// Read from its `run_config` and engagement window; results without a
+/// config get the defaults. Settings the config doesn't record (news events,
+/// causal weights, statistics) keep their defaults, and news-adjacent impacts
+/// keep the `news_nearby` flags they were stored with.
+pub fn stored_options(result: &AnalysisResult, lexicon: Arc<Lexicon>) -> AnalysisOptions {
+    let defaults = AnalysisOptions {
+        engagement_window: result.engagement_window_days,
+        lexicon,
+        ..Default::default()
+    };
+    let Some(config) = &result.run_config else {
+        return defaults;
+    };
+    AnalysisOptions {
+        price_basis: config.price_basis,
+        return_type: config.return_type,
+        price_tolerance_days: config.price_tolerance_days,
+        performance_tolerance_days: Some(config.performance_tolerance_days),
+        repeat_matching: config.repeat_matching,
+        attribution: config.attribution,
+        rise_windows: config.rise_windows.clone(),
+        rise_thresholds: config.rise_thresholds_pct.clone(),
+        exclude_news_from_correlation: config.exclude_news_from_correlation,
+        exclude_mixed_from_correlation: config.exclude_mixed_from_correlation,
+        min_words: config.min_words,
+        max_tweet_age_days: config.max_tweet_age_days,
+        shares_outstanding: config.shares_outstanding,
+        ..defaults
+    }
+}
+
+/// Minimum keyword/remote score gap for a tweet to land in the review queue
+pub const DISAGREEMENT_THRESHOLD: f64 = 0.5;
+
+/// Record how far the keyword score is from a remote model's score for each tweet
+///
+/// `remote_scores` maps tweet id to the remote backend's score in [-1, 1];
+/// send the backend `preprocess_tweet_text(text, TextOptions::SENTIMENT)`
+/// so both scorers see the same words (`remote_sentiment::fetch_scores` does).
+/// Tweets the backend didn't score keep `sentiment_disagreement = None`.
+pub fn apply_remote_sentiment(impacts: &mut [TweetImpact], remote_scores: &HashMap<String, f64>) {
+    for impact in impacts {
+        if let (Some(keyword), Some(remote)) =
+            (impact.tweet.sentiment, remote_scores.get(&impact.tweet.id)) {
+            impact.tweet.sentiment_disagreement = Some((keyword - remote).abs());
+        }
+    }
+}
+
+/// Tweets where the keyword and remote scorers disagree strongly, worst first
+///
+/// These are the likely lexicon failures (sarcasm, slang) worth a manual look.
+pub fn review_queue(impacts: &[TweetImpact], limit: usize) -> Vec<&TweetImpact> {
+    let mut queue: Vec<_> = impacts
+        .iter()
+        .filter(|i| i.tweet.sentiment_disagreement.unwrap_or(0.0) >= DISAGREEMENT_THRESHOLD)
+        .collect();
+    
+    queue.sort_by(|a, b| {
+        let a_gap = a.tweet.sentiment_disagreement.unwrap_or(0.0);
+        let b_gap = b.tweet.sentiment_disagreement.unwrap_or(0.0);
+        b_gap.partial_cmp(&a_gap).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    
+    queue.truncate(limit);
+    queue
+}
+
+/// Fold repeated posts of the same text into their earliest occurrence
+///
+/// Returns the surviving tweets (in input order) with how many posts each
+/// stands for. Likes and retweets of the repeats are added to the survivor.
+fn collapse_repeats(tweets: Vec<Tweet>, matching: RepeatMatching) -> (Vec<Tweet>, Vec<u32>) {
+    let key = |tweet: &Tweet| match matching {
+        RepeatMatching::Off => None,
+        RepeatMatching::Exact => Some(tweet.text.clone()),
+        RepeatMatching::Normalized => Some(normalize_for_repeats(&tweet.text)),
+    };
+    
+    let mut kept: Vec<Tweet> = Vec::with_capacity(tweets.len());
+    let mut counts: Vec<u32> = Vec::with_capacity(tweets.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    
+    for tweet in tweets {
+        let Some(text_key) = key(&tweet).filter(|k| !k.is_empty()) else {
+            kept.push(tweet);
+            counts.push(1);
+            continue;
+        };
+        
+        match seen.get(&text_key) {
+            Some(&idx) => {
+                let survivor = &mut kept[idx];
+                survivor.like_count = survivor.like_count.saturating_add(tweet.like_count);
+                survivor.retweet_count = survivor.retweet_count.saturating_add(tweet.retweet_count);
+                if tweet.created_at < survivor.created_at {
+                    survivor.id = tweet.id;
+                    survivor.created_at = tweet.created_at;
+                }
+                counts[idx] += 1;
+            }
+            None => {
+                seen.insert(text_key, kept.len());
+                kept.push(tweet);
+                counts.push(1);
+            }
+        }
+    }
+    
+    (kept, counts)
+}
+
+/// Lowercased text without links and with whitespace collapsed
+fn normalize_for_repeats(text: &str) -> String {
+    preprocess_tweet_text(text, TextOptions::REPEATS)
+}
+
+/// Create a hashmap of prices indexed by date (YYYY-MM-DD)
+fn create_price_map(prices: &[PricePoint]) -> HashMap<String, &PricePoint> {
+    prices
+        .iter()
+        .map(|p| (p.date.format("%Y-%m-%d").to_string(), p))
+        .collect()
+}
+
+/// Calculate the impact of a single tweet on stock prices
+fn calculate_tweet_impact(
+    tweet: Tweet,
+    price_map: &HashMap<String, &PricePoint>,
+    options: &AnalysisOptions,
+) -> TweetImpact {
+    let tweet_date = tweet.created_at.format("%Y-%m-%d").to_string();
+    
+    // Get price at tweet date
+    let price_at_tweet = price_map.get(&tweet_date).map(|p| p.price(options.price_basis));
+    
+    // Same-day reaction, open to close, whatever the price basis
+    let change_0d = price_map
+        .get(&tweet_date)
+        .and_then(|p| options.return_type.change(p.open, p.close));
+    
+    // Calculate 1-day and 3-day changes
+    let change_1d = forward_change(&tweet, 1, price_map, options);
+    let change_3d = forward_change(&tweet, 3, price_map, options);
+    
+    let tweet_day = tweet.created_at.date_naive();
+    let gap_1d = price_map.get(&tweet_date).and_then(|base| {
+        let (next, _) = nearest_price(price_map, tweet_day, tweet_day + Duration::days(1), options.price_tolerance_days)?;
+        next.gap_percent(base)
+    });
+    let coverage_reason = coverage_reason(tweet_day, price_map, change_1d.is_some() && change_3d.is_some());
+    
+    // is_impactful is set later by the Prolog rules
+    TweetImpact {
+        price_at_tweet,
+        coverage_reason,
+        change_0d,
+        change_1d: change_1d.map(|(change, _)| change),
+        gap_1d,
+        change_3d: change_3d.map(|(change, _)| change),
+        change_1d_approximated: change_1d.is_some_and(|(_, approx)| approx),
+        change_3d_approximated: change_3d.is_some_and(|(_, approx)| approx),
+        ..TweetImpact::new(tweet)
+    }
+}
+
+/// Classify why a tweet's forward windows are (in)complete
+///
+/// History bounds take precedence over a closed market, so a weekend tweet
+/// before the first fetched price reports `BeforePriceHistory`.
+fn coverage_reason(
+    tweet_day: NaiveDate,
+    price_map: &HashMap<String, &PricePoint>,
+    complete: bool,
+) -> CoverageReason {
+    if complete {
+        return CoverageReason::FullCoverage;
+    }
+    let first = price_map.values().map(|p| p.date.date_naive()).min();
+    let last = price_map.values().map(|p| p.date.date_naive()).max();
+    let (Some(first), Some(last)) = (first, last) else {
+        return CoverageReason::AfterPriceHistory;
+    };
+    
+    if tweet_day < first {
+        CoverageReason::BeforePriceHistory
+    } else if tweet_day + Duration::days(LOOKAHEAD_DAYS.iter().copied().max().unwrap_or(1)) > last {
+        CoverageReason::AfterPriceHistory
+    } else if !price_map.contains_key(&tweet_day.format("%Y-%m-%d").to_string()) {
+        CoverageReason::WeekendBaseline
+    } else {
+        CoverageReason::MissingFuturePrice
+    }
+}
+
+/// Tweets dated before the first / after the last price point, as `(older, newer)`
+///
+/// Without any prices nothing is counted; that case is reported on its own.
+fn outside_price_range(impacts: &[TweetImpact], prices: &[PricePoint]) -> (usize, usize) {
+    let first = prices.iter().map(|p| p.date.date_naive()).min();
+    let last = prices.iter().map(|p| p.date.date_naive()).max();
+    let (Some(first), Some(last)) = (first, last) else {
+        return (0, 0);
+    };
+    
+    let days = || impacts.iter().map(|i| i.tweet.created_at.date_naive());
+    (days().filter(|d| *d < first).count(), days().filter(|d| *d > last).count())
+}
+
+/// Change from the tweet day's price to the price `window` days later
+///
+/// The flag is true when the target day had no price and the nearest day
+/// within `options.price_tolerance_days` was used instead (later days win
+/// ties; earlier days must still be after the tweet day).
+fn forward_change(
+    tweet: &Tweet,
+    window: i64,
+    price_map: &HashMap<String, &PricePoint>,
+    options: &AnalysisOptions,
+) -> Option<(f64, bool)> {
+    let tweet_day = tweet.created_at.date_naive();
+    let base = price_map.get(&tweet_day.format("%Y-%m-%d").to_string())?;
+    let (future, approximated) = nearest_price(price_map, tweet_day, tweet_day + Duration::days(window), options.price_tolerance_days)?;
+    
+    let basis = options.price_basis;
+    let change = options.return_type.change(base.price(basis), future.price(basis))?;
+    Some((change, approximated))
+}
+
+/// Price on `target`, or on the nearest day within `tolerance` days of it
+fn nearest_price<'a>(
+    price_map: &HashMap<String, &'a PricePoint>,
+    tweet_day: NaiveDate,
+    target: NaiveDate,
+    tolerance: u32,
+) -> Option<(&'a PricePoint, bool)> {
+    let lookup = |day: NaiveDate| price_map.get(&day.format("%Y-%m-%d").to_string()).copied();
+    
+    if let Some(exact) = lookup(target) {
+        return Some((exact, false));
+    }
+    
+    (1..=tolerance as i64).find_map(|offset| {
+        let earlier = target - Duration::days(offset);
+        lookup(target + Duration::days(offset))
+            .or_else(|| (earlier > tweet_day).then(|| lookup(earlier)).flatten())
+            .map(|price| (price, true))
+    })
+}
+
+/// Part of the US trading day a tweet was posted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarketSession {
+    PreMarket,
+    Regular,
+    /// After the close, or any time on a weekend
+    AfterHours,
+}
+
+/// Classify `at` against regular session hours in the exchange timezone
+fn market_session(at: DateTime<Utc>) -> MarketSession {
+    let local = at.with_timezone(&MARKET_TIMEZONE);
+    let time = local.time();
+    let at_hm = |(h, m): (u32, u32)| NaiveTime::from_hms_opt(h, m, 0).unwrap_or_default();
+    if matches!(local.weekday(), Weekday::Sat | Weekday::Sun) || time >= at_hm(MARKET_CLOSE) {
+        MarketSession::AfterHours
+    } else if time < at_hm(MARKET_OPEN) {
+        MarketSession::PreMarket
+    } else {
+        MarketSession::Regular
+    }
+}
+
+/// The first opening gap the market could react to a tweet with
+///
+/// Pre-market tweets meet the tweet day's own open (against the previous
+/// session's close); every other tweet meets the next session's, `gap_1d`.
+fn reaction_gap(impact: &TweetImpact, prices: &[PricePoint]) -> Option<f64> {
+    if market_session(impact.tweet.created_at) != MarketSession::PreMarket {
+        return impact.gap_1d;
+    }
+    let day = impact.tweet.created_at.date_naive();
+    let open = prices.iter().find(|p| p.date.date_naive() == day)?;
+    let prev = prices
+        .iter()
+        .filter(|p| p.date.date_naive() < day)
+        .max_by_key(|p| p.date)?;
+    open.gap_percent(prev)
+}
+
+/// Change over any window, preferring the stored 1d/3d values
+///
+/// Window 0 is the tweet day's own open-to-close move.
+fn window_change(
+    impact: &TweetImpact,
+    window: i64,
+    price_map: &HashMap<String, &PricePoint>,
+    options: &AnalysisOptions,
+) -> Option<f64> {
+    if impact.attribution_suppressed {
+        return None;
+    }
+    let stored = match window {
+        0 => return impact.change_0d,
+        1 => impact.change_1d,
+        3 => impact.change_3d,
+        _ => None,
+    };
+    stored.or_else(|| forward_change(&impact.tweet, window, price_map, options).map(|(change, _)| change))
+}
+
+/// Whether any news event falls between the tweet day and the end of the longest lookahead
+fn has_news_in_window(tweet_date: NaiveDate, events: &[NewsEvent]) -> bool {
+    let horizon = LOOKAHEAD_DAYS.iter().copied().max().unwrap_or(0);
+    let window_end = tweet_date + Duration::days(horizon);
+    
+    events.iter().any(|e| e.date >= tweet_date && e.date <= window_end)
+}
+
+/// Next-day volume relative to the average of the trading days before the tweet
+///
+/// Returns None without a next-day bar or with too little history for a baseline.
+fn calculate_volume_ratio(tweet_date: NaiveDate, prices: &[PricePoint]) -> Option<f64> {
+    next_day_ratio(tweet_date, prices, |p| Some(p.volume as f64))
+}
+
+/// Next-day intraday range relative to the average range before the tweet
+///
+/// Range is (high - low) / close; same next-day and baseline rules as
+/// `calculate_volume_ratio`.
+fn calculate_range_ratio(tweet_date: NaiveDate, prices: &[PricePoint]) -> Option<f64> {
+    next_day_ratio(tweet_date, prices, |p| (p.close > 0.0).then(|| (p.high - p.low) / p.close))
+}
+
+/// `metric` on the day after the tweet divided by its average over the
+/// `VOLUME_BASELINE_DAYS` trading days before the tweet
+fn next_day_ratio<F>(tweet_date: NaiveDate, prices: &[PricePoint], metric: F) -> Option<f64>
+where
+    F: Fn(&PricePoint) -> Option<f64>,
+{
+    let next_day = tweet_date + Duration::days(1);
+    let next = metric(prices.iter().find(|p| p.date.date_naive() == next_day)?)?;
+    
+    let mut history: Vec<&PricePoint> = prices
+        .iter()
+        .filter(|p| p.date.date_naive() < tweet_date)
+        .collect();
+    history.sort_by_key(|p| p.date);
+    
+    let baseline = &history[history.len().saturating_sub(VOLUME_BASELINE_DAYS)..];
+    let values: Vec<f64> = baseline.iter().filter_map(|p| metric(p)).collect();
+    if values.len() < MIN_VOLUME_BASELINE_DAYS {
+        return None;
+    }
+    
+    let average = values.iter().sum::<f64>() / values.len() as f64;
+    if average == 0.0 {
+        return None;
+    }
+    
+    Some(next / average)
+}
+
+/// Standard deviation of daily % returns over the trading days before the tweet
+///
+/// This is the "volatility regime" a move is judged against: 3% is routine
+/// for a volatile stock and a shock for a quiet one.
+fn calculate_return_volatility(tweet_date: NaiveDate, prices: &[PricePoint], basis: PriceBasis) -> Option<f64> {
+    let mut history: Vec<&PricePoint> = prices
+        .iter()
+        .filter(|p| p.date.date_naive() < tweet_date)
+        .collect();
+    history.sort_by_key(|p| p.date);
+    
+    let window = &history[history.len().saturating_sub(VOLATILITY_BASELINE_DAYS + 1)..];
+    let returns: Vec<f64> = window
+        .windows(2)
+        .filter(|w| w[0].price(basis) != 0.0)
+        .map(|w| (w[1].price(basis) - w[0].price(basis)) / w[0].price(basis) * 100.0)
+        .collect();
+    if returns.len() < MIN_VOLUME_BASELINE_DAYS {
+        return None;
+    }
+    
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    let std_dev = variance.sqrt();
+    (std_dev > 0.0).then_some(std_dev)
+}
+
+/// Normalized surprise components for a tweet
+///
+/// Each component is scaled to 0-1:
+/// - sentiment: |sentiment|
+/// - price_z: |change_1d| / trailing daily volatility, divided by 3 (3 sigma = 1)
+/// - volume: (volume ratio - 1) / 2, so normal volume = 0 and 3x = 1
+/// - engagement: log10(likes + retweets + 1) / 6, so a million interactions = 1
+pub fn surprise_components(impact: &TweetImpact, prices: &[PricePoint], basis: PriceBasis) -> SurpriseComponents {
+    let tweet_date = impact.tweet.created_at.date_naive();
+    let engagement = impact.tweet.like_count as f64 + impact.tweet.retweet_count as f64;
+    
+    SurpriseComponents {
+        sentiment: impact.tweet.sentiment.map(|s| s.abs().min(1.0)),
+        price_z: impact.change_1d
+            .zip(calculate_return_volatility(tweet_date, prices, basis))
+            .map(|(change, vol)| (change.abs() / vol / 3.0).min(1.0)),
+        volume: impact.volume_ratio_1d.map(|r| ((r - 1.0) / 2.0).clamp(0.0, 1.0)),
+        engagement: Some(((engagement + 1.0).log10() / 6.0).min(1.0)),
+    }
+}
+
+/// Weighted sum of the components on a 0-100 scale; missing components count as 0
+pub fn surprise_score(components: &SurpriseComponents) -> f64 {
+    let parts = [components.sentiment, components.price_z, components.volume, components.engagement];
+    let weighted: f64 = parts
+        .iter()
+        .zip(SURPRISE_WEIGHTS)
+        .map(|(part, weight)| part.unwrap_or(0.0) * weight)
+        .sum();
+    weighted * 100.0
+}
+
+/// Fill in an impact's surprise components and score
+fn score_surprise(impact: &mut TweetImpact, prices: &[PricePoint], basis: PriceBasis) {
+    impact.surprise_components = surprise_components(impact, prices, basis);
+    impact.surprise_score = surprise_score(&impact.surprise_components);
+}
+
+/// Calculate Pearson correlation between sentiment and price changes
+fn calculate_correlation<F>(impacts: &[TweetImpact], get_change: F) -> Option<f64>
+where
+    F: Fn(&TweetImpact) -> Option<f64>,
+{
+    pearson(&sentiment_change_pairs(impacts, get_change))
+}
+
+/// Pairs of (sentiment, price_change) where both are available
+fn sentiment_change_pairs<F>(impacts: &[TweetImpact], get_change: F) -> Vec<(f64, f64)>
+where
+    F: Fn(&TweetImpact) -> Option<f64>,
+{
+    impacts
+        .iter()
+        .filter_map(|impact| {
+            let sentiment = impact.tweet.sentiment?;
+            let change = get_change(impact)?;
+            Some((sentiment, change))
+        })
+        .collect()
+}
+
+/// Least-squares slope of sentiment against time, in sentiment per day
+///
+/// Positive when the CEO's tweets grow more positive over the window. None
+/// with fewer than two scored tweets or when they were all posted at once.
+pub fn sentiment_trend_slope(impacts: &[TweetImpact]) -> Option<f64> {
+    let first = impacts.iter().map(|i| i.tweet.created_at).min()?;
+    let pairs: Vec<(f64, f64)> = impacts
+        .iter()
+        .filter_map(|i| {
+            let days = (i.tweet.created_at - first).num_seconds() as f64 / 86_400.0;
+            Some((days, i.tweet.sentiment?))
+        })
+        .collect();
+    linear_regression(&pairs).map(|r| r.slope)
+}
+
+/// Streaks shorter than this aren't checked for a reversal
+pub const MIN_STREAK_LENGTH: usize = 3;
+
+/// Longest runs of same-sentiment tweets and how streaks end
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SentimentStreaks {
+    pub longest_positive: usize,
+    pub longest_negative: usize,
+    /// Percentage of streaks of `MIN_STREAK_LENGTH`+ tweets whose last tweet
+    /// was followed by a 1-day move against the streak (None without any)
+    pub reversal_rate: Option<f64>,
+}
+
+/// Scan tweets in posting order for runs of consecutive positive or negative
+/// sentiment; neutral and unscored tweets end a run
+pub fn sentiment_streaks(impacts: &[TweetImpact]) -> SentimentStreaks {
+    let mut sorted: Vec<&TweetImpact> = impacts.iter().collect();
+    sorted.sort_by_key(|i| i.tweet.created_at);
+    let sign = |i: &TweetImpact| match i.tweet.sentiment {
+        Some(s) if s > 0.0 => 1,
+        Some(s) if s < 0.0 => -1,
+        _ => 0,
+    };
+    
+    let mut streaks = SentimentStreaks::default();
+    let (mut ended, mut reversed) = (0, 0);
+    let mut start = 0;
+    for end in 1..=sorted.len() {
+        let direction = sign(sorted[start]);
+        if end < sorted.len() && sign(sorted[end]) == direction {
+            continue;
+        }
+        let length = end - start;
+        match direction {
+            1 => streaks.longest_positive = streaks.longest_positive.max(length),
+            -1 => streaks.longest_negative = streaks.longest_negative.max(length),
+            _ => {}
+        }
+        if direction != 0 && length >= MIN_STREAK_LENGTH {
+            if let Some(change) = sorted[end - 1].change_1d {
+                ended += 1;
+                if change * f64::from(direction) < 0.0 {
+                    reversed += 1;
+                }
+            }
+        }
+        start = end;
+    }
+    streaks.reversal_rate = (ended > 0).then(|| reversed as f64 / ended as f64 * 100.0);
+    streaks
+}
+
+/// Ordinary least-squares fit of y on x
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+}
+
+/// Fit y = intercept + slope * x over (x, y) pairs
+///
+/// Returns None under the same conditions as `pearson`.
+pub fn linear_regression(pairs: &[(f64, f64)]) -> Option<Regression> {
+    let mut acc = PearsonAccumulator::default();
+    for (x, y) in pairs {
+        acc.push(*x, *y);
+    }
+    acc.regression()
+}
+
+/// Whether a Pearson correlation over `n` pairs is significant at the 5% level
+///
+/// Uses the Fisher z-transform, z = atanh(r) * sqrt(n - 3), against the
+/// two-sided normal critical value. Needs at least four pairs.
+pub fn correlation_is_significant(r: f64, n: usize) -> bool {
+    if n < 4 || !r.is_finite() {
+        return false;
+    }
+    let z = r.clamp(-0.999_999, 0.999_999).atanh() * ((n - 3) as f64).sqrt();
+    z.abs() > Z_CRITICAL_95_TWO_SIDED
+}
+
+/// Pearson correlation coefficient of (x, y) pairs
+///
+/// Returns None for fewer than two pairs or when either side has no variance.
+pub fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let mut acc = PearsonAccumulator::default();
+    for (x, y) in pairs {
+        acc.push(*x, *y);
+    }
+    acc.correlation()
+}
+
+/// Consecutive identical closes after which a price series is treated as
+/// stale (a halted or delisted stock keeps reporting its last close)
+pub const STALE_PRICE_RUN: usize = 5;
+
+/// First and last day of every run of at least `STALE_PRICE_RUN` trading
+/// days with exactly the same close
+pub fn stale_price_ranges(prices: &[PricePoint]) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut sorted: Vec<&PricePoint> = prices.iter().collect();
+    sorted.sort_by_key(|p| p.date);
+    
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for end in 1..=sorted.len() {
+        if end < sorted.len() && sorted[end].close == sorted[start].close {
+            continue;
+        }
+        if end - start >= STALE_PRICE_RUN {
+            ranges.push((sorted[start].date.date_naive(), sorted[end - 1].date.date_naive()));
+        }
+        start = end;
+    }
+    ranges
+}
+
+/// Whether `date` falls inside any of the inclusive `ranges`
+fn in_ranges(date: NaiveDate, ranges: &[(NaiveDate, NaiveDate)]) -> bool {
+    ranges.iter().any(|(first, last)| (*first..=*last).contains(&date))
+}
+
+/// Explanation when (sentiment, change) pairs can't support a correlation or fit
+///
+/// None when both sides vary or there are too few pairs to tell.
+pub fn degenerate_inputs(pairs: &[(f64, f64)]) -> Option<&'static str> {
+    let mut acc = PearsonAccumulator::default();
+    for (x, y) in pairs {
+        acc.push(*x, *y);
+    }
+    if acc.n < 2 {
+        None
+    } else if !acc.y_has_variance() {
+        Some("price series has no variance over the window")
+    } else if !acc.x_has_variance() {
+        Some("tweet sentiment has no variance (every scored tweet has the same score)")
+    } else {
+        None
+    }
+}
+
+/// Whether a series with this second moment and mean actually varies
+fn has_variance(m2: f64, mean: f64, n: usize) -> bool {
+    m2 > MIN_RELATIVE_VARIANCE * n as f64 * mean.abs().max(1.0).powi(2)
+}
+
+/// Running Pearson correlation over a stream of (x, y) pairs
+///
+/// Keeps Welford-style running means and co-moments, so pairs can be fed in
+/// one at a time without holding them all in memory. The result equals the
+/// two-pass computation over all pairs.
+#[derive(Debug, Clone, Default)]
+pub struct PearsonAccumulator {
+    n: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    co_moment: f64,
+}
+
+impl PearsonAccumulator {
+    /// Add one pair
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        let n = self.n as f64;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / n;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.co_moment += dx * (y - self.mean_y);
+    }
+    
+    /// Whether the x values seen so far vary (beyond float noise)
+    pub fn x_has_variance(&self) -> bool {
+        has_variance(self.m2_x, self.mean_x, self.n)
+    }
+    
+    /// Whether the y values seen so far vary (beyond float noise)
+    pub fn y_has_variance(&self) -> bool {
+        has_variance(self.m2_y, self.mean_y, self.n)
+    }
+    
+    /// Correlation so far (None for fewer than two pairs or zero variance)
+    pub fn correlation(&self) -> Option<f64> {
+        if self.n < 2 || !self.x_has_variance() || !self.y_has_variance() {
+            return None;
+        }
+        
+        Some(self.co_moment / (self.m2_x * self.m2_y).sqrt())
+    }
+    
+    /// Least-squares line so far (None when `correlation` is None)
+    pub fn regression(&self) -> Option<Regression> {
+        let r = self.correlation()?;
+        let slope = self.co_moment / self.m2_x;
+        Some(Regression {
+            slope,
+            intercept: self.mean_y - slope * self.mean_x,
+            r_squared: r * r,
+        })
+    }
+}
+
+/// Average tweet sentiment per calendar day for one result
+fn daily_average_sentiment(impacts: &[TweetImpact]) -> BTreeMap<NaiveDate, f64> {
+    let mut sums: BTreeMap<NaiveDate, (f64, usize)> = BTreeMap::new();
+    
+    for impact in impacts {
+        if let Some(sentiment) = impact.tweet.sentiment {
+            let entry = sums.entry(impact.tweet.created_at.date_naive()).or_insert((0.0, 0));
+            entry.0 += sentiment;
+            entry.1 += 1;
+        }
+    }
+    
+    sums.into_iter()
+        .map(|(date, (sum, count))| (date, sum / count as f64))
+        .collect()
+}
+
+/// The (sentiment, change) pairs behind `correlation_0d`, `_1d` and `_3d`,
+/// window by window in tweet order
+///
+/// Same filtering as the correlations: tweets without a score or a change
+/// for the window, and tweets marked `excluded_from_correlation`, are left
+/// out. Impacts trimmed from a stored result can't be listed.
+pub fn correlation_pairs(impacts: &[TweetImpact]) -> Vec<CorrelationPair> {
+    let mut sorted: Vec<&TweetImpact> = impacts.iter().filter(|i| !i.excluded_from_correlation).collect();
+    sorted.sort_by_key(|i| i.tweet.created_at);
+    
+    [0, 1, 3]
+        .into_iter()
+        .flat_map(|window_days| {
+            sorted.iter().filter_map(move |impact| {
+                Some(CorrelationPair {
+                    window_days,
+                    tweet_id: impact.tweet.id.clone(),
+                    date: impact.tweet.created_at.date_naive(),
+                    sentiment: impact.tweet.sentiment?,
+                    change: stored_change(impact, window_days)?,
+                })
+            })
+        })
+        .collect()
+}
+
+/// An impact's stored change for a correlation window (0, 1 or 3 days)
+fn stored_change(impact: &TweetImpact, window_days: i64) -> Option<f64> {
+    match window_days {
+        0 => impact.change_0d,
+        1 => impact.change_1d,
+        _ => impact.change_3d,
+    }
+}
+
+/// `correlation_0d`, `_1d` and `_3d` with their sample sizes, and why each
+/// missing one couldn't be computed
+pub fn correlation_details(impacts: &[TweetImpact]) -> Vec<CorrelationDetail> {
+    [0, 1, 3]
+        .into_iter()
+        .map(|window_days| {
+            let pairs = sentiment_change_pairs(impacts, |i| {
+                stored_change(i, window_days).filter(|_| !i.excluded_from_correlation)
+            });
+            let value = pearson(&pairs);
+            let reason = value.is_none().then(|| unavailable_reason(impacts, window_days, &pairs));
+            CorrelationDetail { window_days, value, n: pairs.len(), reason }
+        })
+        .collect()
+}
+
+/// Why the correlation over `pairs` (one window's inputs) is undefined
+fn unavailable_reason(impacts: &[TweetImpact], window_days: i64, pairs: &[(f64, f64)]) -> String {
+    if pairs.len() >= 2 {
+        return degenerate_inputs(pairs).unwrap_or("correlation is undefined").to_string();
+    }
+    if impacts.is_empty() {
+        return "no tweets".to_string();
+    }
+    if impacts.iter().all(|i| i.price_at_tweet.is_none()) {
+        return "no price data for any tweet".to_string();
+    }
+    let window = if window_days == 0 { "same-day move".to_string() } else { format!("{}d price change", window_days) };
+    let excluded = impacts
+        .iter()
+        .filter(|i| i.excluded_from_correlation && i.tweet.sentiment.is_some() && stored_change(i, window_days).is_some())
+        .count();
+    let mut reason = format!("only {} tweet{} with a {}", pairs.len(), if pairs.len() == 1 { "" } else { "s" }, window);
+    if excluded > 0 {
+        reason.push_str(&format!(" ({} more excluded from correlation)", excluded));
+    }
+    reason
+}
+
+/// One point per calendar day, prices and tweets left-joined by date
+///
+/// Runs from the first tweet's day to the later of the last tweet and the
+/// last price, so forward moves after the final tweet are included. Days
+/// without trading or without tweets keep None / 0 rather than being skipped.
+pub fn daily_timeseries(impacts: &[TweetImpact], prices: &[PricePoint]) -> Vec<DailyPoint> {
+    let Some(first) = impacts.iter().map(|i| i.tweet.created_at.date_naive()).min() else {
+        return Vec::new();
+    };
+    let last = impacts.iter().map(|i| i.tweet.created_at.date_naive())
+        .chain(prices.iter().map(|p| p.date.date_naive()))
+        .max()
+        .unwrap_or(first);
+    
+    let closes: HashMap<NaiveDate, f64> = prices.iter().map(|p| (p.date.date_naive(), p.close)).collect();
+    let sentiment = daily_average_sentiment(impacts);
+    let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+    for impact in impacts {
+        *counts.entry(impact.tweet.created_at.date_naive()).or_default() += 1;
+    }
+    
+    first.iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| DailyPoint {
+            date,
+            close: closes.get(&date).copied(),
+            avg_sentiment: sentiment.get(&date).copied(),
+            tweet_count: counts.get(&date).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Correlate daily average sentiment between every pair of CEOs
+///
+/// Each pair is aligned on the dates both CEOs tweeted, so uneven coverage
+/// only shrinks the sample instead of inventing zero-sentiment days. Results
+/// stored with trimmed impacts use their daily time series, which still
+/// covers every tweet.
+pub fn sentiment_correlation_matrix(results: &[AnalysisResult]) -> SentimentMatrix {
+    let series: Vec<BTreeMap<NaiveDate, f64>> = results
+        .iter()
+        .map(|r| {
+            if r.impacts_trimmed > 0 && !r.daily_timeseries.is_empty() {
+                r.daily_timeseries.iter().filter_map(|p| Some((p.date, p.avg_sentiment?))).collect()
+            } else {
+                daily_average_sentiment(&r.impacts)
+            }
+        })
+        .collect();
+    let n = results.len();
+    
+    let mut matrix = vec![vec![None; n]; n];
+    let mut common_days = vec![vec![0; n]; n];
+    
+    for i in 0..n {
+        for j in i..n {
+            let pairs: Vec<(f64, f64)> = series[i]
+                .iter()
+                .filter_map(|(date, a)| series[j].get(date).map(|b| (*a, *b)))
+                .collect();
+            
+            let correlation = pearson(&pairs);
+            matrix[i][j] = correlation;
+            matrix[j][i] = correlation;
+            common_days[i][j] = pairs.len();
+            common_days[j][i] = pairs.len();
+        }
+    }
+    
+    SentimentMatrix {
+        generated_at: Utc::now(),
+        labels: results.iter().map(|r| r.ceo_handle.clone()).collect(),
+        matrix,
+        common_days,
+    }
+}
+
+/// Positive tweets whose change (from `change_of`) exceeds `threshold`, as
+/// `(rises, positive tweets with that change)`
+///
+/// Tweets without price data for the window are left out of both counts, so
+/// the rate reads "of positive tweets with data, how many rose".
+fn positive_rise_counts<F>(impacts: &[TweetImpact], change_of: F, threshold: f64) -> (usize, usize)
+where
+    F: Fn(&TweetImpact) -> Option<f64>,
+{
+    let changes: Vec<f64> = impacts
+        .iter()
+        .filter(|i| i.tweet.sentiment.unwrap_or(0.0) > 0.0)
+        .filter_map(change_of)
+        .collect();
+    
+    let rises = changes.iter().filter(|c| **c > threshold).count();
+    
+    (rises, changes.len())
+}
+
+/// Success percentage from `(successes, trials)`, None without trials
+fn success_rate((successes, trials): (usize, usize)) -> Option<f64> {
+    (trials > 0).then(|| successes as f64 / trials as f64 * 100.0)
+}
+
+/// 95% Wilson score interval (as percentages) for `(successes, trials)`
+///
+/// Unlike the normal approximation it stays inside [0, 100] and widens
+/// sensibly for small samples. None without trials.
+pub fn wilson_interval((successes, trials): (usize, usize)) -> Option<(f64, f64)> {
+    if trials == 0 {
+        return None;
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = WILSON_Z * WILSON_Z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let margin = WILSON_Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    Some(((center - margin).max(0.0) * 100.0, (center + margin).min(1.0) * 100.0))
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
+    use crate::models::test_support::{jan, test_price, test_tweet};
+    use chrono::TimeZone;
 
     #[test]
     fn test_sentiment_positive() {
         let text = "This is great and amazing!";
-        let score = calculate_sentiment(text);
+        let score = Lexicon::default().score(text);
         assert!(score > 0.0);
     }
 
     #[test]
     fn test_sentiment_negative() {
         let text = "This is terrible and awful!";
-        let score = calculate_sentiment(text);
+        let score = Lexicon::default().score(text);
         assert!(score < 0.0);
     }
 
     #[test]
     fn test_sentiment_neutral() {
         let text = "This is a statement.";
-        let score = calculate_sentiment(text);
+        let score = Lexicon::default().score(text);
         assert_eq!(score, 0.0);
     }
+
+    #[test]
+    fn test_mixed_signals_are_flagged() {
+        let lexicon = Lexicon::default();
+        let (pos, neg) = lexicon.hits("Great product, terrible quarter");
+        assert_eq!((pos, neg), (1, 1));
+        assert!(is_mixed(pos, neg));
+        assert_eq!(lexicon.score("Great product, terrible quarter"), 0.0);
+        
+        // One stray negative word among many positives is not mixed
+        assert!(!is_mixed(4, 1));
+        assert!(!is_mixed(0, 0));
+    }
+
+    #[test]
+    fn test_sentiment_matches_whole_words() {
+        // "goodbye" and "issues" aren't lexicon words; links and mentions don't count
+        let lexicon = Lexicon::default();
+        assert_eq!(lexicon.hits("Goodbye to the old issues"), (0, 0));
+        assert_eq!(lexicon.hits("@winning see https://x.com/great"), (0, 0));
+        assert_eq!(lexicon.hits("GOOD, even great!"), (2, 0));
+    }
+
+    #[test]
+    fn test_period_performance_uses_price_basis() {
+        let prices = vec![
+            PricePoint { open: 100.0, low: 100.0, ..test_price(jan(1, 0), 110.0) },
+            PricePoint { open: 120.0, low: 120.0, ..test_price(jan(8, 0), 121.0) },
+        ];
+        
+        let open_basis = AnalysisOptions { price_basis: PriceBasis::Open, ..Default::default() };
+        let close = calculate_period_performance(&prices, 7, &AnalysisOptions::default()).unwrap();
+        let open = calculate_period_performance(&prices, 7, &open_basis).unwrap();
+        
+        assert!((close - 10.0).abs() < 1e-9);
+        assert!((open - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_period_performance_rejects_sparse_history() {
+        let make_price = |date: NaiveDate, close: f64| test_price(date.and_hms_opt(0, 0, 0).unwrap().and_utc(), close);
+        let day = |m: u32, d: u32| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let options = AnalysisOptions::default();
+        
+        // Two points 90 days apart: a "1m" change would really be a 3-month one
+        let sparse = vec![make_price(day(1, 1), 100.0), make_price(day(4, 1), 150.0)];
+        let err = calculate_period_performance(&sparse, 30, &options).unwrap_err();
+        assert!(err.contains("2025-01-01"), "{}", err);
+        assert!((calculate_period_performance(&sparse, 90, &options).unwrap() - 50.0).abs() < 1e-9);
+        
+        // Start price on a Friday for a Sunday target is within tolerance
+        let weekend = vec![make_price(day(3, 28), 100.0), make_price(day(4, 6), 110.0)];
+        assert!(calculate_period_performance(&weekend, 7, &options).is_ok());
+        let strict = AnalysisOptions { performance_tolerance_days: Some(0), ..Default::default() };
+        assert!(calculate_period_performance(&weekend, 7, &strict).is_err());
+        
+        assert!(calculate_period_performance(&sparse, 365, &options).unwrap_err().contains("starts after"));
+        assert_eq!(calculate_period_performance(&[], 7, &options).unwrap_err(), "no prices");
+    }
+
+    #[test]
+    fn test_surprise_score_weights_and_missing_components() {
+        let full = SurpriseComponents {
+            sentiment: Some(1.0),
+            price_z: Some(1.0),
+            volume: Some(1.0),
+            engagement: Some(1.0),
+        };
+        assert!((surprise_score(&full) - 100.0).abs() < 1e-9);
+        
+        let sentiment_only = SurpriseComponents {
+            sentiment: Some(0.5),
+            ..Default::default()
+        };
+        assert!((surprise_score(&sentiment_only) - 12.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_identical_tweets_collapse_into_one_impact() {
+        let tweets: Vec<Tweet> = (1..=3)
+            .map(|day| Tweet {
+                retweet_count: 10,
+                like_count: 100,
+                ..test_tweet(&day.to_string(), "Buy our new product!", jan(day, 12))
+            })
+            .collect();
+        let options = AnalysisOptions {
+            repeat_matching: RepeatMatching::Exact,
+            ..Default::default()
+        };
+        
+        let result = analyze("ceo", "TSLA", tweets, Vec::new(), &options, false).unwrap();
+        
+        assert_eq!(result.impacts.len(), 1);
+        assert_eq!(result.impacts[0].repeat_count, 3);
+        assert_eq!(result.impacts[0].tweet.id, "1");
+        assert_eq!(result.impacts[0].tweet.like_count, 300);
+        assert_eq!(result.impacts[0].tweet.retweet_count, 30);
+        assert_eq!(result.total_tweets, 1);
+        assert_eq!(result.repeated_tweets_collapsed, 2);
+    }
+
+    #[test]
+    fn test_activity_spike_against_daily_baseline() {
+        // One tweet a day for ten days, plus five more on the 5th
+        let mut impacts: Vec<TweetImpact> = (1..=10)
+            .map(|day| TweetImpact::new(test_tweet(&day.to_string(), "", jan(day, 12))))
+            .collect();
+        impacts.extend((100..105).map(|id| TweetImpact::new(test_tweet(&id.to_string(), "", jan(5, 12)))));
+        
+        let spikes = flag_activity_spikes(&mut impacts);
+        
+        assert_eq!(spikes, vec![(NaiveDate::from_ymd_opt(2025, 1, 5).unwrap(), 6)]);
+        assert_eq!(impacts.iter().filter(|i| i.high_activity_day).count(), 6);
+    }
+
+    #[test]
+    fn test_rise_matrix_covers_configured_windows_and_thresholds() {
+        let tweet = test_tweet("1", "great", jan(6, 15));
+        let prices = vec![test_price(jan(6, 0), 100.0), test_price(jan(7, 0), 102.0), test_price(jan(11, 0), 110.0)];
+        let options = AnalysisOptions {
+            rise_windows: vec![1, 5],
+            rise_thresholds: vec![1.0, 5.0],
+            ..Default::default()
+        };
+        
+        let result = analyze("ceo", "TSLA", vec![tweet], prices, &options, false).unwrap();
+        let matrix = result.rise_matrix.unwrap();
+        
+        assert_eq!(matrix.windows, vec![1, 5]);
+        assert_eq!(matrix.rates, vec![vec![Some(100.0), Some(0.0)], vec![Some(100.0), Some(100.0)]]);
+    }
+
+    #[test]
+    fn test_outside_price_range_counts_each_side() {
+        let impacts: Vec<TweetImpact> = [2, 3, 6, 10, 11, 12]
+            .into_iter()
+            .map(|day| TweetImpact::new(test_tweet(&day.to_string(), "", jan(day, 15))))
+            .collect();
+        let prices: Vec<PricePoint> = (6..=10).map(|day| test_price(jan(day, 0), 100.0)).collect();
+        
+        assert_eq!(outside_price_range(&impacts, &prices), (2, 2));
+        assert_eq!(outside_price_range(&impacts, &[]), (0, 0));
+    }
+
+    #[test]
+    fn test_range_ratio_against_trailing_ranges() {
+        let make_price = |day: u32, high: f64, low: f64| PricePoint { high, low, ..test_price(jan(day, 0), 100.0) };
+        // 2% ranges for a week, then a 6% range the day after the tweet
+        let mut prices: Vec<PricePoint> = (1..=7).map(|day| make_price(day, 101.0, 99.0)).collect();
+        prices.push(make_price(9, 103.0, 97.0));
+        let tweet_day = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        
+        let ratio = calculate_range_ratio(tweet_day, &prices).unwrap();
+        
+        assert!((ratio - 3.0).abs() < 1e-9);
+        assert_eq!(calculate_range_ratio(NaiveDate::from_ymd_opt(2025, 1, 3).unwrap(), &prices), None);
+    }
+
+    #[test]
+    fn test_rise_counts_skip_tweets_without_changes() {
+        let make_impact = |sentiment: f64, change_1d: Option<f64>| TweetImpact {
+            change_1d,
+            ..TweetImpact::new(Tweet { sentiment: Some(sentiment), ..test_tweet("1", "", Utc::now()) })
+        };
+        let impacts = vec![
+            make_impact(0.5, Some(4.0)),
+            make_impact(0.5, Some(1.0)),
+            make_impact(0.5, None),
+            make_impact(0.5, None),
+            make_impact(-0.5, Some(9.0)),
+        ];
+        
+        let counts = positive_rise_counts(&impacts, |i| i.change_1d, RISE_THRESHOLD_PCT);
+        
+        assert_eq!(counts, (1, 2));
+        assert_eq!(success_rate(counts), Some(50.0));
+        assert_eq!(positive_rise_counts(&impacts[2..4], |i| i.change_1d, RISE_THRESHOLD_PCT), (0, 0));
+    }
+
+    #[test]
+    fn test_rise_rate_is_missing_without_positive_priced_tweets() {
+        let prices: Vec<PricePoint> = (6..=10).map(|day| test_price(jan(day, 0), 100.0 + day as f64)).collect();
+        let tweets = vec![test_tweet("1", "terrible", jan(6, 15)), test_tweet("2", "bad loss", jan(7, 15))];
+
+        let result = analyze("ceo", "TSLA", tweets, prices, &AnalysisOptions::default(), false).unwrap();
+
+        // Not a 0% rate: there was no positive tweet to count
+        assert_eq!(result.positive_tweets_with_rise_1d, None);
+        assert_eq!(result.positive_tweets_with_rise_3d, None);
+    }
+
+    #[test]
+    fn test_forward_price_within_tolerance_is_flagged() {
+        let tweet = test_tweet("1", "great", jan(6, 15));
+        // No price on the 9th (3d target); the 8th is one day early
+        let prices = vec![test_price(jan(6, 0), 100.0), test_price(jan(7, 0), 101.0), test_price(jan(8, 0), 104.0)];
+        
+        let exact = analyze("ceo", "TSLA", vec![tweet.clone()], prices.clone(), &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(exact.impacts[0].change_3d, None);
+        
+        let options = AnalysisOptions { price_tolerance_days: 1, ..Default::default() };
+        let result = analyze("ceo", "TSLA", vec![tweet], prices, &options, false).unwrap();
+        let impact = &result.impacts[0];
+        
+        assert!((impact.change_3d.unwrap() - 4.0).abs() < 1e-9);
+        assert!(impact.change_3d_approximated);
+        assert!(!impact.change_1d_approximated);
+    }
+
+    #[test]
+    fn test_linear_regression() {
+        // y = 1 + 20x, plus noise that cancels out
+        let pairs = [(0.0, 1.5), (0.0, 0.5), (0.5, 11.0), (0.5, 11.0), (1.0, 21.0)];
+        let fit = linear_regression(&pairs).unwrap();
+        assert!((fit.slope - 20.0).abs() < 1e-9);
+        assert!((fit.intercept - 1.0).abs() < 1e-9);
+        assert!(fit.r_squared > 0.99 && fit.r_squared < 1.0);
+        
+        assert_eq!(linear_regression(&[(0.3, 1.0), (0.3, 2.0)]), None);
+    }
+
+    #[test]
+    fn test_constant_price_series_is_degenerate() {
+        // Every day's move is the same +1%, so the changes have no variance
+        let prices = (6..=17).map(|day| test_price(jan(day, 0), 50.0 * 1.01f64.powi(day as i32))).collect();
+        let tweets = vec![
+            test_tweet("6", "great", jan(6, 15)),
+            test_tweet("7", "terrible", jan(7, 15)),
+            test_tweet("8", "great amazing", jan(8, 15)),
+        ];
+        
+        let result = analyze("ceo", "FLAT", tweets, prices, &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(result.correlation_1d, None);
+        assert_eq!(result.slope_1d, None);
+        assert_eq!(result.r_squared_1d, None);
+        assert_eq!(result.warnings, vec!["price series has no variance over the window".to_string()]);
+        
+        // Float noise around a constant is still no variance
+        assert_eq!(pearson(&[(0.1, 1e-17), (0.5, 0.0), (0.9, -1e-17)]), None);
+        assert_eq!(degenerate_inputs(&[(0.2, 1.0), (0.2, 3.0)]), Some("tweet sentiment has no variance (every scored tweet has the same score)"));
+        assert_eq!(degenerate_inputs(&[(0.1, 1.0), (0.2, 3.0)]), None);
+    }
+
+    #[test]
+    fn test_correlation_significance() {
+        assert!(correlation_is_significant(0.5, 30));
+        assert!(!correlation_is_significant(0.5, 10));
+        assert!(!correlation_is_significant(0.9, 3));
+        assert!(correlation_is_significant(-0.3, 100));
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let (low, high) = wilson_interval((3, 5)).unwrap();
+        assert!((low - 23.07).abs() < 0.01);
+        assert!((high - 88.24).abs() < 0.01);
+        
+        // Narrower with more trials at the same rate
+        let (low, high) = wilson_interval((300, 500)).unwrap();
+        assert!(low > 55.0 && high < 65.0);
+        
+        assert_eq!(wilson_interval((0, 0)), None);
+        assert_eq!(wilson_interval((0, 4)).unwrap().0, 0.0);
+    }
+
+    #[test]
+    fn test_coverage_reason_explains_missing_windows() {
+        let prices: Vec<PricePoint> = [6, 7, 8, 9, 10, 13, 14]
+            .into_iter()
+            .map(|day| test_price(jan(day, 0), 100.0))
+            .collect();
+        let price_map = create_price_map(&prices);
+        let reason = |day: u32| {
+            let tweet = test_tweet(&day.to_string(), "update", jan(day, 15));
+            calculate_tweet_impact(tweet, &price_map, &AnalysisOptions::default()).coverage_reason
+        };
+        
+        assert_eq!(reason(6), CoverageReason::FullCoverage);
+        assert_eq!(reason(8), CoverageReason::MissingFuturePrice);
+        assert_eq!(reason(11), CoverageReason::WeekendBaseline);
+        assert_eq!(reason(4), CoverageReason::BeforePriceHistory);
+        assert_eq!(reason(13), CoverageReason::AfterPriceHistory);
+    }
+
+    #[test]
+    fn test_replay_fills_only_missing_forward_changes() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let tweet = test_tweet("1", "great", jan(6, 15));
+        let options = AnalysisOptions::default();
+        
+        // Stored when only the tweet day and the next day had prices
+        let early_prices = vec![make_price(6, 100.0), make_price(7, 102.0)];
+        let mut result = analyze("ceo", "TSLA", vec![tweet], early_prices, &options, false).unwrap();
+        assert_eq!(result.impacts[0].change_3d, None);
+        
+        let later_prices = vec![make_price(6, 100.0), make_price(7, 150.0), make_price(9, 110.0)];
+        let updated = replay_forward_prices(&mut result, &later_prices, &options);
+        
+        assert_eq!(updated, 1);
+        assert!((result.impacts[0].change_1d.unwrap() - 2.0).abs() < 1e-9);
+        assert!((result.impacts[0].change_3d.unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_replay_recomputes_market_cap_change() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let tweet = test_tweet("1", "great", jan(6, 15));
+        let options = AnalysisOptions { shares_outstanding: Some(1_000), ..Default::default() };
+        
+        // Stored before the next trading day's close existed
+        let mut result = analyze("ceo", "TSLA", vec![tweet], vec![make_price(6, 100.0)], &options, false).unwrap();
+        assert_eq!(result.impacts[0].market_cap_change_1d, None);
+        
+        replay_forward_prices(&mut result, &[make_price(6, 100.0), make_price(7, 102.0)], &options);
+        assert!((result.impacts[0].market_cap_change_1d.unwrap() - 2_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_replay_keeps_suppressed_impacts_empty() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let tweet = |id: &str, day: u32| test_tweet(id, "great", jan(day, 15));
+        let options = AnalysisOptions { attribution: AttributionMode::First, ..Default::default() };
+        
+        let early_prices = vec![make_price(6, 100.0), make_price(7, 102.0)];
+        let mut result = analyze("ceo", "TSLA", vec![tweet("1", 6), tweet("2", 7)], early_prices, &options, false).unwrap();
+        assert_eq!(result.attribution_suppressed, 1);
+        
+        let later_prices = vec![make_price(6, 100.0), make_price(7, 102.0), make_price(8, 104.0), make_price(9, 110.0), make_price(10, 111.0)];
+        replay_forward_prices(&mut result, &later_prices, &options);
+        
+        let suppressed = result.impacts.iter().find(|i| i.tweet.id == "2").unwrap();
+        assert!(suppressed.attribution_suppressed);
+        assert_eq!((suppressed.change_0d, suppressed.change_1d, suppressed.change_3d), (None, None, None));
+        assert_eq!(result.attribution_suppressed, 1);
+    }
+
+    #[test]
+    fn test_stored_options_follow_run_config() {
+        let options = AnalysisOptions {
+            price_basis: PriceBasis::Open,
+            return_type: ReturnType::Log,
+            attribution: AttributionMode::First,
+            exclude_mixed_from_correlation: true,
+            rise_windows: vec![2, 5],
+            ..Default::default()
+        };
+        let mut result = AnalysisResult::new("ceo".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        assert_eq!(stored_options(&result, Arc::default()).return_type, ReturnType::Simple);
+        result.engagement_window_days = Some(0);
+        assert_eq!(stored_options(&result, Arc::default()).engagement_window, Some(0));
+        
+        result.run_config = Some(run_config(&options, 90, 60, "twitter_api", "test"));
+        let stored = stored_options(&result, Arc::default());
+        assert_eq!((stored.price_basis, stored.return_type, stored.attribution), (PriceBasis::Open, ReturnType::Log, AttributionMode::First));
+        assert!(stored.exclude_mixed_from_correlation && !stored.exclude_news_from_correlation);
+        assert_eq!(stored.rise_windows(), vec![2, 5]);
+    }
+
+    #[test]
+    fn test_rescore_keeps_price_changes() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let tweet = |day: u32, text: &str| test_tweet(&day.to_string(), text, jan(day, 15));
+        let prices = vec![make_price(6, 100.0), make_price(7, 104.0), make_price(8, 99.0), make_price(9, 101.0)];
+        let options = AnalysisOptions::default();
+        let mut result = analyze("ceo", "TSLA", vec![tweet(6, "great"), tweet(7, "terrible")], prices.clone(), &options, false).unwrap();
+        let fresh: Vec<Option<f64>> = result.impacts.iter().map(|i| i.tweet.sentiment).collect();
+        let changes: Vec<Option<f64>> = result.impacts.iter().map(|i| i.change_1d).collect();
+        
+        // As if stored under an older lexicon that scored both tweets neutral
+        for impact in &mut result.impacts {
+            impact.tweet.sentiment = Some(0.0);
+        }
+        result.lexicon_hash = Some("old".to_string());
+        
+        assert_eq!(rescore_sentiment(&mut result, &prices, &options), 2);
+        assert_eq!(result.impacts.iter().map(|i| i.tweet.sentiment).collect::<Vec<_>>(), fresh);
+        assert_eq!(result.impacts.iter().map(|i| i.change_1d).collect::<Vec<_>>(), changes);
+        assert_eq!(result.lexicon_hash, Some(options.lexicon.hash()));
+        assert_eq!(result.lexicon.as_deref(), Some(crate::lexicon::DEFAULT_LEXICON_NAME));
+        assert!(!scored_with_other_lexicon(&result, &options.lexicon));
+        assert_eq!(rescore_sentiment(&mut result, &prices, &options), 0);
+    }
+
+    #[test]
+    fn test_trimmed_results_keep_full_statistics() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let tweet = |day: u32, text: &str| test_tweet(&day.to_string(), text, jan(day, 15));
+        let prices = vec![make_price(6, 100.0), make_price(7, 104.0), make_price(8, 99.0), make_price(9, 101.0)];
+        let tweets = vec![tweet(6, "great"), tweet(7, "terrible"), tweet(8, "good")];
+        let options = AnalysisOptions::default();
+        let mut result = analyze("ceo", "TSLA", tweets.clone(), prices.clone(), &options, false).unwrap();
+        let full = (result.total_tweets, result.correlation_1d);
+        result.trim_impacts(1);
+        
+        // Replay fills the kept impacts but leaves the statistics as stored
+        replay_forward_prices(&mut result, &prices, &options);
+        assert_eq!((result.total_tweets, result.correlation_1d), full);
+        
+        // Rescoring re-analyzes from raw data and trims back to one impact
+        let raw = RawFetch {
+            ceo_handle: "ceo".to_string(),
+            ticker: "TSLA".to_string(),
+            fetched_at: Utc::now(),
+            days: 90,
+            tweet_source: "twitter_api".to_string(),
+            stock_provider: "test".to_string(),
+            follower_count: None,
+            tweets,
+            prices,
+        };
+        rescore_stored(&mut result, raw, &options, &crate::prolog::FactsFilter::default()).unwrap();
+        assert_eq!((result.total_tweets, result.correlation_1d), full);
+        assert_eq!((result.impacts.len(), result.impacts_trimmed), (1, 2));
+    }
+
+    #[test]
+    fn test_same_day_change_from_open_to_close() {
+        let make_price = |day: u32, open: f64, close: f64| PricePoint {
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            ..test_price(jan(day, 0), close)
+        };
+        let tweet = |id: &str, day: u32| test_tweet(id, "great", jan(day, 15));
+        let options = AnalysisOptions { rise_windows: vec![0, 1], ..Default::default() };
+        
+        // Day 4 is a Saturday: no session, so no same-day move
+        let prices = vec![make_price(3, 100.0, 104.0), make_price(6, 100.0, 101.0)];
+        let result = analyze("ceo", "TSLA", vec![tweet("a", 3), tweet("b", 4)], prices, &options, false).unwrap();
+        
+        let by_id = |id: &str| result.impacts.iter().find(|i| i.tweet.id == id).unwrap();
+        assert!((by_id("a").change_0d.unwrap() - 4.0).abs() < 1e-9);
+        assert_eq!(by_id("b").change_0d, None);
+        assert_eq!(result.rise_matrix.unwrap().windows, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_daily_timeseries_left_joins_by_date() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let make_impact = |day: u32, sentiment: f64| TweetImpact::new(Tweet {
+            sentiment: Some(sentiment),
+            ..test_tweet(&day.to_string(), "", jan(day, 15))
+        });
+        // Fri 3rd and Sat 4th tweets; prices Fri 3rd and Mon 6th
+        let impacts = vec![make_impact(3, 0.2), make_impact(3, 0.4), make_impact(4, -0.1)];
+        let prices = vec![make_price(2, 99.0), make_price(3, 100.0), make_price(6, 104.0)];
+        
+        let series = daily_timeseries(&impacts, &prices);
+        let rows: Vec<(u32, Option<f64>, usize)> = series.iter()
+            .map(|p| (p.date.day(), p.close, p.tweet_count))
+            .collect();
+        assert_eq!(rows, vec![(3, Some(100.0), 2), (4, None, 1), (5, None, 0), (6, Some(104.0), 0)]);
+        assert!((series[0].avg_sentiment.unwrap() - 0.3).abs() < 1e-12);
+        assert_eq!(series[2].avg_sentiment, None);
+        assert!(daily_timeseries(&[], &prices).is_empty());
+    }
+
+    #[test]
+    fn test_calibration_buckets_by_sentiment() {
+        let make_impact = |sentiment: f64, change_1d: Option<f64>| TweetImpact {
+            change_1d,
+            ..TweetImpact::new(Tweet { sentiment: Some(sentiment), ..test_tweet("", "", Utc::now()) })
+        };
+        let impacts = vec![
+            make_impact(-0.3, Some(-2.0)),
+            make_impact(-0.05, Some(1.0)),
+            make_impact(0.0, None),
+            make_impact(0.15, Some(4.0)),
+            make_impact(0.5, Some(-1.0)),
+        ];
+        
+        let buckets = sentiment_calibration(&impacts);
+        let labels: Vec<&str> = buckets.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, vec!["strong negative", "negative", "neutral", "positive", "strong positive"]);
+        assert_eq!(buckets.iter().map(|b| b.tweets).collect::<Vec<_>>(), vec![1, 1, 1, 0, 2]);
+        assert_eq!(buckets[2].avg_change_1d, None);
+        assert!((buckets[4].avg_change_1d.unwrap() - 1.5).abs() < 1e-9);
+        assert_eq!(buckets[4].up_share_1d, Some(50.0));
+        assert_eq!(buckets[3].up_share_1d, None);
+    }
+
+    #[test]
+    fn test_custom_statistics_run_per_window() {
+        /// Number of impacts with a change in the window
+        #[derive(Debug)]
+        struct PairCount;
+        
+        impl Statistic for PairCount {
+            fn name(&self) -> &str {
+                "pairs"
+            }
+            
+            fn compute(&self, impacts: &[TweetImpact], change: &dyn Fn(&TweetImpact) -> Option<f64>) -> Option<f64> {
+                Some(impacts.iter().filter(|i| change(i).is_some()).count() as f64)
+            }
+        }
+        
+        let prices: Vec<PricePoint> = (6..=10).map(|d| test_price(jan(d, 0), 100.0 + d as f64)).collect();
+        let tweets = vec![
+            test_tweet("a", "great win", jan(6, 15)),
+            test_tweet("b", "bad loss", jan(7, 15)),
+            test_tweet("c", "great", jan(9, 15)),
+        ];
+        
+        let default = analyze("ceo", "TSLA", tweets.clone(), prices.clone(), &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(default.statistics.get("pearson_1d").copied(), default.correlation_1d);
+        
+        let options = AnalysisOptions { statistics: vec![Arc::new(PairCount)], ..Default::default() };
+        let result = analyze("ceo", "TSLA", tweets, prices, &options, false).unwrap();
+        assert_eq!(result.statistics.get("pairs_1d"), Some(&3.0));
+        assert_eq!(result.statistics.get("pairs_3d"), Some(&2.0));
+        assert!(!result.statistics.contains_key("pearson_1d"));
+    }
+
+    #[test]
+    fn test_causal_score_rescales_missing_factors() {
+        let weights = CausalWeights::default();
+        let components = CausalComponents {
+            abnormal_move: Some(1.0),
+            direction: Some(0.0),
+            ..Default::default()
+        };
+        
+        // Only move and direction have data: 0.35 / (0.35 + 0.20) of the score
+        let contributions = weights.contributions(&components).unwrap();
+        assert!((contributions.abnormal_move.unwrap() - 35.0 / 0.55).abs() < 1e-9);
+        assert_eq!(contributions.direction, Some(0.0));
+        assert_eq!(contributions.volume, None);
+        assert!((weights.score(&components).unwrap() - 35.0 / 0.55).abs() < 1e-9);
+        assert_eq!(weights.score(&CausalComponents::default()), None);
+        
+        assert!(CausalWeights::from_list(&[1.0, 0.0]).is_err());
+        assert!(CausalWeights::from_list(&[0.0; 5]).is_err());
+        assert_eq!(CausalWeights::from_list(&[0.35, 0.2, 0.15, 0.15, 0.15]).unwrap(), weights);
+    }
+
+    #[test]
+    fn test_everything_filtered_gives_no_data_result() {
+        let tweet = test_tweet("1", "great", jan(6, 15));
+        let options = AnalysisOptions { min_words: 3, ..Default::default() };
+        
+        let result = analyze("ceo", "TSLA", vec![tweet], Vec::new(), &options, false).unwrap();
+        assert_eq!(result.status, ResultStatus::NoData);
+        assert_eq!(result.status_reason.as_deref(), Some("all 1 tweets filtered by --min-words 3"));
+        
+        let empty = analyze("ceo", "TSLA", Vec::new(), Vec::new(), &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(empty.status, ResultStatus::NoData);
+    }
+
+    #[test]
+    fn test_max_tweet_age_drops_old_tweets() {
+        let make_tweet = |id: &str, age_days: i64| test_tweet(id, "great news", Utc::now() - Duration::days(age_days));
+        let options = AnalysisOptions { max_tweet_age_days: Some(30), ..Default::default() };
+        
+        let tweets = vec![make_tweet("recent", 2), make_tweet("old", 45), make_tweet("older", 400)];
+        let result = analyze("ceo", "TSLA", tweets, Vec::new(), &options, false).unwrap();
+        assert_eq!(result.total_tweets, 1);
+        assert_eq!(result.tweets_past_max_age, 2);
+        assert_eq!(result.impacts[0].tweet.id, "recent");
+        
+        let stale = analyze("ceo", "TSLA", vec![make_tweet("old", 45)], Vec::new(), &options, false).unwrap();
+        assert_eq!(stale.status, ResultStatus::NoData);
+        assert_eq!(stale.status_reason.as_deref(), Some("all 1 tweets older than --max-tweet-age-days 30"));
+    }
+
+    #[test]
+    fn test_sentiment_trend_slope() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap();
+        let impacts: Vec<TweetImpact> = [(0, -0.2), (10, 0.0), (20, 0.2)]
+            .into_iter()
+            .map(|(day, sentiment)| TweetImpact::new(Tweet {
+                sentiment: Some(sentiment),
+                ..test_tweet(&day.to_string(), "", start + Duration::days(day))
+            }))
+            .collect();
+        
+        // +0.4 over 20 days
+        assert!((sentiment_trend_slope(&impacts).unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(sentiment_trend_slope(&impacts[..1]), None);
+    }
+
+    #[test]
+    fn test_sentiment_streaks() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap();
+        // Posted out of order: the scan must follow created_at
+        let impacts: Vec<TweetImpact> = [(3, 0.4, None), (0, 0.5, None), (1, 0.2, None), (2, 0.1, Some(-1.5)),
+                                         (4, -0.3, None), (5, 0.0, None), (6, -0.2, None), (7, -0.6, Some(0.5))]
+            .into_iter()
+            .map(|(day, sentiment, change)| TweetImpact {
+                change_1d: change,
+                ..TweetImpact::new(Tweet {
+                    sentiment: Some(sentiment),
+                    ..test_tweet(&day.to_string(), "", start + Duration::days(day))
+                })
+            })
+            .collect();
+        
+        let streaks = sentiment_streaks(&impacts);
+        assert_eq!(streaks.longest_positive, 4);
+        assert_eq!(streaks.longest_negative, 2);
+        // The only 3+ streak (days 0-3) ends on day 3, which has no 1d change
+        assert_eq!(streaks.reversal_rate, None);
+        
+        let mut impacts = impacts;
+        impacts[0].change_1d = Some(-2.0);
+        assert_eq!(sentiment_streaks(&impacts).reversal_rate, Some(100.0));
+        assert_eq!(sentiment_streaks(&[]), SentimentStreaks::default());
+    }
+
+    #[test]
+    fn test_attribution_credits_one_tweet_per_window() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap();
+        // Days 0, 1 and 3 share day 0's 3-day window; day 5 starts a new one
+        let impacts = || -> Vec<TweetImpact> {
+            [(0, 10), (1, 500), (3, 20), (5, 0)]
+                .into_iter()
+                .map(|(day, likes)| TweetImpact {
+                    change_1d: Some(2.0),
+                    change_3d: Some(4.0),
+                    ..TweetImpact::new(Tweet {
+                        like_count: likes,
+                        sentiment: Some(0.5),
+                        ..test_tweet(&day.to_string(), "", start + Duration::days(day))
+                    })
+                })
+                .collect()
+        };
+        let credited = |impacts: &[TweetImpact]| -> Vec<String> {
+            impacts.iter().filter(|i| i.change_1d.is_some()).map(|i| i.tweet.id.clone()).collect()
+        };
+        
+        let mut all = impacts();
+        assert_eq!(suppress_overlapping_attribution(&mut all, AttributionMode::All), 0);
+        assert_eq!(credited(&all), ["0", "1", "3", "5"]);
+        
+        let mut first = impacts();
+        assert_eq!(suppress_overlapping_attribution(&mut first, AttributionMode::First), 2);
+        assert_eq!(credited(&first), ["0", "5"]);
+        assert!(first[1].attribution_suppressed && first[1].change_3d.is_none());
+        
+        let mut engaged = impacts();
+        assert_eq!(suppress_overlapping_attribution(&mut engaged, AttributionMode::MostEngaged), 2);
+        assert_eq!(credited(&engaged), ["1", "5"]);
+        
+        // Suppressed tweets stay out even where changes are recomputed from prices
+        assert_eq!(window_change(&engaged[0], 1, &HashMap::new(), &AnalysisOptions::default()), None);
+    }
+
+    #[test]
+    fn test_stale_price_runs_are_left_out_of_correlation() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let make_tweet = |day: u32, text: &str| test_tweet(&day.to_string(), text, jan(day, 15));
+        // Trades normally through the 8th, then repeats 40.0 from the 9th
+        let prices: Vec<PricePoint> = [(6, 50.0), (7, 45.0), (8, 48.0)]
+            .into_iter()
+            .chain((9..=15).map(|day| (day, 40.0)))
+            .map(|(day, close)| make_price(day, close))
+            .collect();
+        let d = |day: u32| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        assert_eq!(stale_price_ranges(&prices), vec![(d(9), d(15))]);
+        assert!(stale_price_ranges(&prices[..7]).is_empty());
+        
+        let tweets = vec![make_tweet(6, "terrible"), make_tweet(7, "great"), make_tweet(10, "great"), make_tweet(11, "terrible")];
+        let result = analyze("ceo", "HALT", tweets, prices, &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(result.stale_price_ranges, vec![(d(9), d(15))]);
+        // Only the two tweets before the halt are left: a perfect fit
+        assert!((result.correlation_1d.unwrap() - 1.0).abs() < 1e-9);
+        
+        // The dumped pairs are exactly the ones the correlation used
+        let pairs_1d: Vec<(f64, f64)> = correlation_pairs(&result.impacts)
+            .into_iter()
+            .filter(|p| p.window_days == 1)
+            .map(|p| (p.sentiment, p.change))
+            .collect();
+        assert_eq!(pairs_1d.len(), 2);
+        assert_eq!(pearson(&pairs_1d), result.correlation_1d);
+        let ids: Vec<String> = correlation_pairs(&result.impacts).into_iter().map(|p| p.tweet_id).collect();
+        assert!(!ids.contains(&"10".to_string()) && !ids.contains(&"11".to_string()));
+    }
+
+    #[test]
+    fn test_exclude_neutral_from_correlation() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let make_tweet = |day: u32, text: &str| test_tweet(&day.to_string(), text, jan(day, 15));
+        let prices: Vec<PricePoint> = [(6, 100.0), (7, 110.0), (8, 99.0), (9, 120.0), (10, 90.0)]
+            .into_iter()
+            .map(|(day, close)| make_price(day, close))
+            .collect();
+        let tweets = || vec![make_tweet(6, "great"), make_tweet(7, "terrible"), make_tweet(8, "hello"), make_tweet(9, "update")];
+        
+        let all = analyze("ceo", "TSLA", tweets(), prices.clone(), &AnalysisOptions::default(), false).unwrap();
+        assert_eq!(all.neutral_excluded_from_correlation, 0);
+        assert_eq!(all.correlation_1d_with_neutral, None);
+        
+        let options = AnalysisOptions { exclude_neutral_from_correlation: true, ..Default::default() };
+        let result = analyze("ceo", "TSLA", tweets(), prices, &options, false).unwrap();
+        // Without the two zero-sentiment tweets only the perfect fit is left
+        assert!((result.correlation_1d.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(result.correlation_1d_with_neutral, all.correlation_1d);
+        assert_eq!(result.neutral_excluded_from_correlation, 2);
+        assert_eq!(result.neutral_tweets, 2);
+        
+        // Each window explains itself: flat intraday prices leave no 0d correlation
+        let detail = |window| result.correlations.iter().find(|c| c.window_days == window).unwrap();
+        assert_eq!((detail(1).value, detail(1).n, detail(1).reason.as_deref()), (result.correlation_1d, 2, None));
+        assert_eq!(detail(0).value, None);
+        assert_eq!(detail(0).reason.as_deref(), Some("price series has no variance over the window"));
+        
+        // Only the first tweet has a next-day close
+        let short = analyze("ceo", "TSLA", tweets(), vec![make_price(6, 100.0), make_price(7, 110.0)], &options, false).unwrap();
+        let one_day = short.correlations.iter().find(|c| c.window_days == 1).unwrap();
+        assert_eq!((one_day.value, one_day.n), (None, 1));
+        assert_eq!(one_day.reason.as_deref(), Some("only 1 tweet with a 1d price change"));
+    }
+
+    #[test]
+    fn test_engagement_move_correlation() {
+        let make_price = |day: u32, close: f64| test_price(jan(day, 0), close);
+        let make_tweet = |day: u32, likes: u32, text: &str| Tweet {
+            retweet_count: likes / 10,
+            like_count: likes,
+            ..test_tweet(&day.to_string(), text, jan(day, 15))
+        };
+        // The two viral tweets precede the two big moves, one up and one down
+        let prices: Vec<PricePoint> = [(6, 100.0), (7, 110.0), (8, 109.0), (9, 90.0), (10, 90.9)]
+            .into_iter()
+            .map(|(day, close)| make_price(day, close))
+            .collect();
+        let tweets = || vec![
+            make_tweet(6, 10_000, "great"),
+            make_tweet(7, 5, "great"),
+            make_tweet(8, 50_000, "great"),
+            make_tweet(9, 0, "terrible"),
+        ];
+        
+        let result = analyze("ceo", "TSLA", tweets(), prices.clone(), &AnalysisOptions::default(), false).unwrap();
+        assert!(result.engagement_move_correlation.unwrap() > 0.9);
+        assert_eq!(result.engagement_window_days, Some(ENGAGEMENT_WINDOW_DAYS));
+        
+        // Flat intraday prices give no same-day moves to correlate with
+        let options = AnalysisOptions { engagement_window: Some(0), ..Default::default() };
+        let same_day = analyze("ceo", "TSLA", tweets(), prices, &options, false).unwrap();
+        assert_eq!(same_day.engagement_move_correlation, None);
+        assert_eq!(same_day.engagement_window_days, Some(0));
+    }
+
+    #[test]
+    fn test_market_cap_change() {
+        let mut impact = TweetImpact::new(test_tweet("1", "great", Utc::now()));
+        impact.price_at_tweet = Some(200.0);
+        impact.change_1d = Some(5.0);
+        
+        // +5% on $200 × 3B shares = +$30B
+        let usd = market_cap_change(&impact, 3_000_000_000, ReturnType::Simple).unwrap();
+        assert!((usd - 30e9).abs() < 1.0);
+        
+        // A log move converts back to the same dollar gain
+        impact.change_1d = Some(1.05f64.ln() * 100.0);
+        let usd = market_cap_change(&impact, 3_000_000_000, ReturnType::Log).unwrap();
+        assert!((usd - 30e9).abs() < 1.0);
+        
+        impact.change_1d = None;
+        assert_eq!(market_cap_change(&impact, 3_000_000_000, ReturnType::Simple), None);
+    }
+
+    #[test]
+    fn test_gap_splits_one_day_move() {
+        let make_price = |day: u32, open: f64, close: f64| PricePoint {
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            ..test_price(jan(day, 0), close)
+        };
+        // 21:30 UTC is 16:30 in New York: after the close
+        let tweet = test_tweet("1", "great", Utc.with_ymd_and_hms(2025, 1, 7, 21, 30, 0).unwrap());
+        let prices = vec![make_price(7, 98.0, 100.0), make_price(8, 104.0, 105.0)];
+        let result = analyze("ceo", "TSLA", vec![tweet], prices, &AnalysisOptions::default(), false).unwrap();
+        
+        let impact = &result.impacts[0];
+        assert!((impact.gap_1d.unwrap() - 4.0).abs() < 1e-9);
+        assert!((impact.change_1d.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(market_session(impact.tweet.created_at), MarketSession::AfterHours);
+        assert_eq!(market_session(Utc.with_ymd_and_hms(2025, 1, 7, 13, 0, 0).unwrap()), MarketSession::PreMarket);
+        assert_eq!(market_session(Utc.with_ymd_and_hms(2025, 1, 7, 15, 0, 0).unwrap()), MarketSession::Regular);
+    }
+
+    #[test]
+    fn test_volume_ratio_against_trailing_average() {
+        let make_price = |day: u32, volume: u64| PricePoint { volume, ..test_price(jan(day, 0), 100.0) };
+        let mut prices: Vec<_> = (1..=6).map(|day| make_price(day, 1000)).collect();
+        prices.push(make_price(7, 9999));
+        prices.push(make_price(8, 3000));
+        
+        let tweet_day = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+        assert!((calculate_volume_ratio(tweet_day, &prices).unwrap() - 3.0).abs() < 1e-9);
+        
+        // Not enough history before the 3rd for a baseline
+        let early = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+        assert_eq!(calculate_volume_ratio(early, &prices), None);
+    }
+
+    #[test]
+    fn test_news_window_covers_lookahead() {
+        let event = |d: u32| NewsEvent {
+            date: NaiveDate::from_ymd_opt(2025, 1, d).unwrap(),
+            headline: String::new(),
+        };
+        let tweet_day = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+        
+        assert!(has_news_in_window(tweet_day, &[event(13)]));
+        assert!(!has_news_in_window(tweet_day, &[event(9), event(14)]));
+    }
+
+    #[test]
+    fn test_streaming_pearson_matches_two_pass() {
+        let pairs: Vec<(f64, f64)> = (0..50)
+            .map(|i| {
+                let x = (i as f64 * 0.37).sin();
+                (x, 2.0 * x + (i as f64 * 1.3).cos())
+            })
+            .collect();
+        
+        // Reference: textbook two-pass formula
+        let n = pairs.len() as f64;
+        let mx = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+        let my = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+        let cov: f64 = pairs.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+        let vx: f64 = pairs.iter().map(|(x, _)| (x - mx).powi(2)).sum();
+        let vy: f64 = pairs.iter().map(|(_, y)| (y - my).powi(2)).sum();
+        let expected = cov / (vx * vy).sqrt();
+        
+        assert!((pearson(&pairs).unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_review_queue_orders_worst_disagreement_first() {
+        let make_impact = |id: &str, sentiment: f64| TweetImpact::new(Tweet {
+            sentiment: Some(sentiment),
+            ..test_tweet(id, "", Utc::now())
+        });
+        
+        let mut impacts = vec![make_impact("a", 0.1), make_impact("b", 0.5), make_impact("c", -0.2)];
+        let remote: HashMap<String, f64> = [
+            ("a".to_string(), 0.0),
+            ("b".to_string(), -0.5),
+            ("c".to_string(), 0.6),
+        ].into_iter().collect();
+        
+        apply_remote_sentiment(&mut impacts, &remote);
+        let queue = review_queue(&impacts, 10);
+        
+        let ids: Vec<_> = queue.iter().map(|i| i.tweet.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_sentiment_matrix_aligns_on_common_dates() {
+        let make_result = |handle: &str, days: &[(u32, f64)]| {
+            let mut result = AnalysisResult::new(handle.to_string(), "X".to_string(), Utc::now(), Utc::now());
+            for (day, sentiment) in days {
+                result.impacts.push(TweetImpact::new(Tweet {
+                    sentiment: Some(*sentiment),
+                    ..test_tweet(&format!("{}-{}", handle, day), "", jan(*day, 12))
+                }));
+            }
+            result
+        };
+        
+        // Day 9 only exists for "a" and must be ignored for the pair
+        let a = make_result("a", &[(1, 0.1), (2, 0.2), (3, 0.3), (9, -1.0)]);
+        let b = make_result("b", &[(1, 0.2), (2, 0.4), (3, 0.6)]);
+        
+        // Trimmed results still count every day they tweeted
+        let mut trimmed = a.clone();
+        trimmed.daily_timeseries = daily_timeseries(&trimmed.impacts, &[]);
+        trimmed.trim_impacts(1);
+        assert_eq!(sentiment_correlation_matrix(&[trimmed, b.clone()]).common_days[0][1], 3);
+        
+        let matrix = sentiment_correlation_matrix(&[a, b]);
+        
+        assert_eq!(matrix.labels, vec!["a", "b"]);
+        assert_eq!(matrix.common_days[0][1], 3);
+        assert!((matrix.matrix[0][1].unwrap() - 1.0).abs() < 1e-9);
+        assert!((matrix.matrix[0][0].unwrap() - 1.0).abs() < 1e-9);
+    }
 }
@@ -2,11 +2,107 @@
 //!
 //! Fetches stock prices from Yahoo Finance (free, no API key)
 //! and tweet counts from Twitter.
-//! Tracks MONTHLY metrics - resets at the start of each month.
+//! Tracks MONTHLY metrics - resets at the start of each month, keeping
+//! each finished month in the entry's `history`.
+//!
+//! `daily-update backfill --months 12` fills `history` for the months before
+//! the current one from Yahoo price history instead. Tweet counts can't be
+//! fetched for past months, so backfilled months leave them null.
+//!
+//! Impactful tweets from the latest batch results (`data/results.json`) are
+//! alerted once, printed and POSTed to ALERT_WEBHOOK_URL when set; the same
+//! tweet isn't alerted again until ALERT_COOLDOWN_DAYS have passed.
+
+#[path = "../http.rs"]
+mod http;
+#[path = "../jitter.rs"]
+mod jitter;
+#[path = "../models.rs"]
+mod models;
+#[path = "../storage.rs"]
+mod storage;
 
 use anyhow::{Context, Result};
-use chrono::{Datelike, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where the tracked monthly entries are stored (read by the web server)
+const TRACKING_FILE: &str = "data/tracking.json";
+
+/// Default number of days before the same tweet may be alerted again
+const DEFAULT_ALERT_COOLDOWN_DAYS: i64 = 7;
+
+/// Default age (days) of the newest tweets considered for alerts
+const DEFAULT_ALERT_LOOKBACK_DAYS: i64 = 7;
+
+/// Default number of Yahoo price requests in flight at once
+const DEFAULT_PRICE_CONCURRENCY: usize = 8;
+
+/// Default pause after each Gemini call (free-tier rate limit)
+const DEFAULT_GEMINI_DELAY_MS: u64 = 3000;
+
+/// Default extra pause between entries
+const DEFAULT_ENTRY_DELAY_MS: u64 = 1500;
+
+/// Default Yahoo chart range, wide enough to reach back over a weekend or missed run
+const DEFAULT_YAHOO_RANGE: &str = "5d";
+
+/// Default age (days) after which a tracked price is flagged stale
+const DEFAULT_STALE_PRICE_DAYS: i64 = 3;
+
+/// Default half-width (%) of the monthly change band shown as "flat"
+const DEFAULT_FLAT_BAND_PCT: f64 = 0.5;
+
+/// Calendar days after the 1st searched for the month's first trading day
+const MONTH_START_SEARCH_DAYS: i64 = 10;
+
+/// Default number of finished months `backfill` fills in
+const DEFAULT_BACKFILL_MONTHS: u32 = 12;
+
+/// Names the providers' rate-limit quotas are tracked under (see `http::record_quota`)
+const YAHOO_QUOTA: &str = "yahoo";
+const GEMINI_QUOTA: &str = "gemini";
+
+/// Daily update; everything but the subcommand is configured through env vars
+#[derive(Debug, Parser)]
+#[command(name = "daily-update", about = "Monthly CEO tweet/price tracking update")]
+struct UpdateArgs {
+    #[command(subcommand)]
+    command: Option<UpdateCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+enum UpdateCommand {
+    /// Fill in the history of finished months from Yahoo prices (tweet counts stay null)
+    Backfill {
+        /// Number of months before the current one to fill in
+        #[arg(long, default_value_t = DEFAULT_BACKFILL_MONTHS, value_parser = clap::value_parser!(u32).range(1..=120))]
+        months: u32,
+    },
+}
+
+/// Where a month's baseline price comes from (MONTH_BASELINE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MonthBaseline {
+    /// The close on the month's first trading day, fetched from Yahoo history (default)
+    FirstTradingDay,
+    /// The first price this job saw in the month, however late it first ran
+    FirstRun,
+}
+
+impl std::str::FromStr for MonthBaseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "first_trading_day" => Ok(Self::FirstTradingDay),
+            "first_run" => Ok(Self::FirstRun),
+            other => Err(format!("unknown month baseline '{}'", other)),
+        }
+    }
+}
 
 /// CEO/Ticker configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +110,12 @@ struct CeoConfig {
     ceo_handle: String,
     ticker: String,
     company: String,
+    // Handles used before a rename; tracking entries under them are migrated
+    #[serde(default)]
+    handle_aliases: Vec<String>,
+    // Per-stock "flat" band (%), e.g. wider for volatile names; overrides FLAT_BAND_PCT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flat_band_pct: Option<f64>,
 }
 
 /// Tracking data for a single CEO/stock pair (MONTHLY)
@@ -29,13 +131,28 @@ struct TrackingEntry {
     // Price at the start of the month
     month_start_price: f64,
 
+    // Trading day month_start_price is from (YYYY-MM-DD)
+    #[serde(default)]
+    month_start_date: Option<String>,
+
     // Current price
     current_price: f64,
 
+    // Trading day of current_price (YYYY-MM-DD), and whether it was older
+    // than STALE_PRICE_DAYS when last updated
+    #[serde(default)]
+    price_date: Option<String>,
+    #[serde(default)]
+    price_stale: bool,
+
     // Monthly price change (%)
     monthly_price_change_pct: f64,
     price_direction: String, // "up", "down", "flat"
 
+    // Half-width (%) of the "flat" band price_direction was classified with
+    #[serde(default)]
+    flat_band_pct: Option<f64>,
+
     // Tweet tracking (THIS MONTH)
     tweets_this_month: u32,
     positive_tweets: u32,
@@ -44,6 +161,36 @@ struct TrackingEntry {
 
     // Metadata
     last_updated: String,
+
+    // Finished months, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<MonthRecord>,
+}
+
+/// Price change and tweet counts of one finished month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct MonthRecord {
+    // Month (e.g., "2026-01")
+    month: String,
+
+    // First and last close of the month, and their trading days (YYYY-MM-DD)
+    start_price: f64,
+    start_date: Option<String>,
+    end_price: f64,
+    end_date: Option<String>,
+
+    price_change_pct: f64,
+    price_direction: String, // "up", "down", "flat"
+
+    // Tweet counts; null for backfilled months, since past tweets can't be counted
+    tweets: Option<u32>,
+    positive_tweets: Option<u32>,
+    negative_tweets: Option<u32>,
+    neutral_tweets: Option<u32>,
+
+    // Filled in from price history by `backfill` rather than tracked live
+    #[serde(default)]
+    backfilled: bool,
 }
 
 /// Full tracking database
@@ -53,6 +200,11 @@ struct TrackingDatabase {
     last_updated: String,
     current_month: String,
     entries: Vec<TrackingEntry>,
+
+    // Tweet id -> when it was last alerted (RFC3339), so daily runs
+    // don't re-fire for the same impactful tweet
+    #[serde(default)]
+    alerted_tweets: HashMap<String, String>,
 }
 
 /// Yahoo Finance quote response
@@ -70,6 +222,20 @@ struct YahooChart {
 #[derive(Debug, Deserialize)]
 struct YahooResult {
     meta: YahooMeta,
+    #[serde(default)]
+    timestamp: Vec<i64>,
+    indicators: Option<YahooIndicators>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooIndicators {
+    quote: Vec<YahooQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooQuote {
+    #[serde(default)]
+    close: Vec<Option<f64>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -78,6 +244,8 @@ struct YahooMeta {
     regular_market_price: Option<f64>,
     #[serde(rename = "previousClose")]
     previous_close: Option<f64>,
+    #[serde(rename = "regularMarketTime")]
+    regular_market_time: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -93,158 +261,321 @@ fn get_current_month() -> String {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = UpdateArgs::parse();
     println!("=== CEO Tweet Tracker - Monthly Update ===\n");
 
     let current_month = get_current_month();
     println!("Current month: {}", current_month);
 
-    // Load CEO configuration
-    let config_str = std::fs::read_to_string("ceo_config.json")
-        .context("Failed to read ceo_config.json")?;
-    let configs: Vec<CeoConfig> = serde_json::from_str(&config_str)
-        .context("Failed to parse ceo_config.json")?;
+    // Load CEO configuration (CEO_CONFIG_PATH, default ceo_config.json)
+    let mut configs: Vec<CeoConfig> = storage::load_ceo_config(&storage::ceo_config_path(None))?;
+    for config in &mut configs {
+        config.ticker = models::canonical_ticker(&config.ticker);
+    }
 
     println!("Loaded {} CEO/ticker pairs", configs.len());
 
-    // Load or create tracking database
-    let mut db = load_or_create_database(&configs, &current_month)?;
+    // Prices are fetched concurrently; Gemini calls stay sequential with pauses
+    let price_concurrency = env_or("PRICE_CONCURRENCY", DEFAULT_PRICE_CONCURRENCY).max(1);
+    let gemini_delay = std::time::Duration::from_millis(env_or("GEMINI_DELAY_MS", DEFAULT_GEMINI_DELAY_MS));
+    let entry_delay = std::time::Duration::from_millis(env_or("ENTRY_DELAY_MS", DEFAULT_ENTRY_DELAY_MS));
 
-    // Check if we need to reset for a new month
-    if db.current_month != current_month {
-        println!("\nNEW MONTH detected! Resetting monthly tracking...");
-        reset_for_new_month(&mut db, &current_month);
+    // Random 0-25% longer pauses and quota waits, randomized retry backoffs (REQUEST_JITTER=true),
+    // seeded by JITTER_SEED for reproducible runs
+    let jitter_seed = std::env::var("JITTER_SEED").ok().and_then(|v| v.parse().ok());
+    jitter::init(env_or("REQUEST_JITTER", false), jitter_seed);
+
+    // Yahoo lookback (YAHOO_RANGE) and when a price counts as stale (STALE_PRICE_DAYS)
+    let yahoo_range = env_or("YAHOO_RANGE", DEFAULT_YAHOO_RANGE.to_string());
+    let stale_after = Duration::days(env_or("STALE_PRICE_DAYS", DEFAULT_STALE_PRICE_DAYS));
+
+    // Month baseline: first trading day's close (default) or the first price this job saw
+    let month_baseline = env_or("MONTH_BASELINE", MonthBaseline::FirstTradingDay);
+
+    // Monthly change within ±FLAT_BAND_PCT (default 0.5) is "flat", unless the config overrides it
+    let default_flat_band = env_or("FLAT_BAND_PCT", DEFAULT_FLAT_BAND_PCT).abs();
+    let flat_bands: HashMap<String, f64> = configs
+        .iter()
+        .filter_map(|c| Some((c.ceo_handle.to_lowercase(), c.flat_band_pct?.abs())))
+        .collect();
+
+    // Re-alert cooldown (ALERT_COOLDOWN_DAYS, default 7), for tweets posted
+    // within ALERT_LOOKBACK_DAYS (default 7), sent to ALERT_WEBHOOK_URL if set
+    let alert_cooldown = Duration::days(env_or("ALERT_COOLDOWN_DAYS", DEFAULT_ALERT_COOLDOWN_DAYS));
+    let alert_lookback = Duration::days(env_or("ALERT_LOOKBACK_DAYS", DEFAULT_ALERT_LOOKBACK_DAYS));
+    let alert_webhook = std::env::var("ALERT_WEBHOOK_URL").ok().filter(|url| !url.is_empty());
+
+    let client = yahoo_client()?;
+
+    if let Some(UpdateCommand::Backfill { months }) = args.command {
+        let flat_band_for = |handle: &str| flat_bands.get(&handle.to_lowercase()).copied().unwrap_or(default_flat_band);
+        update_tracking(TRACKING_FILE, &configs, &current_month, async |db| {
+            backfill_history(db, &client, &current_month, months, flat_band_for, entry_delay).await
+        }).await?;
+        println!("\n=== Backfill complete! ===");
+        println!("Data saved to {}", TRACKING_FILE);
+        return Ok(());
     }
 
-    println!("Tracking month: {}\n", db.current_month);
+    // The tracking file stays locked until this run's update is saved
+    let (month, entry_count) = update_tracking(TRACKING_FILE, &configs, &current_month, async |db| {
+        // Check if we need to reset for a new month
+        if db.current_month != current_month {
+            println!("\nNEW MONTH detected! Resetting monthly tracking...");
+            reset_for_new_month(db, &current_month);
+        }
+
+        println!("Tracking month: {}\n", db.current_month);
 
-    // Update each entry
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .build()?;
+        // Update each entry
+        let tickers: Vec<String> = db.entries.iter().map(|e| e.ticker.clone()).collect();
+        println!("Fetching {} prices ({} at a time)...", tickers.len(), price_concurrency);
+        let mut price_results = fetch_yahoo_prices(&client, tickers, &yahoo_range, price_concurrency).await.into_iter();
 
-    println!("Using Gemini API for AI-powered tweet counting\n");
+        println!("Using Gemini API for AI-powered tweet counting\n");
 
-    let total_entries = db.entries.len();
-    for idx in 0..total_entries {
-        let entry = &db.entries[idx];
-        print!("  [{}/{}] {} (@{})... ",
-            idx + 1,
-            total_entries,
-            entry.ticker,
-            entry.ceo_handle
-        );
-
-        let ticker = entry.ticker.clone();
-        let ceo_handle = entry.ceo_handle.clone();
-        let month_start_price = entry.month_start_price;
-
-        // Fetch current stock price from Yahoo Finance
-        match fetch_yahoo_price(&client, &ticker).await {
-            Ok(price) => {
-                let entry = &mut db.entries[idx];
-                entry.current_price = price;
-
-                if month_start_price > 0.0 {
-                    // Calculate monthly change
-                    entry.monthly_price_change_pct = ((price - month_start_price) / month_start_price) * 100.0;
-                    entry.price_direction = if entry.monthly_price_change_pct > 0.5 {
-                        "up".to_string()
-                    } else if entry.monthly_price_change_pct < -0.5 {
-                        "down".to_string()
+        let total_entries = db.entries.len();
+        for idx in 0..total_entries {
+            let entry = &db.entries[idx];
+            print!("  [{}/{}] {} (@{})... ",
+                idx + 1,
+                total_entries,
+                entry.ticker,
+                entry.ceo_handle
+            );
+
+            let ceo_handle = entry.ceo_handle.clone();
+
+            // Baseline from the month's first trading day, whenever this job first ran
+            if month_baseline == MonthBaseline::FirstTradingDay && !has_month_baseline(entry, &db.current_month) {
+                let ticker = entry.ticker.clone();
+                match fetch_month_start_price(&client, &ticker, &db.current_month).await {
+                    Ok((price, date)) => {
+                        let entry = &mut db.entries[idx];
+                        entry.month_start_price = price;
+                        entry.month_start_date = Some(date.to_string());
+                        print!("baseline ${:.2} ({}) ", price, date);
+                    }
+                    Err(e) => {
+                        // Falls back to the first price seen below
+                        db.entries[idx].month_start_price = 0.0;
+                        print!("baseline error: {} ", e);
+                    }
+                }
+            }
+
+            let month_start_price = db.entries[idx].month_start_price;
+            let flat_band = flat_bands.get(&ceo_handle.to_lowercase()).copied().unwrap_or(default_flat_band);
+
+            // Current stock price from Yahoo Finance (fetched above)
+            let price_result = price_results
+                .next()
+                .unwrap_or_else(|| Err(anyhow::anyhow!("price not fetched")));
+            match price_result {
+                Ok((price, price_date)) => {
+                    let entry = &mut db.entries[idx];
+                    entry.current_price = price;
+                    entry.price_date = Some(price_date.to_string());
+                    entry.price_stale = Utc::now().date_naive() - price_date > stale_after;
+                    entry.flat_band_pct = Some(flat_band);
+
+                    if month_start_price > 0.0 {
+                        // Calculate monthly change
+                        entry.monthly_price_change_pct = ((price - month_start_price) / month_start_price) * 100.0;
+                        entry.price_direction = price_direction(entry.monthly_price_change_pct, flat_band).to_string();
                     } else {
-                        "flat".to_string()
-                    };
-                } else {
-                    // First update this month - set start price
-                    entry.month_start_price = price;
-                    entry.monthly_price_change_pct = 0.0;
-                    entry.price_direction = "flat".to_string();
+                        // First update this month - set start price
+                        entry.month_start_price = price;
+                        entry.month_start_date = Some(price_date.to_string());
+                        entry.monthly_price_change_pct = 0.0;
+                        entry.price_direction = "flat".to_string();
+                    }
+                    print!("${:.2} ({:+.2}%) ", price, entry.monthly_price_change_pct);
+                    if entry.price_stale {
+                        print!("STALE (as of {}) ", price_date);
+                    }
+                }
+                Err(e) => {
+                    print!("price error: {} ", e);
                 }
-                print!("${:.2} ({:+.2}%) ", price, entry.monthly_price_change_pct);
             }
-            Err(e) => {
-                print!("price error: {} ", e);
+
+            // Fetch tweet count using Gemini API (Direct REST)
+            match fetch_tweet_count(&ceo_handle, &client).await {
+                Ok((total, positive, negative, neutral)) => {
+                    let entry = &mut db.entries[idx];
+                    entry.tweets_this_month = total;
+                    entry.positive_tweets = positive;
+                    entry.negative_tweets = negative;
+                    entry.neutral_tweets = neutral;
+                    println!("tweets: {} OK", total);
+                }
+                Err(e) => {
+                    println!("tweets: ERR ({})", e);
+                }
             }
+        
+            // Add delay to avoid rate limits (Genesis/Gemini free tier)
+            tokio::time::sleep(jitter::delay(gemini_delay)).await;
+
+            db.entries[idx].last_updated = Utc::now().to_rfc3339();
+            println!("OK");
+            tokio::time::sleep(jitter::delay(entry_delay)).await;
         }
 
-        // Fetch tweet count using Gemini API (Direct REST)
-        match fetch_tweet_count(&ceo_handle, &client).await {
-            Ok((total, positive, negative, neutral)) => {
-                let entry = &mut db.entries[idx];
-                entry.tweets_this_month = total;
-                entry.positive_tweets = positive;
-                entry.negative_tweets = negative;
-                entry.neutral_tweets = neutral;
-                println!("tweets: {} OK", total);
-            }
-            Err(e) => {
-                println!("tweets: ERR ({})", e);
+        // Alert on impactful tweets from the latest batch results
+        match storage::load_results() {
+            Ok(results) => {
+                let sent = send_alerts(db, &results, &client, alert_webhook.as_deref(), alert_lookback, alert_cooldown).await;
+                println!("\nSent {} tweet alerts", sent);
             }
+            Err(e) => println!("\nAlerts skipped: {}", e),
         }
-        
-        // Add delay to avoid rate limits (Genesis/Gemini free tier)
-        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
 
-        db.entries[idx].last_updated = Utc::now().to_rfc3339();
-        println!("OK");
-        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
-    }
+        // Forget alerts whose cooldown has expired so the set stays small
+        let expired = prune_alerted_tweets(db, Utc::now(), alert_cooldown);
+        if expired > 0 {
+            println!("Cleared {} expired alert cooldowns", expired);
+        }
 
-    // Save database
-    db.last_updated = Utc::now().to_rfc3339();
-    save_database(&db)?;
+        Ok((db.current_month.clone(), db.entries.len()))
+    }).await?;
 
     println!("\n=== Update complete! ===");
-    println!("Data saved to data/tracking.json");
-    println!("Month: {} | Entries: {}", db.current_month, db.entries.len());
+    println!("Data saved to {}", TRACKING_FILE);
+    println!("Month: {} | Entries: {}", month, entry_count);
 
     Ok(())
 }
 
-fn load_or_create_database(configs: &[CeoConfig], current_month: &str) -> Result<TrackingDatabase> {
-    let path = "data/tracking.json";
+/// Load (or create) the tracking database, let `update` change it, and save
+/// it, all under the tracking file's lock, as `storage::update_results` does
+///
+/// A second run started meanwhile waits for the lock (up to
+/// STORAGE_LOCK_TIMEOUT_SECS) instead of overwriting this run's update.
+/// Nothing is saved when `update` fails.
+async fn update_tracking<T>(
+    path: &str,
+    configs: &[CeoConfig],
+    current_month: &str,
+    update: impl AsyncFnOnce(&mut TrackingDatabase) -> Result<T>,
+) -> Result<T> {
+    let _lock = storage::lock(path)?;
+    let mut db = load_or_create_database(path, configs, current_month)?;
+    for (old, current) in merge_handle_aliases(&mut db, configs) {
+        println!("Merged tracking entry for @{} into @{} (handle alias)", old, current);
+    }
 
-    if let Ok(content) = std::fs::read_to_string(path) {
-        if let Ok(db) = serde_json::from_str::<TrackingDatabase>(&content) {
-            return Ok(db);
-        }
+    let outcome = update(&mut db).await?;
+    db.last_updated = Utc::now().to_rfc3339();
+    storage::write_locked(path, &storage::to_json(&db, false)?)?;
+    Ok(outcome)
+}
+
+/// The tracking database at `path`, or a new one for `configs` if there is none
+///
+/// A file that can't be read or parsed is an error rather than replaced, so
+/// a bad edit never wipes the tracked history.
+fn load_or_create_database(path: &str, configs: &[CeoConfig], current_month: &str) -> Result<TrackingDatabase> {
+    if let Some(db) = storage::load_json(path)? {
+        return Ok(db);
     }
 
     // Create new database
     println!("Creating new tracking database...");
     let now = Utc::now();
-    let entries: Vec<TrackingEntry> = configs.iter().map(|c| TrackingEntry {
+    Ok(TrackingDatabase {
+        created_at: now.to_rfc3339(),
+        last_updated: now.to_rfc3339(),
+        current_month: current_month.to_string(),
+        entries: new_entries(configs, current_month, now),
+        alerted_tweets: HashMap::new(),
+    })
+}
+
+/// Fresh, empty tracking entries for `configs`
+fn new_entries(configs: &[CeoConfig], current_month: &str, now: DateTime<Utc>) -> Vec<TrackingEntry> {
+    configs.iter().map(|c| TrackingEntry {
         ticker: c.ticker.clone(),
         company: c.company.clone(),
         ceo_handle: c.ceo_handle.clone(),
         current_month: current_month.to_string(),
         month_start_price: 0.0,
+        month_start_date: None,
         current_price: 0.0,
+        price_date: None,
+        price_stale: false,
         monthly_price_change_pct: 0.0,
         price_direction: "flat".to_string(),
+        flat_band_pct: None,
         tweets_this_month: 0,
         positive_tweets: 0,
         negative_tweets: 0,
         neutral_tweets: 0,
         last_updated: now.to_rfc3339(),
-    }).collect();
+        history: Vec::new(),
+    }).collect()
+}
 
-    Ok(TrackingDatabase {
-        created_at: now.to_rfc3339(),
-        last_updated: now.to_rfc3339(),
-        current_month: current_month.to_string(),
-        entries,
-    })
+/// Move entries tracked under an old handle onto the CEO's current handle
+///
+/// An alias entry is renamed, or dropped when the current handle already has
+/// its own entry; its finished months are then merged into that entry's
+/// history, which wins for months both tracked. Returns the `(old, current)`
+/// pairs that were merged.
+fn merge_handle_aliases(db: &mut TrackingDatabase, configs: &[CeoConfig]) -> Vec<(String, String)> {
+    let mut merged = Vec::new();
+
+    for config in configs {
+        for alias in &config.handle_aliases {
+            let Some(idx) = db.entries.iter().position(|e| e.ceo_handle.eq_ignore_ascii_case(alias)) else {
+                continue;
+            };
+            let old = db.entries[idx].ceo_handle.clone();
+            if db.entries.iter().any(|e| e.ceo_handle == config.ceo_handle) {
+                let mut history = db.entries.remove(idx).history;
+                if let Some(current) = db.entries.iter_mut().find(|e| e.ceo_handle == config.ceo_handle) {
+                    merge_history(&mut history, std::mem::take(&mut current.history));
+                    current.history = history;
+                }
+            } else {
+                db.entries[idx].ceo_handle = config.ceo_handle.clone();
+            }
+            merged.push((old, config.ceo_handle.clone()));
+        }
+    }
+
+    merged
 }
 
-/// Reset all entries for a new month
+/// Reset all entries for a new month, moving the finished month into `history`
 fn reset_for_new_month(db: &mut TrackingDatabase, new_month: &str) {
     db.current_month = new_month.to_string();
 
     for entry in &mut db.entries {
+        if entry.month_start_price > 0.0 && entry.current_price > 0.0 {
+            let finished = MonthRecord {
+                month: entry.current_month.clone(),
+                start_price: entry.month_start_price,
+                start_date: entry.month_start_date.clone(),
+                end_price: entry.current_price,
+                end_date: entry.price_date.clone(),
+                price_change_pct: entry.monthly_price_change_pct,
+                price_direction: entry.price_direction.clone(),
+                tweets: Some(entry.tweets_this_month),
+                positive_tweets: Some(entry.positive_tweets),
+                negative_tweets: Some(entry.negative_tweets),
+                neutral_tweets: Some(entry.neutral_tweets),
+                backfilled: false,
+            };
+            merge_history(&mut entry.history, vec![finished]);
+        }
+
         entry.current_month = new_month.to_string();
-        // Keep current_price as the new month's start price
+        // Keep current_price as the new month's start price (dated in the old
+        // month, so the first-trading-day baseline replaces it)
         entry.month_start_price = entry.current_price;
+        entry.month_start_date = entry.price_date.clone();
         entry.monthly_price_change_pct = 0.0;
         entry.price_direction = "flat".to_string();
         entry.tweets_this_month = 0;
@@ -254,25 +585,339 @@ fn reset_for_new_month(db: &mut TrackingDatabase, new_month: &str) {
     }
 }
 
-fn save_database(db: &TrackingDatabase) -> Result<()> {
-    std::fs::create_dir_all("data")?;
-    let json = serde_json::to_string_pretty(db)?;
-    std::fs::write("data/tracking.json", json)?;
+/// Whether a tweet is new or its last alert is older than the cooldown
+fn should_alert(db: &TrackingDatabase, tweet_id: &str, now: DateTime<Utc>, cooldown: Duration) -> bool {
+    match db.alerted_tweets.get(tweet_id).and_then(|ts| DateTime::parse_from_rfc3339(ts).ok()) {
+        Some(last) => now - last.with_timezone(&Utc) >= cooldown,
+        None => true,
+    }
+}
+
+/// Remember that a tweet was alerted so later runs respect the cooldown
+fn record_alert(db: &mut TrackingDatabase, tweet_id: &str, now: DateTime<Utc>) {
+    db.alerted_tweets.insert(tweet_id.to_string(), now.to_rfc3339());
+}
+
+/// Drop alert records past their cooldown (or unparseable), returning how many were removed
+fn prune_alerted_tweets(db: &mut TrackingDatabase, now: DateTime<Utc>, cooldown: Duration) -> usize {
+    let before = db.alerted_tweets.len();
+    db.alerted_tweets.retain(|_, ts| {
+        DateTime::parse_from_rfc3339(ts)
+            .map(|last| now - last.with_timezone(&Utc) < cooldown)
+            .unwrap_or(false)
+    });
+    before - db.alerted_tweets.len()
+}
+
+/// Impactful tweets of tracked CEOs posted within `lookback` of `now` that are
+/// outside their alert cooldown, with the result they came from
+fn pending_alerts<'a>(
+    db: &TrackingDatabase,
+    results: &'a [models::AnalysisResult],
+    now: DateTime<Utc>,
+    lookback: Duration,
+    cooldown: Duration,
+) -> Vec<(&'a models::AnalysisResult, &'a models::TweetImpact)> {
+    results
+        .iter()
+        .filter(|r| db.entries.iter().any(|e| e.ceo_handle.eq_ignore_ascii_case(&r.ceo_handle)))
+        .flat_map(|r| r.impacts.iter().map(move |impact| (r, impact)))
+        .filter(|(_, impact)| impact.is_impactful && now - impact.tweet.created_at <= lookback)
+        .filter(|(_, impact)| should_alert(db, &impact.tweet.id, now, cooldown))
+        .collect()
+}
+
+/// Alert every pending impactful tweet and record it, returning how many were sent
+///
+/// A tweet whose webhook call fails isn't recorded, so the next run retries it.
+async fn send_alerts(
+    db: &mut TrackingDatabase,
+    results: &[models::AnalysisResult],
+    client: &reqwest::Client,
+    webhook: Option<&str>,
+    lookback: Duration,
+    cooldown: Duration,
+) -> usize {
+    let now = Utc::now();
+    let pending: Vec<(String, String, models::TweetImpact)> = pending_alerts(db, results, now, lookback, cooldown)
+        .into_iter()
+        .map(|(result, impact)| (result.ceo_handle.clone(), result.ticker.clone(), impact.clone()))
+        .collect();
+
+    let mut sent = 0;
+    for (handle, ticker, impact) in pending {
+        match send_alert(client, webhook, &handle, &ticker, &impact).await {
+            Ok(()) => {
+                record_alert(db, &impact.tweet.id, now);
+                sent += 1;
+            }
+            Err(e) => println!("  alert for tweet {} failed: {}", impact.tweet.id, e),
+        }
+    }
+    sent
+}
+
+/// Print one impactful-tweet alert and POST it to the webhook, if any
+async fn send_alert(
+    client: &reqwest::Client,
+    webhook: Option<&str>,
+    handle: &str,
+    ticker: &str,
+    impact: &models::TweetImpact,
+) -> Result<()> {
+    let change = impact.change_1d.map_or("n/a".to_string(), |c| format!("{:+.2}%", c));
+    println!("  ALERT @{} ({}) 1d {}: {}", handle, ticker, change, impact.tweet.text);
+
+    let Some(url) = webhook else {
+        return Ok(());
+    };
+    let body = json!({
+        "ceo_handle": handle,
+        "ticker": ticker,
+        "tweet_id": impact.tweet.id,
+        "text": impact.tweet.text,
+        "created_at": impact.tweet.created_at.to_rfc3339(),
+        "change_1d": impact.change_1d,
+        "impact_level": impact.impact_level,
+    });
+    let response = client.post(url).json(&body).send().await.context("Failed to call alert webhook")?;
+    if !response.status().is_success() {
+        anyhow::bail!("alert webhook returned {}", response.status());
+    }
+    Ok(())
+}
+
+/// Add `records` to `history`, keeping it sorted by month
+///
+/// A month already in `history` is only replaced when the stored record was
+/// backfilled or the new one wasn't, so a backfill never overwrites a month
+/// tracked live. Returns how many records were added or replaced.
+fn merge_history(history: &mut Vec<MonthRecord>, records: Vec<MonthRecord>) -> usize {
+    let mut merged = 0;
+
+    for record in records {
+        match history.iter_mut().find(|r| r.month == record.month) {
+            Some(existing) if existing.backfilled || !record.backfilled => {
+                *existing = record;
+                merged += 1;
+            }
+            Some(_) => {}
+            None => {
+                history.push(record);
+                merged += 1;
+            }
+        }
+    }
+
+    history.sort_by(|a, b| a.month.cmp(&b.month));
+    merged
+}
+
+/// The `count` months ("YYYY-MM") before `current_month`, oldest first
+fn months_before(current_month: &str, count: u32) -> Result<Vec<String>> {
+    let first_day = NaiveDate::parse_from_str(&format!("{}-01", current_month), "%Y-%m-%d")
+        .context(format!("Invalid month: {}", current_month))?;
+    (1..=count)
+        .rev()
+        .map(|back| {
+            first_day
+                .checked_sub_months(Months::new(back))
+                .map(|d| d.format("%Y-%m").to_string())
+                .context(format!("Cannot go back {} months from {}", back, current_month))
+        })
+        .collect()
+}
+
+/// A backfilled record per month in `months` from daily closes (oldest first)
+///
+/// Each month runs from its first to its last close; months without any
+/// close are left out.
+fn monthly_records(closes: &[(f64, NaiveDate)], months: &[String], flat_band_pct: f64) -> Vec<MonthRecord> {
+    months
+        .iter()
+        .filter_map(|month| {
+            let mut in_month = closes.iter().filter(|(_, date)| date.format("%Y-%m").to_string() == *month);
+            let (start_price, start_date) = *in_month.next()?;
+            let (end_price, end_date) = in_month.next_back().copied().unwrap_or((start_price, start_date));
+            let change_pct = (end_price - start_price) / start_price * 100.0;
+
+            Some(MonthRecord {
+                month: month.clone(),
+                start_price,
+                start_date: Some(start_date.to_string()),
+                end_price,
+                end_date: Some(end_date.to_string()),
+                price_change_pct: change_pct,
+                price_direction: price_direction(change_pct, flat_band_pct).to_string(),
+                tweets: None,
+                positive_tweets: None,
+                negative_tweets: None,
+                neutral_tweets: None,
+                backfilled: true,
+            })
+        })
+        .collect()
+}
+
+/// Fill each entry's history with the `months` months before `current_month`
+/// from Yahoo daily closes
+///
+/// A failed fetch is reported and skips that entry; the others still run.
+async fn backfill_history(
+    db: &mut TrackingDatabase,
+    client: &reqwest::Client,
+    current_month: &str,
+    months: u32,
+    flat_band_for: impl Fn(&str) -> f64,
+    entry_delay: std::time::Duration,
+) -> Result<()> {
+    let months = months_before(current_month, months)?;
+    let (Some(first), Some(last)) = (months.first(), months.last()) else {
+        return Ok(());
+    };
+    println!("Backfilling {} to {} from Yahoo price history (tweet counts left null)\n", first, last);
+
+    let month_start = |month: &str| -> Result<i64> {
+        let day = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .context(format!("Invalid month: {}", month))?;
+        Ok(day.and_hms_opt(0, 0, 0).context("Invalid month start")?.and_utc().timestamp())
+    };
+    let query = format!("period1={}&period2={}", month_start(first)?, month_start(current_month)?);
+
+    let total_entries = db.entries.len();
+    for (idx, entry) in db.entries.iter_mut().enumerate() {
+        print!("  [{}/{}] {} (@{})... ", idx + 1, total_entries, entry.ticker, entry.ceo_handle);
+
+        match fetch_yahoo_chart(client, &entry.ticker, &query).await {
+            Ok(result) => {
+                let records = monthly_records(&valid_closes(&result), &months, flat_band_for(&entry.ceo_handle));
+                let found = records.len();
+                let merged = merge_history(&mut entry.history, records);
+                println!("{} of {} months with prices, {} stored", found, months.len(), merged);
+            }
+            Err(e) => println!("ERR ({})", e),
+        }
+
+        tokio::time::sleep(jitter::delay(entry_delay)).await;
+    }
+
     Ok(())
 }
 
-/// Fetch stock price from Yahoo Finance (no API key needed)
-async fn fetch_yahoo_price(client: &reqwest::Client, ticker: &str) -> Result<f64> {
+/// "up", "down" or "flat" for a monthly change, flat within ±`flat_band_pct`
+fn price_direction(change_pct: f64, flat_band_pct: f64) -> &'static str {
+    if change_pct > flat_band_pct {
+        "up"
+    } else if change_pct < -flat_band_pct {
+        "down"
+    } else {
+        "flat"
+    }
+}
+
+/// Read a setting from the environment, falling back to `default`
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Fetch prices for many tickers with at most `concurrency` requests in flight
+///
+/// Results are returned in the same order as `tickers`.
+async fn fetch_yahoo_prices(
+    client: &reqwest::Client,
+    tickers: Vec<String>,
+    range: &str,
+    concurrency: usize,
+) -> Vec<Result<(f64, NaiveDate)>> {
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (idx, ticker) in tickers.iter().enumerate() {
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        let ticker = ticker.clone();
+        let range = range.to_string();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (idx, fetch_yahoo_price(&client, &ticker, &range).await)
+        });
+    }
+
+    let mut results: Vec<Option<Result<(f64, NaiveDate)>>> = tickers.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((idx, result)) = joined {
+            results[idx] = Some(result);
+        }
+    }
+
+    results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("price task failed"))))
+        .collect()
+}
+
+/// Whether the entry's baseline is already dated inside `month` ("YYYY-MM")
+fn has_month_baseline(entry: &TrackingEntry, month: &str) -> bool {
+    entry.month_start_price > 0.0
+        && entry.month_start_date.as_deref().is_some_and(|d| d.starts_with(month))
+}
+
+/// Close on the first trading day of `month` ("YYYY-MM") and that day
+async fn fetch_month_start_price(client: &reqwest::Client, ticker: &str, month: &str) -> Result<(f64, NaiveDate)> {
+    let first_day = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .context(format!("Invalid month: {}", month))?;
+    let start = first_day.and_hms_opt(0, 0, 0).context("Invalid month start")?.and_utc();
+    let end = start + Duration::days(MONTH_START_SEARCH_DAYS);
+
+    let query = format!("period1={}&period2={}", start.timestamp(), end.timestamp());
+    let result = fetch_yahoo_chart(client, ticker, &query).await?;
+    first_close(&result, first_day).context(format!("No trading day found from {}", first_day))
+}
+
+/// Fetch the latest stock price and its trading day from Yahoo Finance (no API key needed)
+async fn fetch_yahoo_price(client: &reqwest::Client, ticker: &str, range: &str) -> Result<(f64, NaiveDate)> {
+    let result = fetch_yahoo_chart(client, ticker, &format!("range={}", range)).await?;
+
+    if let Some(latest) = latest_close(&result) {
+        return Ok(latest);
+    }
+
+    // No usable bar: fall back to the quote metadata, dated by its market time
+    let price = result.meta.regular_market_price
+        .or(result.meta.previous_close)
+        .context("No price in Yahoo Finance response")?;
+    let date = result.meta.regular_market_time
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .context("No date for Yahoo Finance price")?
+        .date_naive();
+    Ok((price, date))
+}
+
+/// Client for Yahoo and Gemini requests, with a browser user agent Yahoo accepts
+fn yahoo_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+/// Daily chart for `ticker`, with `query` selecting the span (`range=` or `period1=&period2=`)
+async fn fetch_yahoo_chart(client: &reqwest::Client, ticker: &str, query: &str) -> Result<YahooResult> {
     let url = format!(
-        "https://query1.finance.yahoo.com/v8/finance/chart/{}?interval=1d&range=1d",
-        ticker
+        "https://query1.finance.yahoo.com/v8/finance/chart/{}?interval=1d&{}",
+        ticker, query
     );
 
+    http::wait_for_quota(YAHOO_QUOTA, false).await;
     let response = client
         .get(&url)
         .send()
         .await
         .context("Failed to fetch from Yahoo Finance")?;
+    http::record_quota(YAHOO_QUOTA, response.headers(), false);
 
     if !response.status().is_success() {
         anyhow::bail!("Yahoo Finance returned {}", response.status());
@@ -285,13 +930,33 @@ async fn fetch_yahoo_price(client: &reqwest::Client, ticker: &str) -> Result<f64
         anyhow::bail!("Yahoo Finance error: {}", error.description);
     }
 
-    let result = data.chart.result
+    data.chart.result
         .and_then(|r| r.into_iter().next())
-        .context("No data in Yahoo Finance response")?;
+        .context("No data in Yahoo Finance response")
+}
 
-    result.meta.regular_market_price
-        .or(result.meta.previous_close)
-        .context("No price in Yahoo Finance response")
+/// Daily closes with a valid price, as (close, trading day), oldest first
+fn valid_closes(result: &YahooResult) -> Vec<(f64, NaiveDate)> {
+    let Some(quote) = result.indicators.as_ref().and_then(|i| i.quote.first()) else {
+        return Vec::new();
+    };
+    result.timestamp.iter()
+        .zip(&quote.close)
+        .filter_map(|(t, close)| {
+            let close = close.filter(|c| c.is_finite() && *c > 0.0)?;
+            Some((close, DateTime::from_timestamp(*t, 0)?.date_naive()))
+        })
+        .collect()
+}
+
+/// First daily bar on or after `from` with a valid close
+fn first_close(result: &YahooResult, from: NaiveDate) -> Option<(f64, NaiveDate)> {
+    valid_closes(result).into_iter().find(|(_, date)| *date >= from)
+}
+
+/// Most recent daily bar with a valid close, as (close, trading day)
+fn latest_close(result: &YahooResult) -> Option<(f64, NaiveDate)> {
+    valid_closes(result).pop()
 }
 
 use serde_json::json;
@@ -330,11 +995,13 @@ async fn fetch_tweet_count(handle: &str, client: &reqwest::Client) -> Result<(u3
         }]
     });
     
+    http::wait_for_quota(GEMINI_QUOTA, false).await;
     let response = client.post(&url)
         .json(&body)
         .send()
         .await
         .context("Failed to call Gemini API")?;
+    http::record_quota(GEMINI_QUOTA, response.headers(), false);
         
     if !response.status().is_success() {
         let status = response.status();
@@ -345,19 +1012,63 @@ async fn fetch_tweet_count(handle: &str, client: &reqwest::Client) -> Result<(u3
     
     let json_resp: serde_json::Value = response.json().await
         .context("Failed to parse Gemini response")?;
-        
-    // Extract text from: candidates[0].content.parts[0].text
-    if let Some(text) = json_resp["candidates"][0]["content"]["parts"][0]["text"].as_str() {
-        // Try to parse the first number found in the response
-        let count = text
-            .split_whitespace()
-            .find_map(|word| word.trim().parse::<u32>().ok())
-            .unwrap_or(0);
-        
-        return Ok((count, 0, 0, count));
+    
+    match gemini_reply(&json_resp) {
+        GeminiReply::Text(text) => {
+            let count = extract_count(&text)
+                .with_context(|| format!("No number in Gemini reply: {:?}", text))?;
+            Ok((count, 0, 0, count))
+        }
+        GeminiReply::Blocked(reason) => {
+            eprintln!("Gemini blocked the request for @{}: {}", handle, reason);
+            anyhow::bail!("Gemini response blocked ({})", reason)
+        }
+        GeminiReply::Empty => anyhow::bail!("Gemini returned no text"),
+    }
+}
+
+/// What a generateContent response boils down to
+#[derive(Debug, PartialEq)]
+enum GeminiReply {
+    /// All text parts of the first candidate that has any, concatenated
+    Text(String),
+    /// Prompt or candidate blocked (e.g. SAFETY), with the reason
+    Blocked(String),
+    /// No text and no block reason
+    Empty,
+}
+
+/// Finish reasons that mean the answer was withheld rather than empty
+const BLOCKED_FINISH_REASONS: [&str; 5] = ["SAFETY", "RECITATION", "BLOCKLIST", "PROHIBITED_CONTENT", "SPII"];
+
+/// Read a Gemini response without assuming a fixed shape
+fn gemini_reply(response: &serde_json::Value) -> GeminiReply {
+    if let Some(reason) = response["promptFeedback"]["blockReason"].as_str() {
+        return GeminiReply::Blocked(reason.to_string());
+    }
+    
+    let candidates = response["candidates"].as_array().map(Vec::as_slice).unwrap_or_default();
+    for candidate in candidates {
+        let parts = candidate["content"]["parts"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let text: String = parts.iter().filter_map(|p| p["text"].as_str()).collect();
+        if !text.trim().is_empty() {
+            return GeminiReply::Text(text);
+        }
     }
     
-    Ok((0, 0, 0, 0))
+    candidates
+        .iter()
+        .filter_map(|c| c["finishReason"].as_str())
+        .find(|reason| BLOCKED_FINISH_REASONS.contains(reason))
+        .map_or(GeminiReply::Empty, |reason| GeminiReply::Blocked(reason.to_string()))
+}
+
+/// First number in the text, accepting thousands separators ("≈1,200" -> 1200)
+fn extract_count(text: &str) -> Option<u32> {
+    static NUMBER: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let number = NUMBER.get_or_init(|| regex::Regex::new(r"\d{1,3}(?:,\d{3})+|\d+").unwrap());
+    
+    number.find(text)?.as_str().replace(',', "").parse().ok()
 }
 
 /// Simple keyword-based sentiment analysis
@@ -393,3 +1104,223 @@ fn analyze_sentiment(text: &str) -> f64 {
 
     score
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::test_support::test_tweet;
+    use serde_json::json;
+
+    #[test]
+    fn test_price_direction_uses_flat_band() {
+        assert_eq!(price_direction(0.8, DEFAULT_FLAT_BAND_PCT), "up");
+        assert_eq!(price_direction(0.8, 2.0), "flat");
+        assert_eq!(price_direction(-2.5, 2.0), "down");
+        assert_eq!(price_direction(0.5, 0.5), "flat");
+    }
+
+    #[test]
+    fn test_latest_close_skips_missing_bars() {
+        // Fri 2024-03-08 and Mon 2024-03-11, with Monday's bar not closed yet
+        let result: YahooResult = serde_json::from_value(json!({
+            "meta": {"regularMarketPrice": 101.0},
+            "timestamp": [1709906400, 1710165600],
+            "indicators": {"quote": [{"close": [100.5, null]}]}
+        })).unwrap();
+
+        let (price, date) = latest_close(&result).unwrap();
+        assert_eq!(price, 100.5);
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 3, 8).unwrap());
+    }
+
+    #[test]
+    fn test_first_close_finds_first_trading_day() {
+        // Fri 2024-02-29, then Fri 2024-03-01 (not closed) and Mon 2024-03-04
+        let result: YahooResult = serde_json::from_value(json!({
+            "meta": {"regularMarketPrice": 103.0},
+            "timestamp": [1709217000, 1709303400, 1709562600],
+            "indicators": {"quote": [{"close": [99.0, null, 102.0]}]}
+        })).unwrap();
+        let march = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert_eq!(first_close(&result, march), Some((102.0, NaiveDate::from_ymd_opt(2024, 3, 4).unwrap())));
+        assert_eq!("first_run".parse(), Ok(MonthBaseline::FirstRun));
+    }
+
+    #[test]
+    fn test_backfill_groups_closes_by_month_and_keeps_tracked_months() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let closes = [(100.0, day(1, 2)), (104.0, day(1, 31)), (110.0, day(2, 1)), (99.0, day(2, 29))];
+
+        let months = months_before("2024-04", 3).unwrap();
+        assert_eq!(months, ["2024-01", "2024-02", "2024-03"]);
+        assert_eq!(months_before("2025-01", 1).unwrap(), ["2024-12"]);
+
+        // March has no closes and is left out
+        let records = monthly_records(&closes, &months, 1.0);
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].start_price, records[0].end_price), (100.0, 104.0));
+        assert_eq!(records[0].end_date.as_deref(), Some("2024-01-31"));
+        assert_eq!(records[0].price_direction, "up");
+        assert_eq!(records[1].price_direction, "down");
+        assert!(records.iter().all(|r| r.backfilled && r.tweets.is_none()));
+
+        // A month tracked live keeps its tweet counts
+        let tracked = MonthRecord { backfilled: false, tweets: Some(12), ..records[1].clone() };
+        let mut history = vec![tracked.clone()];
+        assert_eq!(merge_history(&mut history, records.clone()), 1);
+        assert_eq!(history.iter().map(|r| r.month.as_str()).collect::<Vec<_>>(), ["2024-01", "2024-02"]);
+        assert_eq!(history[1], tracked);
+    }
+
+    #[test]
+    fn test_alias_merge_keeps_old_handle_history() {
+        let month = |month: &str, tweets| MonthRecord {
+            month: month.to_string(),
+            start_price: 100.0,
+            start_date: None,
+            end_price: 110.0,
+            end_date: None,
+            price_change_pct: 10.0,
+            price_direction: "up".to_string(),
+            tweets: Some(tweets),
+            positive_tweets: None,
+            negative_tweets: None,
+            neutral_tweets: None,
+            backfilled: false,
+        };
+        let entry = |handle: &str, history| TrackingEntry {
+            ticker: "TSLA".to_string(),
+            company: "Tesla".to_string(),
+            ceo_handle: handle.to_string(),
+            current_month: "2024-04".to_string(),
+            month_start_price: 100.0,
+            month_start_date: None,
+            current_price: 100.0,
+            price_date: None,
+            price_stale: false,
+            monthly_price_change_pct: 0.0,
+            price_direction: "flat".to_string(),
+            flat_band_pct: None,
+            tweets_this_month: 0,
+            positive_tweets: 0,
+            negative_tweets: 0,
+            neutral_tweets: 0,
+            last_updated: String::new(),
+            history,
+        };
+        let mut db = TrackingDatabase {
+            created_at: String::new(),
+            last_updated: String::new(),
+            current_month: "2024-04".to_string(),
+            entries: vec![
+                entry("oldhandle", vec![month("2024-01", 5), month("2024-02", 6)]),
+                entry("newhandle", vec![month("2024-02", 9), month("2024-03", 7)]),
+            ],
+            alerted_tweets: HashMap::new(),
+        };
+        let configs = [CeoConfig {
+            ceo_handle: "newhandle".to_string(),
+            ticker: "TSLA".to_string(),
+            company: "Tesla".to_string(),
+            handle_aliases: vec!["oldhandle".to_string()],
+            flat_band_pct: None,
+        }];
+
+        assert_eq!(merge_handle_aliases(&mut db, &configs), [("oldhandle".to_string(), "newhandle".to_string())]);
+        assert_eq!(db.entries.len(), 1);
+        let tweets: Vec<_> = db.entries[0].history.iter().map(|r| (r.month.as_str(), r.tweets)).collect();
+        assert_eq!(tweets, [("2024-01", Some(5)), ("2024-02", Some(9)), ("2024-03", Some(7))]);
+    }
+
+    #[test]
+    fn test_tweet_alerted_within_cooldown_is_suppressed() {
+        let now = Utc::now();
+        let config = CeoConfig {
+            ceo_handle: "elonmusk".to_string(),
+            ticker: "TSLA".to_string(),
+            company: "Tesla".to_string(),
+            handle_aliases: Vec::new(),
+            flat_band_pct: None,
+        };
+        let mut db = TrackingDatabase {
+            created_at: String::new(),
+            last_updated: String::new(),
+            current_month: "2024-04".to_string(),
+            entries: Vec::new(),
+            alerted_tweets: HashMap::new(),
+        };
+        db.entries = new_entries(&[config], "2024-04", now);
+
+        let mut result = models::AnalysisResult::new("elonmusk".to_string(), "TSLA".to_string(), now, now);
+        result.impacts = ["1", "2"]
+            .iter()
+            .map(|id| {
+                let mut impact = models::TweetImpact::new(test_tweet(id, "", now - Duration::days(1)));
+                impact.is_impactful = true;
+                impact
+            })
+            .collect();
+        let results = [result];
+        let (lookback, cooldown) = (Duration::days(7), Duration::days(7));
+
+        record_alert(&mut db, "1", now - Duration::days(2));
+        let pending: Vec<&str> = pending_alerts(&db, &results, now, lookback, cooldown)
+            .iter()
+            .map(|(_, impact)| impact.tweet.id.as_str())
+            .collect();
+        assert_eq!(pending, ["2"]);
+
+        // Once the cooldown has passed, the tweet may be alerted again
+        assert!(should_alert(&db, "1", now + Duration::days(5), cooldown));
+        assert_eq!(prune_alerted_tweets(&mut db, now + Duration::days(5), cooldown), 1);
+    }
+
+    #[test]
+    fn test_unparseable_tracking_file_is_not_replaced() {
+        let dir = std::env::temp_dir().join(format!("tracking-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tracking.json");
+        let path = path.to_str().unwrap();
+        let config = CeoConfig {
+            ceo_handle: "elonmusk".to_string(),
+            ticker: "TSLA".to_string(),
+            company: "Tesla".to_string(),
+            handle_aliases: Vec::new(),
+            flat_band_pct: None,
+        };
+
+        let db = load_or_create_database(path, std::slice::from_ref(&config), "2024-04").unwrap();
+        assert_eq!(db.entries.len(), 1);
+
+        std::fs::write(path, "{ truncated").unwrap();
+        assert!(load_or_create_database(path, &[config], "2024-04").is_err());
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "{ truncated");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_blocked_gemini_response() {
+        let response = json!({
+            "candidates": [{"finishReason": "SAFETY", "index": 0}]
+        });
+
+        assert_eq!(gemini_reply(&response), GeminiReply::Blocked("SAFETY".to_string()));
+    }
+
+    #[test]
+    fn test_multi_part_reply_with_comma_formatted_number() {
+        let response = json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "About "}, {"text": "≈1,200 tweets"}]}
+            }]
+        });
+
+        let GeminiReply::Text(text) = gemini_reply(&response) else {
+            panic!("expected text reply");
+        };
+        assert_eq!(extract_count(&text), Some(1200));
+        assert_eq!(extract_count("42"), Some(42));
+        assert_eq!(extract_count("no idea"), None);
+    }
+}
@@ -1,7 +1,15 @@
 #[path = "../analysis.rs"]
 mod analysis;
+#[path = "../http.rs"]
+mod http;
+#[path = "../jitter.rs"]
+mod jitter;
+#[path = "../lexicon.rs"]
+mod lexicon;
 #[path = "../models.rs"]
 mod models;
+#[path = "../news.rs"]
+mod news;
 #[path = "../prolog.rs"]
 mod prolog;
 #[path = "../stocks.rs"]
@@ -12,21 +20,233 @@ mod storage;
 mod twitter;
 
 use anyhow::Result;
-use models::AnalysisResult;
+use chrono::Utc;
+use clap::Parser;
+use lexicon::Lexicon;
+use models::{AnalysisResult, PricePoint};
 use serde::Deserialize;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
+/// Days of tweets and prices analyzed per CEO
+const DEFAULT_DAYS: u32 = 90;
+
+/// Batch runner options
+#[derive(Parser, Debug)]
+#[command(name = "run_batch", about = "Analyze every CEO in ceo_config.json")]
+struct BatchArgs {
+    /// Don't refetch tweets; backfill forward prices for stored results whose
+    /// 1d/3d windows have completed since they were saved
+    #[arg(long)]
+    replay: bool,
+
+    /// Don't fetch anything; re-score stored tweets with each CEO's lexicon
+    /// and recompute their statistics against the stored raw prices
+    #[arg(long, conflicts_with = "replay")]
+    rescore: bool,
+
+    /// Collapse repeated (copy-pasted) tweets: off, exact, or normalized
+    #[arg(long, value_enum, default_value_t = models::RepeatMatching::Off, value_name = "MODE")]
+    collapse_repeats: models::RepeatMatching,
+
+    /// Credit moves in overlapping lookahead windows to all tweets, the first,
+    /// or the most-engaged one
+    #[arg(long, value_enum, default_value_t = models::AttributionMode::All, value_name = "MODE")]
+    attribution: models::AttributionMode,
+
+    /// Write data/*.json without indentation (smaller, but noisier diffs)
+    #[arg(long)]
+    compact_json: bool,
+
+    /// CSV of known news events (date,headline) to flag confounded tweets
+    #[arg(long, value_name = "PATH")]
+    news_file: Option<String>,
+
+    /// Leave tweets whose window overlaps a news event out of the correlation
+    #[arg(long, requires = "news_file")]
+    exclude_news: bool,
+
+    /// Leave neutral tweets (|sentiment| <= --neutral-band) out of the
+    /// correlation; the 1d correlation with them is still stored
+    #[arg(long)]
+    exclude_neutral_from_correlation: bool,
+
+    /// Largest |sentiment| treated as neutral by --exclude-neutral-from-correlation
+    #[arg(long, default_value_t = 0.0, requires = "exclude_neutral_from_correlation", value_name = "X")]
+    neutral_band: f64,
+
+    /// Forward window (days) for the engagement vs absolute move correlation (default 1)
+    #[arg(long, value_name = "DAYS")]
+    engagement_window: Option<i64>,
+
+    /// Only process these handles (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "HANDLES")]
+    only: Vec<String>,
+
+    /// Skip these handles (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "HANDLES")]
+    skip: Vec<String>,
+
+    /// Continue an interrupted run: reuse results of CEOs it already completed
+    /// (from data/batch_checkpoint.json) and analyze only the rest
+    #[arg(long, conflicts_with_all = ["replay", "rescore"])]
+    resume: bool,
+
+    /// Exit nonzero when fewer than this % of the processed CEOs produced a
+    /// result (tweets and prices found), e.g. 80
+    #[arg(long, conflicts_with_all = ["replay", "rescore"], value_name = "PCT")]
+    min_success_rate: Option<f64>,
+
+    /// Idle HTTP connections kept alive per API host for reuse
+    #[arg(long, default_value_t = http::DEFAULT_POOL_SIZE, value_name = "N")]
+    http_pool_size: usize,
+
+    /// Stretch each pause between API requests (and quota waits) by a random
+    /// 0-25% and randomize retry backoffs, so scheduled runs don't hit the
+    /// APIs in lockstep
+    #[arg(long)]
+    request_jitter: bool,
+
+    /// Seed for --request-jitter (reproducible delays)
+    #[arg(long, requires = "request_jitter", value_name = "N")]
+    jitter_seed: Option<u64>,
+
+    /// CEO config file (default: CEO_CONFIG_PATH, then ceo_config.json)
+    #[arg(long, value_name = "PATH")]
+    config_path: Option<String>,
+
+    /// Domain -> lexicon file mapping for CEOs with a `domain` (default:
+    /// LEXICONS_PATH, then lexicons.json)
+    #[arg(long, value_name = "PATH")]
+    lexicons: Option<String>,
+
+    /// Store at most N impacts per CEO (impactful, then biggest moves, then
+    /// most engaged); statistics still cover every tweet
+    #[arg(long, value_name = "N")]
+    store_max_impacts: Option<usize>,
+
+    /// Retrieve at most N impactful_tweet/1 solutions per CEO; tweets past
+    /// the cap stay unclassified
+    #[arg(long, value_name = "N")]
+    prolog_max_solutions: Option<usize>,
+
+    /// Print how long each CEO's fetch, analysis and rule stages took, plus
+    /// totals over the batch, and store them on each result
+    #[arg(long)]
+    timings: bool,
+
+    /// If the API rejects the token (auth error or quota exhausted), fetch
+    /// timelines through the scraper instead (needs TWITTER_USERNAME and
+    /// TWITTER_PASSWORD as well)
+    #[arg(long)]
+    twitter_fallback: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct CeoConfig {
     ceo_handle: String,
     ticker: String,
     #[allow(dead_code)]
     company: String,
+    /// Handles this CEO used before a rename; their stored data is merged in
+    #[serde(default)]
+    handle_aliases: Vec<String>,
+    /// Shares outstanding, for market cap changes in USD
+    #[serde(default)]
+    shares_outstanding: Option<u64>,
+    /// Sector whose lexicon scores this CEO's tweets (None = built-in lexicon)
+    #[serde(default)]
+    domain: Option<String>,
+}
+
+/// Lowercased old handle -> current handle, from every config entry
+fn alias_map(configs: &[CeoConfig]) -> HashMap<String, String> {
+    configs
+        .iter()
+        .flat_map(|c| c.handle_aliases.iter().map(move |old| (old.to_lowercase(), c.ceo_handle.clone())))
+        .collect()
+}
+
+/// Lowercased handle -> the lexicon of its entry's domain, for every config entry
+fn lexicon_map(configs: &[CeoConfig], lexicons: &lexicon::DomainLexicons) -> Result<HashMap<String, Arc<Lexicon>>> {
+    configs
+        .iter()
+        .map(|c| {
+            let lexicon = lexicons
+                .get(c.domain.as_deref())
+                .map_err(|e| e.context(format!("@{} in the CEO config", c.ceo_handle)))?;
+            Ok((c.ceo_handle.to_lowercase(), lexicon))
+        })
+        .collect()
+}
+
+/// Lexicon for `handle`; the built-in one for handles no longer in the config
+fn lexicon_for(lexicons: &HashMap<String, Arc<Lexicon>>, handle: &str) -> Arc<Lexicon> {
+    lexicons.get(&handle.to_lowercase()).cloned().unwrap_or_default()
+}
+
+/// Print one line per alias merge
+fn report_alias_merges(merged: &[(String, String)]) {
+    for (old, current) in merged {
+        println!("  Merged stored data for @{} into @{} (handle alias)", old, current);
+    }
+}
+
+/// Fold stored results under old handles into the current ones
+///
+/// A result that took in another handle's tweets has its statistics
+/// recomputed against its stored raw prices, so they cover the merged
+/// tweets. Trimmed results keep theirs, since their full tweet set is gone.
+fn merge_aliases(
+    results: &mut Vec<AnalysisResult>,
+    aliases: &HashMap<String, String>,
+    lexicons: &HashMap<String, Arc<Lexicon>>,
+) -> Vec<(String, String)> {
+    let existing: HashSet<String> = results.iter().map(|r| r.ceo_handle.to_lowercase()).collect();
+    let merged = storage::merge_handle_aliases(results, aliases);
+
+    for (old, current) in &merged {
+        if !existing.contains(&current.to_lowercase()) {
+            continue;
+        }
+        let Some(result) = results.iter_mut().find(|r| r.ceo_handle.eq_ignore_ascii_case(current)) else {
+            continue;
+        };
+        if result.impacts_trimmed > 0 {
+            println!("    WARNING: @{} impacts were trimmed; its statistics don't cover @{}'s tweets", current, old);
+            continue;
+        }
+        match storage::load_raw_fetch(current) {
+            Ok(Some(raw)) => {
+                let options = analysis::stored_options(result, lexicon_for(lexicons, current));
+                analysis::recompute_statistics(result, &raw.prices, &options);
+            }
+            Ok(None) => println!("    WARNING: @{} has no stored raw data; statistics not recomputed", current),
+            Err(e) => eprintln!("    WARNING: Failed to load raw data for @{}: {}", current, e),
+        }
+    }
+    merged
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = BatchArgs::parse();
+    http::init(args.http_pool_size)?;
+    jitter::init(args.request_jitter, args.jitter_seed);
+    if args.min_success_rate.is_some_and(|pct| !(0.0..=100.0).contains(&pct)) {
+        anyhow::bail!("--min-success-rate must be between 0 and 100");
+    }
+    if args.prolog_max_solutions == Some(0) {
+        anyhow::bail!("--prolog-max-solutions must be at least 1");
+    }
+    if !(0.0..1.0).contains(&args.neutral_band) {
+        anyhow::bail!("--neutral-band must be at least 0 and below 1");
+    }
+    if args.engagement_window.is_some_and(|w| w < 0) {
+        anyhow::bail!("--engagement-window can't be negative");
+    }
     println!("Starting CEO Tweet Analyzer Batch Runner...");
 
     // Get API keys
@@ -37,112 +257,534 @@ async fn main() -> Result<()> {
     if twitter_token.is_none() && (twitter_username.is_none() || twitter_password.is_none()) {
          println!("WARNING: No Twitter credentials found (API token or username/password).");
     }
+    if args.twitter_fallback && (twitter_token.is_none() || twitter_username.is_none() || twitter_password.is_none()) {
+        println!("WARNING: --twitter-fallback needs both TWITTER_BEARER_TOKEN and TWITTER_USERNAME/TWITTER_PASSWORD; fallback disabled.");
+    }
 
-    let stock_api_key = std::env::var("STOCK_API_KEY")
-        .expect("STOCK_API_KEY environment variable not set");
+    // Optional overrides for proxies / self-hosted compatible endpoints
+    let stock_api_base = std::env::var("STOCK_API_BASE")
+        .unwrap_or_else(|_| stocks::ALPHA_VANTAGE_BASE.to_string());
+    let twitter_api_base = std::env::var("TWITTER_API_BASE")
+        .unwrap_or_else(|_| twitter::TWITTER_API_BASE.to_string());
 
     // Load configuration
-    // ... (lines 38-48 match existing, skipping for brevity in replacement if possible, but I must replace contiguous block)
-    let config_str = std::fs::read_to_string("ceo_config.json")
-        .expect("Failed to read ceo_config.json");
-    let configs: Vec<CeoConfig> = serde_json::from_str(&config_str)
-        .expect("Failed to parse configurations");
+    let mut configs: Vec<CeoConfig> = storage::load_ceo_config(&storage::ceo_config_path(args.config_path.as_deref()))?;
+    for config in &mut configs {
+        config.ticker = models::canonical_ticker(&config.ticker);
+    }
+    let aliases = alias_map(&configs);
+    let lexicons = lexicon_map(
+        &configs,
+        &lexicon::DomainLexicons::load(&lexicon::lexicons_path(args.lexicons.as_deref()))?,
+    )?;
+
+    let filter = models::HandleFilter { only: args.only.clone(), skip: args.skip.clone() };
+    let known: Vec<&str> = configs.iter().map(|c| c.ceo_handle.as_str()).collect();
+    for handle in filter.unknown_handles(&known) {
+        println!("WARNING: --only handle @{} is not in ceo_config.json", handle);
+    }
+
+    let compact_json = args.compact_json;
+    let facts_filter = prolog::FactsFilter { max_solutions: args.prolog_max_solutions, ..Default::default() };
+    if args.rescore {
+        return rescore_stored_results(&aliases, &lexicons, &filter, &facts_filter, compact_json);
+    }
+
+    let stock_api_key = std::env::var("STOCK_API_KEY")
+        .expect("STOCK_API_KEY environment variable not set");
+    if args.replay {
+        return replay_stored_results(&stock_api_key, &stock_api_base, &aliases, &lexicons, &filter, &facts_filter, compact_json).await;
+    }
 
     println!("Loaded {} CEO/ticker pairs", configs.len());
+    let configs: Vec<&CeoConfig> = configs.iter().filter(|c| filter.selects(&c.ceo_handle)).collect();
+    if configs.len() < known.len() {
+        println!("Processing {} of them (--only/--skip)", configs.len());
+    }
+
+    // Stored results under an old handle carry forward into the current one
+    let mut stored = storage::load_results().unwrap_or_default();
+    let merged_aliases = merge_aliases(&mut stored, &aliases, &lexicons);
+    report_alias_merges(&merged_aliases);
 
+    // Completed CEOs are checkpointed after each one, so a failed run can --resume
     let mut results = Vec::new();
-    let days = 90;
+    if args.resume {
+        results = storage::load_checkpoint()?;
+        results.retain(|r| configs.iter().any(|c| c.ceo_handle.eq_ignore_ascii_case(&r.ceo_handle)));
+        // Sentiments from another lexicon aren't comparable; analyze those again
+        let before = results.len();
+        results.retain(|r| !analysis::scored_with_other_lexicon(r, &lexicon_for(&lexicons, &r.ceo_handle)));
+        if results.len() < before {
+            println!("  Discarding {} checkpointed results scored with a different lexicon", before - results.len());
+        }
+        println!("Resuming: reusing {} completed results from {}", results.len(), storage::BATCH_CHECKPOINT_FILE);
+    }
+    let mut failed = 0;
+    let days = DEFAULT_DAYS;
+    let options = analysis::AnalysisOptions {
+        news_events: match &args.news_file {
+            Some(path) => news::load_news_csv(path)?,
+            None => Vec::new(),
+        },
+        exclude_news_from_correlation: args.exclude_news,
+        exclude_neutral_from_correlation: args.exclude_neutral_from_correlation,
+        neutral_band: args.neutral_band,
+        engagement_window: args.engagement_window,
+        repeat_matching: args.collapse_repeats,
+        attribution: args.attribution,
+        ..Default::default()
+    };
+    let tweet_source = if twitter_token.is_some() { "twitter_api" } else { "scraper" };
 
     // Process each CEO (limit to first 25)
-    for (idx, config) in configs.iter().take(25).enumerate() {
+    let batch_size = configs.len().min(25);
+    let mut batch_timings: Vec<Vec<models::StageTiming>> = Vec::new();
+    for (idx, config) in configs.iter().take(batch_size).enumerate() {
         println!(
-            "  [{}/25] Analyzing @{} / {}...",
+            "  [{}/{}] Analyzing @{} / {}...",
             idx + 1,
+            batch_size,
             config.ceo_handle,
             config.ticker
         );
 
-        // Fetch tweets
-        let tweets = match twitter::fetch_tweets(
+        if results.iter().any(|r: &AnalysisResult| r.ceo_handle.eq_ignore_ascii_case(&config.ceo_handle)) {
+            println!("    Already completed (checkpoint), skipping");
+            continue;
+        }
+
+        // Fetch tweets, only the ones newer than the last stored fetch when possible
+        let mut timings = Vec::new();
+        let started = Instant::now();
+        let cached_tweets = storage::load_raw_fetch(&config.ceo_handle)
+            .ok()
+            .flatten()
+            .map(|raw| raw.cached_timeline(days))
+            .unwrap_or_default();
+        let fetch = match twitter::fetch_tweets(
             &config.ceo_handle,
+            &twitter_api_base,
             twitter_token.as_deref(),
             twitter_username.as_deref(),
             twitter_password.as_deref(),
-            days,
+            &twitter::FetchOptions {
+                cached_tweets,
+                scraper_fallback: args.twitter_fallback,
+                ..twitter::FetchOptions::days(days)
+            },
             false,
         ).await {
             Ok(t) => t,
             Err(e) => {
                 eprintln!("    WARNING: Failed to fetch tweets: {}", e);
+                failed += 1;
                 continue;
             }
         };
 
+        timings.push(models::StageTiming::since("fetch_tweets", started));
+        let follower_count = fetch.follower_count;
+        let tweet_source = if fetch.fell_back_to_scraper { "scraper" } else { tweet_source };
+        let mut tweets = fetch.tweets;
+        if fetch.dropped_without_timestamp > 0 {
+            println!("    WARNING: Skipped {} tweets with no timestamp", fetch.dropped_without_timestamp);
+        }
+        if fetch.stop == twitter::FetchStop::MaxTweets {
+            println!("    WARNING: Tweet limit hit before the {}-day window was covered", days);
+        }
+
+        // Tweets stored under a previous handle that this fetch didn't return
+        if merged_aliases.iter().any(|(_, current)| current == &config.ceo_handle) {
+            if let Some(previous) = stored.iter().find(|r| r.ceo_handle == config.ceo_handle) {
+                let fetched: HashSet<String> = tweets.iter().map(|t| t.id.clone()).collect();
+                let carried: Vec<_> = previous.impacts.iter()
+                    .filter(|i| !fetched.contains(&i.tweet.id))
+                    .map(|i| i.tweet.clone())
+                    .collect();
+                println!("    Carried forward {} stored tweets from previous handles", carried.len());
+                tweets.extend(carried);
+            }
+        }
+
         if tweets.is_empty() {
             println!("    WARNING: No tweets found");
+            failed += 1;
             continue;
         }
 
         // Fetch stock prices
-        let prices = match stocks::fetch_prices(
+        // Shared cache: CEOs of the same ticker reuse one fetch within the run
+        let started = Instant::now();
+        let prices = match stocks::fetch_prices_shared(
             &config.ticker,
             &stock_api_key,
+            &stock_api_base,
             days,
+            stocks::DEFAULT_PRICE_BUFFER_DAYS,
+            Duration::MAX,
             false,
         ).await {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("    WARNING: Failed to fetch prices: {}", e);
+                failed += 1;
                 continue;
             }
         };
 
         if prices.is_empty() {
             println!("    WARNING: No price data found");
+            failed += 1;
             continue;
         }
+        timings.push(models::StageTiming::since("fetch_prices", started));
+
+        // Keep what was fetched so the web server's /api/recompute can re-run it offline
+        let raw = models::RawFetch {
+            ceo_handle: config.ceo_handle.clone(),
+            ticker: config.ticker.clone(),
+            fetched_at: Utc::now(),
+            days,
+            tweet_source: tweet_source.to_string(),
+            stock_provider: stock_api_base.clone(),
+            follower_count,
+            tweets: tweets.clone(),
+            prices: prices.clone(),
+        };
+        if let Err(e) = storage::save_raw_fetch(&raw) {
+            eprintln!("    WARNING: Failed to store raw data: {}", e);
+        }
 
         // Analyze
+        let started = Instant::now();
+        let options = analysis::AnalysisOptions {
+            shares_outstanding: config.shares_outstanding,
+            lexicon: lexicon_for(&lexicons, &config.ceo_handle),
+            ..options.clone()
+        };
         let mut result = match analysis::analyze(
             &config.ceo_handle,
             &config.ticker,
             tweets,
             prices,
+            &options,
             false,
         ) {
             Ok(r) => r,
             Err(e) => {
                 eprintln!("    WARNING: Analysis failed: {}", e);
+                failed += 1;
                 continue;
             }
         };
+        for (first, last) in &result.stale_price_ranges {
+            println!("    WARNING: {} closed flat from {} to {} (halted or stale?); excluded from correlation",
+                     result.ticker, first, last);
+        }
+
+        result.follower_count = follower_count;
+        result.run_config = Some(analysis::run_config(
+            &options,
+            days,
+            stocks::DEFAULT_PRICE_BUFFER_DAYS,
+            tweet_source,
+            &stock_api_base,
+        ));
+        timings.push(models::StageTiming::since("analyze", started));
 
         // Apply Prolog rules
-        if let Err(e) = prolog::apply_rules(&mut result, None) {
+        let started = Instant::now();
+        if let Err(e) = prolog::apply_rules(&mut result, None, &facts_filter) {
             eprintln!("    WARNING: Prolog rules failed: {}", e);
         }
+        timings.push(models::StageTiming::since("apply_rules", started));
+        if let Some(cap) = result.impact_solutions_cap {
+            println!("    WARNING: Only the first {} impactful tweets were classified (--prolog-max-solutions)", cap);
+        }
 
         println!(
             "    SUCCESS: Correlation: {:.3}, Tweets: {}",
             result.correlation_1d.unwrap_or(0.0),
             result.total_tweets
         );
+        if args.timings {
+            let stages: Vec<String> = timings.iter().map(|t| format!("{} {:.3}s", t.stage, t.seconds)).collect();
+            println!("    Timings: {}", stages.join(", "));
+            result.timings = timings.clone();
+            batch_timings.push(timings);
+        }
+        if let Some(max) = args.store_max_impacts {
+            let dropped = result.trim_impacts(max);
+            if dropped > 0 {
+                println!("    Stored {} of {} impacts (--store-max-impacts)", result.impacts.len(), result.impacts.len() + dropped);
+            }
+        }
 
         results.push(result);
+        if let Err(e) = storage::save_checkpoint(&results) {
+            eprintln!("    WARNING: Failed to write checkpoint: {}", e);
+        }
 
         // Rate limiting
-        sleep(Duration::from_millis(500)).await;
+        sleep(jitter::delay(Duration::from_millis(500))).await;
     }
 
     println!("\nBatch analysis complete! Analyzed {} companies", results.len());
+    if args.timings && !batch_timings.is_empty() {
+        println!("\nTimings over {} CEOs analyzed this run (total / mean per CEO):", batch_timings.len());
+        for total in models::sum_timings(batch_timings.iter().map(Vec::as_slice)) {
+            println!(
+                "  {:<14} {:>9.3}s {:>9.3}s",
+                total.stage,
+                total.seconds,
+                total.seconds / batch_timings.len() as f64
+            );
+        }
+    }
+    for summary in results.iter().map(AnalysisResult::summary) {
+        println!(
+            "  @{} ({}): {} tweets, {} impactful, corr 1d {}",
+            summary.ceo_handle,
+            summary.ticker,
+            summary.total_tweets,
+            summary.impactful_count,
+            summary.correlation_1d.map_or("n/a".to_string(), |c| format!("{:.3}", c))
+        );
+    }
 
-    // Save results
+    // Influence leaderboard (CEOs with unknown follower counts rank last)
+    let mut leaderboard: Vec<_> = results.iter().map(|r| (r, r.influence_score())).collect();
+    leaderboard.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    println!("\nInfluence leaderboard:");
+    for (rank, (result, score)) in leaderboard.iter().enumerate() {
+        match score {
+            Some(score) => println!("  {:>2}. @{} ({}) score {:.2}", rank + 1, result.ceo_handle, result.ticker, score),
+            None => println!("  {:>2}. @{} ({}) score n/a (followers unknown)", rank + 1, result.ceo_handle, result.ticker),
+        }
+    }
+
+    // Save results; CEOs outside --only/--skip keep their stored results,
+    // re-read under the lock so results saved during the run aren't lost
     if !results.is_empty() {
-        storage::save_results(&results)?;
-        println!("Saved analysis results to data/results.json");
+        let all_results = storage::update_results(compact_json, |stored| {
+            merge_aliases(stored, &aliases, &lexicons);
+            *stored = storage::merge_processed_results(std::mem::take(stored), results.clone(), &filter);
+            Ok(stored.clone())
+        })?;
+        println!("Saved analysis results for {} CEOs to data/results.json", all_results.len());
+
+        let matrix = analysis::sentiment_correlation_matrix(&all_results);
+        storage::save_sentiment_matrix(&matrix, args.compact_json)?;
+        println!("Saved sentiment correlation matrix to {}", storage::SENTIMENT_MATRIX_FILE);
     } else {
         println!("No results to save.");
     }
 
+    if failed == 0 {
+        storage::clear_checkpoint()?;
+    } else {
+        println!("{} CEOs failed; rerun with --resume to retry only those", failed);
+    }
+
+    // Quality gate for CI: a mostly empty run usually means expired keys or an API change
+    if let Some(min_rate) = args.min_success_rate {
+        let rate = if batch_size == 0 { 100.0 } else { results.len() as f64 / batch_size as f64 * 100.0 };
+        if rate < min_rate {
+            anyhow::bail!(
+                "Only {} of {} CEOs succeeded ({:.1}%), below --min-success-rate {}%",
+                results.len(),
+                batch_size,
+                rate,
+                min_rate
+            );
+        }
+        println!("Success rate {:.1}% ({} of {} CEOs)", rate, results.len(), batch_size);
+    }
+
+    Ok(())
+}
+
+/// Re-score stored results with each CEO's lexicon and the settings they
+/// were run with, using the prices kept in its raw fetch; results without
+/// raw data are left as they are
+fn rescore_stored_results(
+    aliases: &HashMap<String, String>,
+    lexicons: &HashMap<String, Arc<Lexicon>>,
+    filter: &models::HandleFilter,
+    facts_filter: &prolog::FactsFilter,
+    compact_json: bool,
+) -> Result<()> {
+    // The whole read-modify-write holds the results lock
+    let (total_changed, results) = storage::update_results(compact_json, |results| {
+        report_alias_merges(&merge_aliases(results, aliases, lexicons));
+        let selected = results.iter().filter(|r| filter.selects(&r.ceo_handle)).count();
+        println!("Re-scoring {} stored results", selected);
+
+        let mut total_changed = 0;
+
+        for result in results.iter_mut().filter(|r| filter.selects(&r.ceo_handle)) {
+            let raw = match storage::load_raw_fetch(&result.ceo_handle) {
+                Ok(Some(raw)) => raw,
+                Ok(None) => {
+                    println!("  WARNING: @{} has no stored raw data; skipped", result.ceo_handle);
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("    WARNING: Failed to load raw data for @{}: {}", result.ceo_handle, e);
+                    continue;
+                }
+            };
+            let options = analysis::stored_options(result, lexicon_for(lexicons, &result.ceo_handle));
+            // Trimmed results are analyzed again from raw data, so statistics cover every tweet
+            if result.impacts_trimmed > 0 {
+                println!("  @{}: impacts were trimmed; re-analyzing from raw data", result.ceo_handle);
+            }
+            let changed = match analysis::rescore_stored(result, raw, &options, facts_filter) {
+                Ok(changed) => changed,
+                Err(e) => {
+                    eprintln!("    WARNING: Re-scoring @{} failed: {}", result.ceo_handle, e);
+                    continue;
+                }
+            };
+            println!(
+                "  @{}: {} tweets changed score (lexicon {} {})",
+                result.ceo_handle,
+                changed,
+                options.lexicon.name,
+                options.lexicon.hash()
+            );
+            total_changed += changed;
+        }
+
+        Ok((total_changed, results.clone()))
+    })?;
+
+    println!("\nRescore complete: {} tweets changed score, saved to {}", total_changed, storage::DATA_FILE);
+
+    let matrix = analysis::sentiment_correlation_matrix(&results);
+    storage::save_sentiment_matrix(&matrix, compact_json)?;
+    println!("Saved sentiment correlation matrix to {}", storage::SENTIMENT_MATRIX_FILE);
+
+    Ok(())
+}
+
+/// Re-fetch prices for stored results and backfill forward windows that
+/// have completed since the results were saved
+async fn replay_stored_results(
+    stock_api_key: &str,
+    stock_api_base: &str,
+    aliases: &HashMap<String, String>,
+    lexicons: &HashMap<String, Arc<Lexicon>>,
+    filter: &models::HandleFilter,
+    facts_filter: &prolog::FactsFilter,
+    compact_json: bool,
+) -> Result<()> {
+    // Aliases are merged once, under the lock below; until then a result
+    // under an old handle is planned with the handle it will be merged into
+    let current_handle = |handle: &str| {
+        aliases.get(&handle.to_lowercase()).cloned().unwrap_or_else(|| handle.to_lowercase())
+    };
+
+    // Ticker and days of history to fetch per handle, None once all windows are complete
+    let mut plans: BTreeMap<String, Option<(String, u32)>> = BTreeMap::new();
+    for result in storage::load_results()? {
+        let handle = current_handle(&result.ceo_handle);
+        if !filter.selects(&handle) {
+            continue;
+        }
+        let plan = plans.entry(handle).or_insert(None);
+
+        // Oldest tweet still missing a forward window decides how far back to fetch
+        let oldest_pending = result.impacts.iter()
+            .filter(|i| i.change_1d.is_none() || i.change_3d.is_none())
+            .map(|i| i.tweet.created_at)
+            .min();
+        let Some(oldest_pending) = oldest_pending else {
+            continue;
+        };
+
+        // At least the original window, so 1w/1m/3m performance is recomputed from a full history
+        let pending_days = ((Utc::now() - oldest_pending).num_days() + 5).clamp(1, 3650) as u32;
+        let days = pending_days.max(result.run_config.as_ref().map_or(DEFAULT_DAYS, |c| c.days));
+        match plan {
+            Some((_, planned)) => *planned = (*planned).max(days),
+            None => *plan = Some((result.ticker, days)),
+        }
+    }
+    println!("Replaying {} stored results against current prices", plans.len());
+
+    // Prices are fetched first, so the results lock is only held while applying them
+    let mut fetched: HashMap<String, Vec<PricePoint>> = HashMap::new();
+    for (handle, plan) in plans {
+        let Some((ticker, days)) = plan else {
+            println!("  @{}: all windows complete", handle);
+            continue;
+        };
+        let prices = match stocks::fetch_prices_shared(
+            &ticker,
+            stock_api_key,
+            stock_api_base,
+            days,
+            stocks::DEFAULT_PRICE_BUFFER_DAYS,
+            Duration::MAX,
+            false,
+        ).await {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("    WARNING: Failed to fetch prices for {}: {}", ticker, e);
+                continue;
+            }
+        };
+        fetched.insert(handle, prices);
+
+        sleep(jitter::delay(Duration::from_millis(500))).await;
+    }
+
+    // Re-read under the lock, so results saved while fetching aren't lost
+    let total_updated = storage::update_results(compact_json, |results| {
+        report_alias_merges(&merge_aliases(results, aliases, lexicons));
+        let mut total_updated = 0;
+        for result in results.iter_mut() {
+            let Some(prices) = fetched.get(&current_handle(&result.ceo_handle)) else {
+                continue;
+            };
+            let lexicon = lexicon_for(lexicons, &result.ceo_handle);
+            if analysis::scored_with_other_lexicon(result, &lexicon) {
+                println!(
+                    "  WARNING: @{} was scored with lexicon {}, not the current {} {}; its sentiments are kept as stored",
+                    result.ceo_handle,
+                    result.lexicon_hash.as_deref().unwrap_or("?"),
+                    lexicon.name,
+                    lexicon.hash()
+                );
+            }
+            if result.impacts_trimmed > 0 {
+                println!(
+                    "  WARNING: @{} was stored without {} of its impacts; its statistics are kept as stored",
+                    result.ceo_handle,
+                    result.impacts_trimmed
+                );
+            }
+            // Same price basis, return type, exclusions and windows the result was made with
+            let options = analysis::stored_options(result, lexicon);
+            let updated = analysis::replay_forward_prices(result, prices, &options);
+            if let Err(e) = prolog::apply_rules(result, None, facts_filter) {
+                eprintln!("    WARNING: Prolog rules failed: {}", e);
+            }
+            println!("  @{}: backfilled {} impacts", result.ceo_handle, updated);
+            total_updated += updated;
+        }
+
+        Ok(total_updated)
+    })?;
+
+    println!("\nReplay complete: {} impacts updated, saved to {}", total_updated, storage::DATA_FILE);
+
     Ok(())
 }
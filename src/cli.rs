@@ -3,6 +3,8 @@
 //! This module defines the CLI arguments for the CEO tweet analyzer,
 //! including Twitter handle, stock ticker, date range, and API credentials.
 
+use crate::models::{canonical_ticker, AttributionMode, PriceBasis, RepeatMatching, ReturnType};
+use chrono::NaiveDate;
 use clap::Parser;
 
 /// CEO Tweet Analyzer - Correlate CEO tweets with stock price movements
@@ -16,12 +18,18 @@ use clap::Parser;
                   and Lean 4 for formal verification."
 )]
 pub struct Cli {
+    /// Optional subcommand (the default is a full analysis run)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Twitter handle of the CEO (without @)
-    #[arg(long, value_name = "HANDLE")]
+    // Empty defaults let subcommands run without them; validate() enforces them for analysis
+    #[arg(long, value_name = "HANDLE", default_value = "", hide_default_value = true)]
     pub ceo_handle: String,
 
     /// Stock ticker symbol (e.g., TSLA, AAPL)
-    #[arg(long, value_name = "TICKER")]
+    #[arg(long, value_name = "TICKER", default_value = "", hide_default_value = true,
+          value_parser = parse_ticker)]
     pub ticker: String,
 
     /// Number of days to look back for tweets and stock data
@@ -32,6 +40,40 @@ pub struct Cli {
     #[arg(long, env = "TWITTER_BEARER_TOKEN", value_name = "TOKEN")]
     pub api_key_twitter: Option<String>,
 
+    /// Stop fetching after N tweets even if the --days window isn't covered yet
+    /// [default: 50, or 10000 with --twitter-search-archive]
+    #[arg(long, value_name = "N")]
+    pub max_tweets: Option<usize>,
+
+    /// Stop paging once tweets predate this day (YYYY-MM-DD, UTC)
+    #[arg(long, value_name = "DATE")]
+    pub fetch_until_date: Option<NaiveDate>,
+
+    /// Stop after N pages of up to 100 tweets from the Twitter API
+    #[arg(long, value_name = "N")]
+    pub fetch_max_pages: Option<usize>,
+
+    /// Analyze these tweets (IDs or tweet URLs, comma-separated) instead of
+    /// fetching the timeline; needs an API token
+    #[arg(long, value_delimiter = ',', value_name = "IDS")]
+    pub tweet_ids: Vec<String>,
+
+    /// File of tweet IDs or URLs to analyze, one per line (# starts a comment)
+    #[arg(long, value_name = "PATH")]
+    pub tweet_ids_file: Option<String>,
+
+    /// Fetch tweets from full-archive search (/2/tweets/search/all) over the
+    /// whole --days range instead of the latest timeline page; needs an
+    /// academic/elevated API token
+    #[arg(long)]
+    pub twitter_search_archive: bool,
+
+    /// If the API rejects the token (auth error or quota exhausted), fetch
+    /// the timeline through the scraper instead; needs both a token and
+    /// scraper credentials
+    #[arg(long)]
+    pub twitter_fallback: bool,
+
     /// Twitter Username (for scraping)
     #[arg(long, env = "TWITTER_USERNAME")]
     pub twitter_username: Option<String>,
@@ -41,13 +83,170 @@ pub struct Cli {
     pub twitter_password: Option<String>,
 
     /// Stock API key (Alpha Vantage) (or set via STOCK_API_KEY env var)
-    #[arg(long, env = "STOCK_API_KEY", value_name = "KEY")]
+    #[arg(long, env = "STOCK_API_KEY", value_name = "KEY", default_value = "", hide_default_value = true)]
     pub api_key_stocks: String,
 
-    /// Output format: table, json, or both
+    /// Alpha Vantage-compatible base URL (e.g. a caching proxy or mock server)
+    #[arg(long, env = "STOCK_API_BASE", default_value = crate::stocks::ALPHA_VANTAGE_BASE, value_name = "URL")]
+    pub stock_api_base: String,
+
+    /// Twitter API v2 base URL
+    #[arg(long, env = "TWITTER_API_BASE", default_value = crate::twitter::TWITTER_API_BASE, value_name = "URL")]
+    pub twitter_api_base: String,
+
+    /// Also fetch prices from a secondary provider, filling gaps in the primary
+    /// series and reporting dates where the two disagree
+    #[arg(long, value_enum, value_name = "PROVIDER")]
+    pub secondary_prices: Option<crate::stocks::PriceProvider>,
+
+    /// Close-price difference (percent) above which primary and secondary
+    /// providers are reported as disagreeing
+    #[arg(long, default_value_t = crate::stocks::DEFAULT_DISCREPANCY_PCT, value_name = "PCT")]
+    pub price_discrepancy_pct: f64,
+
+    /// Extra trading days of prices fetched before the window for indicator warm-up
+    #[arg(long, default_value_t = crate::stocks::DEFAULT_PRICE_BUFFER_DAYS, value_name = "DAYS")]
+    pub price_buffer_days: u32,
+
+    /// Idle HTTP connections kept alive per API host for reuse
+    #[arg(long, default_value_t = crate::http::DEFAULT_POOL_SIZE, value_name = "N")]
+    pub http_pool_size: usize,
+
+    /// Stretch each pause between API requests (and quota waits) by a random
+    /// 0-25% and randomize retry backoffs, so scheduled runs don't hit the
+    /// APIs in lockstep
+    #[arg(long)]
+    pub request_jitter: bool,
+
+    /// Seed for --request-jitter (reproducible delays)
+    #[arg(long, requires = "request_jitter", value_name = "N")]
+    pub jitter_seed: Option<u64>,
+
+    /// Accept a forward price up to N calendar days from its target day when the
+    /// target has none (such changes are flagged as approximated)
+    #[arg(long, default_value_t = 0, value_name = "DAYS")]
+    pub price_tolerance_days: u32,
+
+    /// Accept a 1w/1m/3m performance start price up to N calendar days before
+    /// its target day; with a bigger gap the performance is left out
+    #[arg(long, default_value_t = crate::analysis::PERFORMANCE_TOLERANCE_DAYS, value_name = "DAYS")]
+    pub performance_tolerance_days: u32,
+
+    /// Daily price used for impact windows and 1w/1m/3m performance
+    #[arg(long, value_enum, default_value_t = PriceBasis::Close, value_name = "BASIS")]
+    pub price_basis: PriceBasis,
+
+    /// Return type for tweet changes and performance: simple (%) or log
+    /// (100 * ln(p1/p0), shown as "log%"); correlations use the same values
+    #[arg(long, value_enum, default_value_t = ReturnType::Simple, value_name = "TYPE")]
+    pub return_type: ReturnType,
+
+    /// Collapse repeated (copy-pasted) tweets: off, exact, or normalized
+    /// (ignoring case, whitespace and links)
+    #[arg(long, value_enum, default_value_t = RepeatMatching::Off, value_name = "MODE")]
+    pub collapse_repeats: RepeatMatching,
+
+    /// When tweets fall inside an earlier tweet's lookahead window, credit the
+    /// move to all of them, only the first, or only the most-engaged one
+    #[arg(long, value_enum, default_value_t = AttributionMode::All, value_name = "MODE")]
+    pub attribution: AttributionMode,
+
+    /// Leave out tweets with fewer than N words (links don't count)
+    #[arg(long, default_value_t = 0, value_name = "N")]
+    pub min_words: usize,
+
+    /// Leave out tweets posted more than N days ago, however many the fetch
+    /// returned (a hard recency cutoff on top of --days)
+    #[arg(long, value_name = "N")]
+    pub max_tweet_age_days: Option<u32>,
+
+    /// Forward windows (days) for the rise success matrix, e.g. 0,1,3,5
+    /// (0 is the tweet day's open-to-close move)
+    #[arg(long, value_delimiter = ',', value_name = "DAYS")]
+    pub rise_windows: Vec<i64>,
+
+    /// Rise thresholds (%) for the rise success matrix, e.g. 1,3,5
+    #[arg(long, value_delimiter = ',', value_name = "PCT")]
+    pub rise_thresholds: Vec<f64>,
+
+    /// Price (USD) below which the table warns that fixed % thresholds are
+    /// within a penny stock's daily noise
+    #[arg(long, default_value_t = crate::output::DEFAULT_PENNY_STOCK_FLOOR, value_name = "USD")]
+    pub penny_stock_floor: f64,
+
+    /// Shares outstanding, to report each tweet's 1-day market cap change in
+    /// USD (percent moves only without it)
+    #[arg(long, value_name = "N")]
+    pub shares_outstanding: Option<u64>,
+
+    /// CSV of known news events (date,headline) to flag confounded tweets
+    #[arg(long, value_name = "PATH")]
+    pub news_file: Option<String>,
+
+    /// Leave tweets whose window overlaps a news event out of the correlation
+    #[arg(long, requires = "news_file")]
+    pub exclude_news: bool,
+
+    /// Leave mixed-signal tweets (strong positive and negative words) out of the correlation
+    #[arg(long)]
+    pub exclude_mixed: bool,
+
+    /// Leave neutral tweets (|sentiment| <= --neutral-band) out of the
+    /// correlation; the 1d correlation with them is still reported
+    #[arg(long)]
+    pub exclude_neutral_from_correlation: bool,
+
+    /// Largest |sentiment| treated as neutral by --exclude-neutral-from-correlation
+    #[arg(long, default_value_t = 0.0, requires = "exclude_neutral_from_correlation", value_name = "X")]
+    pub neutral_band: f64,
+
+    /// Forward window (days) for the engagement vs absolute move correlation (default 1)
+    #[arg(long, value_name = "DAYS")]
+    pub engagement_window: Option<i64>,
+
+    /// Score tweets with this domain's lexicon (e.g. biotech) instead of the built-in one
+    #[arg(long, value_name = "NAME")]
+    pub domain: Option<String>,
+
+    /// Domain -> lexicon file mapping (default: LEXICONS_PATH, then lexicons.json)
+    #[arg(long, requires = "domain", value_name = "PATH")]
+    pub lexicons: Option<String>,
+
+    /// Also score tweets with a remote sentiment model at this URL, listing
+    /// strong disagreements with the keyword scorer in a review queue
+    #[arg(long, env = "REMOTE_SENTIMENT_URL", value_name = "URL")]
+    pub remote_sentiment_url: Option<String>,
+
+    /// Causal score weights in the order move,direction,momentum,news,volume
+    /// (default 0.35,0.2,0.15,0.15,0.15)
+    #[arg(long, value_delimiter = ',', value_name = "WEIGHTS")]
+    pub causal_weights: Vec<f64>,
+
+    /// Output format: table, json, both, csv, or markdown
     #[arg(long, default_value = "table", value_name = "FORMAT")]
     pub output_format: OutputFormat,
 
+    /// Order of the highlighted tweets: impactful (Prolog rules) or surprise score
+    #[arg(long, value_enum, default_value_t = crate::output::TweetSort::Impactful, value_name = "ORDER")]
+    pub sort_by: crate::output::TweetSort,
+
+    /// Disable ANSI colors and use ASCII table rules (also when NO_COLOR is
+    /// set or stdout isn't a terminal)
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Print JSON output on one line instead of indented
+    #[arg(long)]
+    pub compact_json: bool,
+
+    /// Only list tweets classified as impactful (statistics still use every tweet)
+    #[arg(long)]
+    pub impactful_only: bool,
+
+    /// Group CSV/JSON output into week or month rows instead of one row per tweet
+    #[arg(long, value_enum, value_name = "PERIOD")]
+    pub aggregate: Option<crate::output::AggregatePeriod>,
+
     /// Enable verbose logging
     #[arg(short, long)]
     pub verbose: bool,
@@ -56,9 +255,51 @@ pub struct Cli {
     #[arg(long, value_name = "PATH")]
     pub export_prolog: Option<String>,
 
+    /// Only export facts for the N tweets with the largest price moves
+    #[arg(long, value_name = "N")]
+    pub prolog_limit: Option<usize>,
+
+    /// Only export facts for tweets that have price data
+    #[arg(long)]
+    pub prolog_priced_only: bool,
+
+    /// Retrieve at most N impactful_tweet/1 solutions; keeps the rules fast on
+    /// high-volume accounts, but tweets past the cap stay unclassified
+    #[arg(long, value_name = "N")]
+    pub prolog_max_solutions: Option<usize>,
+
+    /// Also keep the facts in prolog/{handle}_{date}.pl and report how the
+    /// classifications changed since the previous dated export
+    #[arg(long)]
+    pub prolog_history: bool,
+
     /// Generate chart (PNG file)
     #[arg(long, value_name = "PATH")]
     pub chart_output: Option<String>,
+
+    /// Print how long each pipeline stage took (fetch tweets, fetch prices,
+    /// analyze, apply rules, render); JSON output carries all but render
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Write the (sentiment, change) pairs behind each correlation window to
+    /// PATH, as JSON for a .json path and CSV otherwise
+    #[arg(long, value_name = "PATH")]
+    pub dump_pairs: Option<String>,
+}
+
+/// Subcommands
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum Command {
+    /// Check API keys and connectivity for every configured provider
+    Doctor,
+    /// Compare the tweet classifications of two Prolog facts exports
+    DiffFacts {
+        /// Earlier export
+        previous: String,
+        /// Later export
+        current: String,
+    },
 }
 
 /// Output format options
@@ -70,6 +311,15 @@ pub enum OutputFormat {
     Json,
     /// Both table and JSON
     Both,
+    /// One CSV row per tweet
+    Csv,
+    /// Markdown summary and tweet table
+    Markdown,
+}
+
+/// Parse `--ticker` into its canonical uppercase form
+fn parse_ticker(value: &str) -> Result<String, String> {
+    Ok(canonical_ticker(value))
 }
 
 impl Cli {
@@ -91,72 +341,224 @@ impl Cli {
             anyhow::bail!("Either Twitter API key (TWITTER_BEARER_TOKEN) OR Twitter credentials (TWITTER_USERNAME, TWITTER_PASSWORD) are required");
         }
         
+        if self.max_tweets == Some(0) {
+            anyhow::bail!("--max-tweets must be at least 1");
+        }
+        if self.fetch_max_pages == Some(0) {
+            anyhow::bail!("--fetch-max-pages must be at least 1");
+        }
+        if self.prolog_max_solutions == Some(0) {
+            anyhow::bail!("--prolog-max-solutions must be at least 1");
+        }
+        
+        let by_id = !self.tweet_ids.is_empty() || self.tweet_ids_file.is_some();
+        if by_id && self.api_key_twitter.is_none() {
+            anyhow::bail!("--tweet-ids/--tweet-ids-file require a Twitter API key (TWITTER_BEARER_TOKEN)");
+        }
+        if by_id && self.twitter_search_archive {
+            anyhow::bail!("--tweet-ids/--tweet-ids-file can't be combined with --twitter-search-archive");
+        }
+        
+        if self.twitter_search_archive && self.api_key_twitter.is_none() {
+            anyhow::bail!("--twitter-search-archive requires a Twitter API key (TWITTER_BEARER_TOKEN)");
+        }
+        
+        let scraper_credentials = self.twitter_username.is_some() && self.twitter_password.is_some();
+        if self.twitter_fallback && (self.api_key_twitter.is_none() || !scraper_credentials) {
+            anyhow::bail!("--twitter-fallback requires both a Twitter API key and scraper credentials (TWITTER_USERNAME, TWITTER_PASSWORD)");
+        }
+        
         if self.api_key_stocks.is_empty() {
             anyhow::bail!("Stock API key is required (use --api-key-stocks or STOCK_API_KEY env var)");
         }
         
+        if !(0.0..1.0).contains(&self.neutral_band) {
+            anyhow::bail!("--neutral-band must be at least 0 and below 1");
+        }
+        
+        if self.shares_outstanding == Some(0) {
+            anyhow::bail!("--shares-outstanding must be at least 1");
+        }
+        
+        if self.max_tweet_age_days == Some(0) {
+            anyhow::bail!("--max-tweet-age-days must be at least 1");
+        }
+        
+        if self.rise_windows.iter().any(|w| *w < 0) {
+            anyhow::bail!("Rise windows can't be negative");
+        }
+        
+        if self.engagement_window.is_some_and(|w| w < 0) {
+            anyhow::bail!("--engagement-window can't be negative");
+        }
+        
+        if !self.causal_weights.is_empty() {
+            crate::analysis::CausalWeights::from_list(&self.causal_weights)?;
+        }
+        
+        for base in [&self.stock_api_base, &self.twitter_api_base].into_iter().chain(&self.remote_sentiment_url) {
+            if !base.starts_with("http://") && !base.starts_with("https://") {
+                anyhow::bail!("API URL must start with http:// or https:// (got {})", base);
+            }
+        }
+        
         Ok(())
     }
 }
 
+impl Cli {
+    /// Tweet IDs from --tweet-ids and --tweet-ids-file (None = fetch the timeline)
+    pub fn requested_tweet_ids(&self) -> anyhow::Result<Option<Vec<String>>> {
+        let mut inputs = self.tweet_ids.clone();
+        if let Some(path) = &self.tweet_ids_file {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read tweet ID file {}: {}", path, e))?;
+            inputs.extend(
+                content
+                    .lines()
+                    .map(|line| line.split('#').next().unwrap_or_default().trim().to_string())
+                    .filter(|line| !line.is_empty()),
+            );
+        }
+        if inputs.is_empty() {
+            return Ok(None);
+        }
+        
+        let mut ids = Vec::new();
+        for input in &inputs {
+            let id = crate::twitter::parse_tweet_id(input)
+                .ok_or_else(|| anyhow::anyhow!("Not a tweet ID or tweet URL: {}", input))?;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        Ok(Some(ids))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_cli_validation_empty_handle() {
-        let cli = Cli {
-            ceo_handle: String::new(),
+    /// A valid token-authenticated CLI that individual tests tweak
+    fn valid_cli() -> Cli {
+        Cli {
+            command: None,
+            ceo_handle: "elonmusk".to_string(),
             ticker: "TSLA".to_string(),
             days: 365,
-            api_key_twitter: Some("test".to_string()),
+            price_buffer_days: crate::stocks::DEFAULT_PRICE_BUFFER_DAYS,
+            http_pool_size: crate::http::DEFAULT_POOL_SIZE,
+            request_jitter: false,
+            jitter_seed: None,
+            price_tolerance_days: 0,
+            performance_tolerance_days: crate::analysis::PERFORMANCE_TOLERANCE_DAYS,
+            api_key_twitter: Some("test_token".to_string()),
+            twitter_search_archive: false,
+            twitter_fallback: false,
+            max_tweets: None,
+            fetch_until_date: None,
+            fetch_max_pages: None,
+            tweet_ids: Vec::new(),
+            tweet_ids_file: None,
             twitter_username: None,
             twitter_password: None,
-            api_key_stocks: "test".to_string(),
+            api_key_stocks: "test_key".to_string(),
+            stock_api_base: crate::stocks::ALPHA_VANTAGE_BASE.to_string(),
+            twitter_api_base: crate::twitter::TWITTER_API_BASE.to_string(),
+            secondary_prices: None,
+            price_discrepancy_pct: crate::stocks::DEFAULT_DISCREPANCY_PCT,
+            price_basis: PriceBasis::Close,
+            return_type: ReturnType::Simple,
+            collapse_repeats: RepeatMatching::Off,
+            attribution: AttributionMode::All,
+            min_words: 0,
+            max_tweet_age_days: None,
+            rise_windows: Vec::new(),
+            rise_thresholds: Vec::new(),
+            penny_stock_floor: crate::output::DEFAULT_PENNY_STOCK_FLOOR,
+            shares_outstanding: None,
+            news_file: None,
+            exclude_news: false,
+            exclude_mixed: false,
+            exclude_neutral_from_correlation: false,
+            neutral_band: 0.0,
+            engagement_window: None,
+            domain: None,
+            lexicons: None,
+            remote_sentiment_url: None,
+            causal_weights: Vec::new(),
             output_format: OutputFormat::Table,
+            sort_by: crate::output::TweetSort::Impactful,
+            no_color: false,
+            compact_json: false,
+            impactful_only: false,
+            aggregate: None,
             verbose: false,
             export_prolog: None,
+            prolog_limit: None,
+            prolog_priced_only: false,
+            prolog_max_solutions: None,
+            prolog_history: false,
             chart_output: None,
-        };
+            timings: false,
+            dump_pairs: None,
+        }
+    }
+
+    #[test]
+    fn test_cli_validation_empty_handle() {
+        let mut cli = valid_cli();
+        cli.ceo_handle = String::new();
         
         assert!(cli.validate().is_err());
     }
 
     #[test]
     fn test_cli_validation_valid() {
-        let cli = Cli {
-            ceo_handle: "elonmusk".to_string(),
-            ticker: "TSLA".to_string(),
-            days: 365,
-            api_key_twitter: Some("test_token".to_string()),
-            twitter_username: None,
-            twitter_password: None,
-            api_key_stocks: "test_key".to_string(),
-            output_format: OutputFormat::Table,
-            verbose: false,
-            export_prolog: None,
-            chart_output: None,
-        };
+        let cli = valid_cli();
         
         assert!(cli.validate().is_ok());
     }
 
     #[test]
     fn test_cli_validation_scraping_creds() {
-        let cli = Cli {
-            ceo_handle: "elonmusk".to_string(),
-            ticker: "TSLA".to_string(),
-            days: 365,
-            api_key_twitter: None,
-            twitter_username: Some("user".to_string()),
-            twitter_password: Some("pass".to_string()),
-            api_key_stocks: "test_key".to_string(),
-            output_format: OutputFormat::Table,
-            verbose: false,
-            export_prolog: None,
-            chart_output: None,
-        };
+        let mut cli = valid_cli();
+        cli.api_key_twitter = None;
+        cli.twitter_username = Some("user".to_string());
+        cli.twitter_password = Some("pass".to_string());
         
         assert!(cli.validate().is_ok());
     }
+
+    #[test]
+    fn test_cli_validation_archive_search_needs_token() {
+        let mut cli = valid_cli();
+        cli.twitter_search_archive = true;
+        assert!(cli.validate().is_ok());
+        
+        cli.api_key_twitter = None;
+        cli.twitter_username = Some("user".to_string());
+        cli.twitter_password = Some("pass".to_string());
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_validation_bad_api_base() {
+        let mut cli = valid_cli();
+        cli.stock_api_base = "localhost:8080/query".to_string();
+        
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_cli_ticker_is_canonical() {
+        let lower = Cli::try_parse_from(["ceo-tweet-analyzer", "--ticker", "tsla"]).unwrap();
+        let upper = Cli::try_parse_from(["ceo-tweet-analyzer", "--ticker", "TSLA"]).unwrap();
+        assert_eq!(lower.ticker, "TSLA");
+        assert_eq!(lower.ticker, upper.ticker);
+
+        let suffixed = Cli::try_parse_from(["ceo-tweet-analyzer", "--ticker", " brk.b "]).unwrap();
+        assert_eq!(suffixed.ticker, "BRK.B");
+    }
 }
@@ -0,0 +1,118 @@
+//! Connectivity self-check for the external providers.
+//!
+//! `ceo-tweet-analyzer doctor` exercises each integration with a known-good
+//! input and reports OK/FAIL with latency, so a broken key or endpoint can be
+//! pinpointed before a long run.
+
+use crate::cli::Cli;
+use crate::{stocks, twitter};
+use anyhow::Result;
+use std::future::Future;
+use std::time::Instant;
+
+/// Handle used for the Twitter lookup check
+const DOCTOR_HANDLE: &str = "X";
+
+/// Ticker used for the stock provider check
+const DOCTOR_TICKER: &str = "AAPL";
+
+/// Gemini model listing endpoint (cheap, needs only a valid key)
+const GEMINI_MODELS_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Outcome of a single provider check
+enum CheckStatus {
+    Ok(String),
+    Fail(String),
+    Skip(String),
+}
+
+/// Run every provider check and print a report
+///
+/// Returns an error if any configured provider failed, so scripts can gate on it.
+pub async fn run(args: &Cli) -> Result<()> {
+    println!("\nChecking provider connectivity...\n");
+    
+    let mut failures = 0;
+    
+    // Twitter user lookup (API path only; the scraper has no cheap probe)
+    let twitter = match args.api_key_twitter.as_deref() {
+        Some(token) => timed(async {
+            twitter::lookup_user(DOCTOR_HANDLE, &args.twitter_api_base, token)
+                .await
+                .map(|user| format!("@{} -> {}", DOCTOR_HANDLE, user.id))
+        }).await,
+        None => (CheckStatus::Skip("no TWITTER_BEARER_TOKEN set".to_string()), None),
+    };
+    failures += report("Twitter API", twitter);
+    
+    // One day of prices from the configured stock provider
+    let stocks_check = if args.api_key_stocks.is_empty() {
+        (CheckStatus::Fail("no STOCK_API_KEY set".to_string()), None)
+    } else {
+        timed(async {
+            stocks::fetch_prices(DOCTOR_TICKER, &args.api_key_stocks, &args.stock_api_base, 1, 0, false)
+                .await
+                .map(|prices| format!("{} price point(s) for {}", prices.len(), DOCTOR_TICKER))
+        }).await
+    };
+    failures += report("Stock provider", stocks_check);
+    
+    // Gemini (only used by the daily updater)
+    let gemini = match std::env::var("GEMINI_API_KEY") {
+        Ok(key) => timed(async move {
+            let response = crate::http::client()
+                .get(format!("{}?key={}", GEMINI_MODELS_URL, key))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                anyhow::bail!("Gemini API returned {}", response.status());
+            }
+            Ok("key accepted".to_string())
+        }).await,
+        Err(_) => (CheckStatus::Skip("no GEMINI_API_KEY set".to_string()), None),
+    };
+    failures += report("Gemini API", gemini);
+    
+    println!();
+    if failures > 0 {
+        anyhow::bail!("{} provider check(s) failed", failures);
+    }
+    
+    println!("All configured providers OK\n");
+    Ok(())
+}
+
+/// Await a check, timing it and mapping the outcome to a status
+async fn timed<F>(check: F) -> (CheckStatus, Option<u128>)
+where
+    F: Future<Output = Result<String>>,
+{
+    let started = Instant::now();
+    let outcome = check.await;
+    let elapsed = started.elapsed().as_millis();
+    
+    match outcome {
+        Ok(detail) => (CheckStatus::Ok(detail), Some(elapsed)),
+        Err(e) => (CheckStatus::Fail(format!("{:#}", e)), Some(elapsed)),
+    }
+}
+
+/// Print one report line, returning 1 if the check failed
+fn report(name: &str, (status, elapsed_ms): (CheckStatus, Option<u128>)) -> usize {
+    let latency = elapsed_ms.map(|ms| format!("{:>6} ms", ms)).unwrap_or_else(|| "       -".to_string());
+    
+    match status {
+        CheckStatus::Ok(detail) => {
+            println!("  {:<16} OK    {}  {}", name, latency, detail);
+            0
+        }
+        CheckStatus::Fail(detail) => {
+            println!("  {:<16} FAIL  {}  {}", name, latency, detail);
+            1
+        }
+        CheckStatus::Skip(detail) => {
+            println!("  {:<16} SKIP  {}  {}", name, latency, detail);
+            0
+        }
+    }
+}
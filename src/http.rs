@@ -0,0 +1,178 @@
+//! Shared HTTP client for every API call.
+//!
+//! One `reqwest::Client` is reused across fetches so connections to the same
+//! host are kept alive and pooled instead of reopened per request, which
+//! matters once batch runs fetch many CEOs in parallel.
+//!
+//! Rate-limit headers (`x-rate-limit-remaining` / `x-rate-limit-reset`) are
+//! recorded per provider, or per endpoint where the provider limits each
+//! endpoint separately, as responses come in. Once a provider's quota is
+//! used up, the next request to it waits for the reset instead of being
+//! rejected with a 429.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Default idle connections kept open per host (override with `--http-pool-size`)
+pub const DEFAULT_POOL_SIZE: usize = 8;
+
+/// How long an idle pooled connection is kept before closing
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// TCP keep-alive probe interval for pooled connections
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Longest wait for a quota reset; a later reset (e.g. a monthly cap) is
+/// left to fail rather than stall the run
+pub const MAX_QUOTA_WAIT: Duration = Duration::from_secs(16 * 60);
+
+/// Extra wait after the advertised reset, for clock skew
+const QUOTA_RESET_MARGIN: Duration = Duration::from_secs(1);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Latest quota reported by each provider ("alpha_vantage", "yahoo", "gemini")
+/// or Twitter endpoint ("twitter:/users/:id/tweets", ...)
+static QUOTAS: Mutex<BTreeMap<String, Quota>> = Mutex::new(BTreeMap::new());
+
+/// A provider's rate-limit window, as reported by its last response
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Quota {
+    /// Requests allowed per window, when reported
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: u64,
+    /// When the window resets, when reported
+    pub reset: Option<DateTime<Utc>>,
+}
+
+impl Quota {
+    /// Rate-limit headers of a response, if it sent `remaining`
+    ///
+    /// Reads `x-rate-limit-*` (Twitter) and `x-ratelimit-*`; the reset is
+    /// epoch seconds.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            [format!("x-rate-limit-{}", name), format!("x-ratelimit-{}", name)]
+                .iter()
+                .find_map(|key| headers.get(key.as_str())?.to_str().ok()?.trim().parse::<u64>().ok())
+        };
+        Some(Quota {
+            limit: header("limit"),
+            remaining: header("remaining")?,
+            reset: header("reset").and_then(|t| DateTime::from_timestamp(i64::try_from(t).ok()?, 0)),
+        })
+    }
+
+    /// How long to wait before the next request at `now`: until the reset
+    /// when nothing is left, None when requests remain, the reset has passed
+    /// or it is further off than `MAX_QUOTA_WAIT`
+    pub fn wait(&self, now: DateTime<Utc>) -> Option<Duration> {
+        if self.remaining > 0 {
+            return None;
+        }
+        let wait = (self.reset? - now).to_std().ok()? + QUOTA_RESET_MARGIN;
+        (wait <= MAX_QUOTA_WAIT).then_some(wait)
+    }
+}
+
+impl std::fmt::Display for Quota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            Some(limit) => write!(f, "{}/{} requests left", self.remaining, limit)?,
+            None => write!(f, "{} requests left", self.remaining)?,
+        }
+        if let Some(reset) = self.reset {
+            write!(f, ", resets {}", reset.format("%H:%M:%S UTC"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Remember the quota in `headers` for `provider`, if the response sent one
+pub fn record_quota(provider: &str, headers: &HeaderMap, verbose: bool) {
+    let Some(quota) = Quota::from_headers(headers) else {
+        return;
+    };
+    if verbose {
+        println!("  → {} quota: {}", provider, quota);
+    }
+    QUOTAS.lock().unwrap().insert(provider.to_string(), quota);
+}
+
+/// Sleep until `provider`'s quota resets if its last response left none
+///
+/// The wait is jittered (only ever lengthened), so runs that used up a
+/// shared quota together don't all retry the moment it resets.
+pub async fn wait_for_quota(provider: &str, verbose: bool) {
+    let quota = QUOTAS.lock().unwrap().get(provider).copied();
+    let Some(wait) = quota.and_then(|q| q.wait(Utc::now())) else {
+        return;
+    };
+    if verbose {
+        println!("  → {} quota used up, waiting {}s for the reset...", provider, wait.as_secs());
+    }
+    tokio::time::sleep(crate::jitter::delay(wait)).await;
+    QUOTAS.lock().unwrap().remove(provider);
+}
+
+/// Latest known quota of every provider that reported one
+pub fn quotas() -> BTreeMap<String, Quota> {
+    QUOTAS.lock().unwrap().clone()
+}
+
+/// Build the shared client with `pool_size` idle connections per host
+///
+/// Call once at startup, before any fetch; without it the first fetch
+/// builds the client with `DEFAULT_POOL_SIZE`.
+pub fn init(pool_size: usize) -> Result<()> {
+    let client = build(pool_size)?;
+    CLIENT
+        .set(client)
+        .map_err(|_| anyhow::anyhow!("HTTP client already initialized"))
+}
+
+/// The process-wide client
+pub fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| build(DEFAULT_POOL_SIZE).expect("default HTTP client"))
+}
+
+fn build(pool_size: usize) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .pool_max_idle_per_host(pool_size)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_quota_waits_for_reset_only_when_used_up() {
+        let now = Utc::now();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-limit", HeaderValue::from_static("900"));
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("0"));
+        let reset = now.timestamp() + 60;
+        headers.insert("x-rate-limit-reset", HeaderValue::from_str(&reset.to_string()).unwrap());
+
+        let quota = Quota::from_headers(&headers).unwrap();
+        assert_eq!((quota.limit, quota.remaining), (Some(900), 0));
+        let wait = quota.wait(now).unwrap();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(61));
+
+        assert_eq!(Quota { remaining: 3, ..quota }.wait(now), None);
+        assert_eq!(quota.wait(now + chrono::Duration::minutes(2)), None);
+        assert_eq!(quota.wait(now - chrono::Duration::hours(1)), None);
+        assert_eq!(Quota::from_headers(&HeaderMap::new()), None);
+    }
+}
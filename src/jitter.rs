@@ -0,0 +1,134 @@
+//! Randomized jitter for the pauses between API requests.
+//!
+//! Fixed sleeps make every scheduled run (and every instance of it) hit the
+//! shared Twitter, Alpha Vantage, Yahoo and Gemini endpoints in lockstep.
+//! With jitter on, each pause is stretched by a random amount so bursts
+//! drift apart. Pauses that keep a rate limit (fixed delays, quota waits)
+//! are only ever lengthened, so a delay that keeps a free-tier limit still
+//! keeps it. Retry backoffs use full jitter instead: anywhere from zero up
+//! to the backoff, which spreads simultaneous retries the furthest.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Largest extra pause, as a fraction of the base delay
+pub const JITTER_FRACTION: f64 = 0.25;
+
+/// splitmix64 increment
+const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The process-wide generator behind [`delay`] and [`backoff`]
+static GLOBAL: Jitter = Jitter::off();
+
+/// A jitter switch and its random sequence
+///
+/// The binaries share one through [`init`]; tests build their own, so they
+/// don't depend on the order other tests run in.
+pub struct Jitter {
+    enabled: AtomicBool,
+    state: AtomicU64,
+}
+
+impl Jitter {
+    /// Jitter switched off: every delay is used as given
+    pub const fn off() -> Self {
+        Jitter { enabled: AtomicBool::new(false), state: AtomicU64::new(0) }
+    }
+
+    /// Jitter on or off, seeded with `seed` (reproducible runs and tests) or
+    /// from the clock and process ID
+    #[cfg(test)]
+    pub fn new(enabled: bool, seed: Option<u64>) -> Self {
+        let jitter = Jitter::off();
+        jitter.reset(enabled, seed);
+        jitter
+    }
+
+    fn reset(&self, enabled: bool, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64);
+            nanos ^ u64::from(std::process::id()).rotate_left(32)
+        });
+        self.state.store(seed, Ordering::Relaxed);
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// `base` plus up to `JITTER_FRACTION` of it at random when jitter is on
+    pub fn delay(&self, base: Duration) -> Duration {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return base;
+        }
+        base + base.mul_f64(JITTER_FRACTION * self.next_unit())
+    }
+
+    /// Pause before retry `attempt` (0 for the first): `base` doubled per
+    /// attempt up to `cap`, or with jitter on a uniform pick below that
+    pub fn backoff(&self, base: Duration, attempt: u32, cap: Duration) -> Duration {
+        let ceiling = base.saturating_mul(2u32.saturating_pow(attempt)).min(cap);
+        if !self.enabled.load(Ordering::Relaxed) {
+            return ceiling;
+        }
+        ceiling.mul_f64(self.next_unit())
+    }
+
+    /// Uniform in [0, 1), from a splitmix64 sequence shared by all callers
+    fn next_unit(&self) -> f64 {
+        let mut z = self.state.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Turn the process-wide jitter on or off (see [`Jitter::new`])
+///
+/// Call once at startup; without it delays are used as given.
+pub fn init(enabled: bool, seed: Option<u64>) {
+    GLOBAL.reset(enabled, seed);
+}
+
+/// [`Jitter::delay`] with the process-wide generator
+pub fn delay(base: Duration) -> Duration {
+    GLOBAL.delay(base)
+}
+
+/// [`Jitter::backoff`] with the process-wide generator
+pub fn backoff(base: Duration, attempt: u32, cap: Duration) -> Duration {
+    GLOBAL.backoff(base, attempt, cap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_is_seeded_and_only_lengthens() {
+        let base = Duration::from_millis(1000);
+        let sample = |jitter: &Jitter| (0..5).map(|_| jitter.delay(base)).collect::<Vec<_>>();
+
+        assert!(sample(&Jitter::new(false, Some(7))).iter().all(|d| *d == base));
+
+        let first = sample(&Jitter::new(true, Some(7)));
+        assert!(first.iter().all(|d| *d >= base && *d < base.mul_f64(1.0 + JITTER_FRACTION)));
+        assert!(first.iter().any(|d| *d != first[0]));
+
+        assert_eq!(sample(&Jitter::new(true, Some(7))), first);
+    }
+
+    #[test]
+    fn test_backoff_doubles_to_cap_with_full_jitter() {
+        let (base, cap) = (Duration::from_secs(1), Duration::from_secs(5));
+
+        let off = Jitter::new(false, Some(7));
+        let ceilings: Vec<u64> = (0..5).map(|attempt| off.backoff(base, attempt, cap).as_secs()).collect();
+        assert_eq!(ceilings, [1, 2, 4, 5, 5]);
+
+        let on = Jitter::new(true, Some(7));
+        let pauses: Vec<Duration> = (0..20).map(|_| on.backoff(base, 2, cap)).collect();
+        assert!(pauses.iter().all(|d| *d < Duration::from_secs(4)));
+        assert!(pauses.iter().any(|d| *d < base));
+    }
+}
@@ -0,0 +1,231 @@
+//! Sentiment lexicons, selectable per CEO domain.
+//!
+//! A biotech CEO's good news ("fda approval", "phase 3") reads nothing like a
+//! crypto CEO's ("moon", "hodl"). The built-in word lists cover general
+//! business language; a mapping file assigns other lexicons to domains, and
+//! each CEO config entry with a `domain` is scored with that domain's lexicon.
+
+use crate::models::{preprocess_tweet_text, TextOptions};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Domain -> lexicon file mapping read when neither `--lexicons` nor LEXICONS_PATH is set
+pub const DEFAULT_LEXICONS: &str = "lexicons.json";
+
+/// Name recorded on results scored with the built-in lexicon
+pub const DEFAULT_LEXICON_NAME: &str = "default";
+
+/// Version of the scoring rules (text preprocessing, matching, normalization)
+///
+/// Bump whenever the same lexicon would score a tweet differently, so the
+/// lexicon hash marks results scored under the old rules as stale.
+pub const SCORER_VERSION: u32 = 2;
+
+/// Words that push a tweet's sentiment score up
+const POSITIVE_WORDS: [&str; 19] = [
+    "great", "excellent", "amazing", "good", "success", "win", "winning",
+    "growth", "profit", "record", "best", "excited", "love", "fantastic",
+    "incredible", "revolutionary", "breakthrough", "proud", "happy",
+];
+
+/// Words that push a tweet's sentiment score down
+const NEGATIVE_WORDS: [&str; 19] = [
+    "bad", "terrible", "awful", "poor", "loss", "losing", "fail", "failure",
+    "worst", "sad", "disappointed", "concern", "problem", "issue", "difficult",
+    "challenge", "unfortunate", "regret", "sorry",
+];
+
+/// Net term hits that score a full ±1.0
+///
+/// Fixed at the built-in list size rather than each lexicon's, so a tweet
+/// scores the same under a large domain lexicon as under a small one.
+const FULL_SCORE_HITS: f64 = POSITIVE_WORDS.len() as f64;
+
+/// Positive and negative terms a tweet is scored against
+///
+/// Terms may be phrases ("phase 3"), matched as consecutive words.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Lexicon {
+    /// Domain the lexicon was loaded for, or `DEFAULT_LEXICON_NAME`
+    #[serde(skip)]
+    pub name: String,
+
+    /// Terms that push the score up
+    pub positive: Vec<String>,
+
+    /// Terms that push the score down
+    pub negative: Vec<String>,
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        let words = |list: &[&str]| list.iter().map(|w| w.to_string()).collect();
+        Lexicon {
+            name: DEFAULT_LEXICON_NAME.to_string(),
+            positive: words(&POSITIVE_WORDS),
+            negative: words(&NEGATIVE_WORDS),
+        }
+    }
+}
+
+impl Lexicon {
+    /// Load a `{"positive": [...], "negative": [...]}` lexicon file as `name`
+    ///
+    /// Terms are lowercased and their whitespace collapsed, as tweet text is.
+    pub fn load(path: &Path, name: &str) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .context(format!("Failed to read lexicon file: {}", path.display()))?;
+        let mut lexicon: Lexicon = serde_json::from_str(&json)
+            .context(format!("Failed to parse lexicon file: {}", path.display()))?;
+
+        for terms in [&mut lexicon.positive, &mut lexicon.negative] {
+            *terms = terms.iter()
+                .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        if lexicon.positive.is_empty() && lexicon.negative.is_empty() {
+            anyhow::bail!("Lexicon file {} has no terms", path.display());
+        }
+        lexicon.name = name.to_string();
+        Ok(lexicon)
+    }
+
+    /// Sentiment score of `text` between -1.0 (very negative) and 1.0 (very positive)
+    pub fn score(&self, text: &str) -> f64 {
+        let (pos_hits, neg_hits) = self.hits(text);
+
+        let score = (pos_hits as f64 - neg_hits as f64) / FULL_SCORE_HITS;
+        score.clamp(-1.0, 1.0)
+    }
+
+    /// Number of positive and negative terms found in `text`
+    ///
+    /// Matches whole words only, so "goodbye" doesn't count as "good"; links
+    /// and mentions are ignored.
+    pub fn hits(&self, text: &str) -> (u32, u32) {
+        let cleaned = preprocess_tweet_text(text, TextOptions::SENTIMENT);
+        let tokens: Vec<&str> = cleaned
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect();
+        let count = |terms: &[String]| terms.iter().filter(|term| contains_term(&tokens, term)).count() as u32;
+        (count(&self.positive), count(&self.negative))
+    }
+
+    /// Stable fingerprint of the word lists and `SCORER_VERSION` (FNV-1a)
+    ///
+    /// Changes whenever a term is added, removed or moved between lists, or
+    /// the scoring rules change, so results scored differently can be told apart.
+    pub fn hash(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let version = vec![format!("v{}", SCORER_VERSION)];
+        let lists = [("=", &version), ("+", &self.positive), ("-", &self.negative)];
+
+        for (sign, terms) in lists {
+            for term in terms.iter() {
+                for byte in sign.bytes().chain(term.bytes()).chain(std::iter::once(b'\n')) {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        format!("{:016x}", hash)
+    }
+}
+
+/// Whether `term`'s words appear consecutively in `tokens`
+fn contains_term(tokens: &[&str], term: &str) -> bool {
+    let words: Vec<&str> = term
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    !words.is_empty() && tokens.windows(words.len()).any(|window| window == words.as_slice())
+}
+
+/// Lexicons by domain, from a mapping file of `{"domain": "lexicon file"}`
+///
+/// Lexicon paths are relative to the mapping file. Domains match case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct DomainLexicons {
+    by_domain: HashMap<String, Arc<Lexicon>>,
+    default: Arc<Lexicon>,
+}
+
+impl DomainLexicons {
+    /// Load the mapping at `path` and every lexicon it names
+    ///
+    /// A missing mapping file means no domain lexicons; every CEO then gets
+    /// the built-in one.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .context(format!("Failed to read lexicon mapping: {}", path.display()))?;
+        let mapping: HashMap<String, PathBuf> = serde_json::from_str(&json)
+            .context(format!("Failed to parse lexicon mapping: {}", path.display()))?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut by_domain = HashMap::new();
+        for (domain, file) in mapping {
+            let domain = domain.trim().to_lowercase();
+            let lexicon = Lexicon::load(&base.join(file), &domain)?;
+            by_domain.insert(domain, Arc::new(lexicon));
+        }
+        Ok(DomainLexicons { by_domain, default: Arc::default() })
+    }
+
+    /// Lexicon for a config entry's `domain`; the built-in one when it has none
+    pub fn get(&self, domain: Option<&str>) -> Result<Arc<Lexicon>> {
+        let Some(domain) = domain.map(|d| d.trim().to_lowercase()).filter(|d| !d.is_empty()) else {
+            return Ok(self.default.clone());
+        };
+        self.by_domain
+            .get(&domain)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No lexicon mapped for domain '{}'", domain))
+    }
+}
+
+/// Where the domain lexicon mapping lives: `flag`, else LEXICONS_PATH, else `DEFAULT_LEXICONS`
+pub fn lexicons_path(flag: Option<&str>) -> PathBuf {
+    let path = flag
+        .map(str::to_string)
+        .or_else(|| std::env::var("LEXICONS_PATH").ok().filter(|p| !p.is_empty()))
+        .unwrap_or_else(|| DEFAULT_LEXICONS.to_string());
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_lexicon_scores_phrases() {
+        let dir = std::env::temp_dir().join(format!("lexicons-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("biotech.json"), r#"{"positive": ["FDA  approval", "phase 3"], "negative": ["clinical hold"]}"#).unwrap();
+        fs::write(dir.join("lexicons.json"), r#"{"Biotech": "biotech.json"}"#).unwrap();
+
+        let lexicons = DomainLexicons::load(&dir.join("lexicons.json")).unwrap();
+        let biotech = lexicons.get(Some("biotech")).unwrap();
+        assert_eq!(biotech.name, "biotech");
+        assert_eq!(biotech.hits("FDA approval for our phase 3 drug!"), (2, 0));
+        assert_eq!(biotech.hits("approval of the FDA, phase three"), (0, 0));
+        assert!(biotech.score("Clinical hold lifted") < 0.0);
+        assert_ne!(biotech.hash(), Lexicon::default().hash());
+
+        // A lexicon's size doesn't change what a hit is worth
+        assert_eq!(biotech.score("FDA approval"), Lexicon::default().score("great"));
+
+        assert_eq!(lexicons.get(None).unwrap().name, DEFAULT_LEXICON_NAME);
+        assert!(lexicons.get(Some("crypto")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
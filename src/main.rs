@@ -10,18 +10,37 @@ mod twitter;
 mod stocks;
 mod analysis;
 mod prolog;
+mod doctor;
+mod http;
+mod jitter;
+mod lexicon;
+mod news;
+mod output;
+mod remote_sentiment;
+mod storage;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
+use output::OutputRenderer;
+use std::time::Instant;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse CLI arguments
     let args = Cli::parse();
+    http::init(args.http_pool_size)?;
+    jitter::init(args.request_jitter, args.jitter_seed);
+    
+    match &args.command {
+        Some(Command::Doctor) => return doctor::run(&args).await,
+        Some(Command::DiffFacts { previous, current }) => return diff_facts(previous, current),
+        None => {}
+    }
     
     // Validate arguments
     args.validate()?;
+    output::init_terminal_style(args.no_color);
     
     // Set up logging based on verbosity
     if args.verbose {
@@ -32,55 +51,245 @@ async fn main() -> Result<()> {
     }
     
     println!("\nCEO Tweet Analyzer Starting...\n");
+    let mut timings = Vec::new();
     
-    // Step 1: Fetch tweets
-    println!("Fetching tweets from @{}...", args.ceo_handle);
-    let tweets = twitter::fetch_tweets(
-        &args.ceo_handle,
-        args.api_key_twitter.as_deref(),
-        args.twitter_username.as_deref(),
-        args.twitter_password.as_deref(),
-        args.days,
-        args.verbose,
-    )
-    .await?;
+    // Step 1: Fetch tweets (or hydrate the requested IDs)
+    let started = Instant::now();
+    let fetch = match (args.requested_tweet_ids()?, args.api_key_twitter.as_deref()) {
+        (Some(ids), Some(token)) => {
+            println!("Looking up {} tweets by ID...", ids.len());
+            twitter::fetch_tweets_by_id(&ids, &args.twitter_api_base, token, args.verbose).await?
+        }
+        _ => {
+            println!("Fetching tweets from @{}...", args.ceo_handle);
+            twitter::fetch_tweets(
+                &args.ceo_handle,
+                &args.twitter_api_base,
+                args.api_key_twitter.as_deref(),
+                args.twitter_username.as_deref(),
+                args.twitter_password.as_deref(),
+                &twitter::FetchOptions {
+                    days: args.days,
+                    max_tweets: args.max_tweets,
+                    search_archive: args.twitter_search_archive,
+                    until_date: args.fetch_until_date,
+                    max_pages: args.fetch_max_pages,
+                    cached_tweets: Vec::new(),
+                    scraper_fallback: args.twitter_fallback,
+                },
+                args.verbose,
+            )
+            .await?
+        }
+    };
     
-    println!("Fetched {} tweets", tweets.len());
+    timings.push(models::StageTiming::since("fetch_tweets", started));
+    let tweets = fetch.tweets;
+    println!("Fetched {} tweets ({})", tweets.len(), fetch.stop.describe());
+    let raise = match fetch.stop {
+        twitter::FetchStop::MaxTweets => Some("--max-tweets"),
+        twitter::FetchStop::MaxPages => Some("--fetch-max-pages"),
+        _ => None,
+    };
+    if let (Some(flag), Some(oldest)) = (raise, tweets.iter().map(|t| t.created_at).min()) {
+        println!(
+            "WARNING: Fetch limit hit; only tweets since {} are covered of the {}-day window (raise {})",
+            oldest.format("%Y-%m-%d"),
+            args.days,
+            flag
+        );
+    }
+    if fetch.dropped_without_timestamp > 0 {
+        println!("WARNING: Skipped {} tweets with no timestamp", fetch.dropped_without_timestamp);
+    }
     
-    // Step 2: Fetch stock prices
+    // Step 2: Fetch stock prices (far enough back for hand-picked older tweets)
+    let started = Instant::now();
+    let price_days = tweets
+        .iter()
+        .map(|t| (chrono::Utc::now() - t.created_at).num_days() + 5)
+        .max()
+        .map_or(args.days, |needed| args.days.max(needed.clamp(1, 3650) as u32));
     println!("\nFetching stock prices for {}...", args.ticker);
-    let prices = stocks::fetch_prices(
+    let mut prices = stocks::fetch_prices(
         &args.ticker,
         &args.api_key_stocks,
-        args.days,
+        &args.stock_api_base,
+        price_days,
+        args.price_buffer_days,
         args.verbose,
     )
     .await?;
     
+    let mut price_discrepancies = Vec::new();
+    if let Some(provider) = args.secondary_prices {
+        match stocks::fetch_secondary_prices(
+            provider,
+            &args.ticker,
+            price_days,
+            args.price_buffer_days,
+            args.verbose,
+        )
+        .await
+        {
+            Ok(secondary) => {
+                let primary_count = prices.len();
+                let (merged, discrepancies) =
+                    stocks::reconcile_prices(prices, secondary, args.price_discrepancy_pct);
+                prices = merged;
+                price_discrepancies = discrepancies;
+                if args.verbose {
+                    println!(
+                        "  → Secondary provider filled {} missing days",
+                        prices.len() - primary_count
+                    );
+                }
+                if !price_discrepancies.is_empty() {
+                    println!(
+                        "WARNING: {} days where price providers disagree by more than {}%",
+                        price_discrepancies.len(),
+                        args.price_discrepancy_pct
+                    );
+                }
+            }
+            Err(e) => println!("WARNING: Secondary price fetch failed, using primary only: {}", e),
+        }
+    }
+    
+    timings.push(models::StageTiming::since("fetch_prices", started));
     println!("Fetched {} price points", prices.len());
     
     // Step 3: Perform analysis
     println!("\nAnalyzing tweet impacts and correlations...");
+    let started = Instant::now();
+    let news_events = match &args.news_file {
+        Some(path) => news::load_news_csv(path)?,
+        None => Vec::new(),
+    };
+    let options = analysis::AnalysisOptions {
+        price_basis: args.price_basis,
+        return_type: args.return_type,
+        price_tolerance_days: args.price_tolerance_days,
+        performance_tolerance_days: Some(args.performance_tolerance_days),
+        repeat_matching: args.collapse_repeats,
+        attribution: args.attribution,
+        min_words: args.min_words,
+        max_tweet_age_days: args.max_tweet_age_days,
+        shares_outstanding: args.shares_outstanding,
+        rise_windows: args.rise_windows.clone(),
+        rise_thresholds: args.rise_thresholds.clone(),
+        news_events,
+        exclude_news_from_correlation: args.exclude_news,
+        exclude_mixed_from_correlation: args.exclude_mixed,
+        exclude_neutral_from_correlation: args.exclude_neutral_from_correlation,
+        neutral_band: args.neutral_band,
+        engagement_window: args.engagement_window,
+        causal_weights: if args.causal_weights.is_empty() {
+            analysis::CausalWeights::default()
+        } else {
+            analysis::CausalWeights::from_list(&args.causal_weights)?
+        },
+        statistics: Vec::new(),
+        lexicon: lexicon::DomainLexicons::load(&lexicon::lexicons_path(args.lexicons.as_deref()))?
+            .get(args.domain.as_deref())?,
+    };
+    if args.domain.is_some() && args.verbose {
+        println!("  → Scoring with the {} lexicon", options.lexicon.name);
+    }
     let mut analysis_result = analysis::analyze(
         &args.ceo_handle,
         &args.ticker,
         tweets,
         prices,
+        &options,
         args.verbose,
     )?;
+    analysis_result.follower_count = fetch.follower_count;
+    analysis_result.price_discrepancies = price_discrepancies;
+    let tweet_source = match (&args.api_key_twitter, args.twitter_search_archive) {
+        _ if fetch.fell_back_to_scraper => "scraper",
+        (Some(_), true) => "twitter_archive",
+        (Some(_), false) => "twitter_api",
+        (None, _) => "scraper",
+    };
+    analysis_result.run_config = Some(analysis::run_config(
+        &options,
+        args.days,
+        args.price_buffer_days,
+        tweet_source,
+        &args.stock_api_base,
+    ));
+    // Tweets the keyword scorer and the remote model disagree on go to the review queue
+    if let Some(url) = &args.remote_sentiment_url {
+        match remote_sentiment::fetch_scores(url, &analysis_result.impacts, args.verbose).await {
+            Ok(scores) => analysis::apply_remote_sentiment(&mut analysis_result.impacts, &scores),
+            Err(e) => println!("WARNING: Remote sentiment scoring failed: {}; no review queue", e),
+        }
+    }
+    timings.push(models::StageTiming::since("analyze", started));
     
     println!("Analysis complete");
+    if let Some(reason) = &analysis_result.status_reason {
+        println!("WARNING: Nothing to analyze: {}", reason);
+    }
+    for warning in &analysis_result.warnings {
+        println!("WARNING: {}; correlation and regression are unavailable", warning);
+    }
+    for (first, last) in &analysis_result.stale_price_ranges {
+        println!("WARNING: {} closed at the same price every day from {} to {} (halted or stale data?); \
+                  tweets in that stretch are left out of the correlations", analysis_result.ticker, first, last);
+    }
     
     // Step 4: Apply Prolog rules
     println!("\nApplying Prolog rules for pattern detection...");
-    prolog::apply_rules(&mut analysis_result, args.export_prolog.as_deref())?;
+    let started = Instant::now();
+    let facts_filter = prolog::FactsFilter {
+        limit: args.prolog_limit,
+        priced_only: args.prolog_priced_only,
+        max_solutions: args.prolog_max_solutions,
+    };
+    prolog::apply_rules(&mut analysis_result, args.export_prolog.as_deref(), &facts_filter)?;
+    if let Some(cap) = analysis_result.impact_solutions_cap {
+        println!("WARNING: More than {} tweets are impactful; only the first {} were classified (--prolog-max-solutions)",
+                 cap, cap);
+    }
+    if args.prolog_history {
+        let (path, diff) = prolog::record_facts_history(
+            &analysis_result,
+            &facts_filter,
+            std::path::Path::new(prolog::FACTS_HISTORY_DIR),
+            chrono::Utc::now().date_naive(),
+        )?;
+        println!("  → Saved Prolog facts to {}", path.display());
+        match diff {
+            Some(diff) => print_facts_diff(&diff),
+            None => println!("  → No earlier export to compare with"),
+        }
+    }
     
+    timings.push(models::StageTiming::since("apply_rules", started));
     println!("Prolog analysis complete");
     
-    // Step 5: Display results
+    // Step 5: Display results (JSON output can't include its own render time)
+    if args.timings {
+        analysis_result.timings = timings.clone();
+    }
     println!("\nResults:\n");
+    let started = Instant::now();
     display_results(&analysis_result, &args)?;
     
+    if let Some(path) = &args.dump_pairs {
+        let pairs = analysis::correlation_pairs(&analysis_result.impacts);
+        let contents = if path.ends_with(".json") {
+            serde_json::to_string_pretty(&pairs)?
+        } else {
+            output::render_pairs_csv(&pairs)
+        };
+        std::fs::write(path, contents).context(format!("Failed to write correlation pairs: {}", path))?;
+        println!("Wrote {} correlation pairs to {}", pairs.len(), path);
+    }
+    timings.push(models::StageTiming::since("render", started));
+    
     // Step 6: Generate chart if requested
     if let Some(chart_path) = &args.chart_output {
         println!("\nGenerating chart to {}...", chart_path);
@@ -88,90 +297,78 @@ async fn main() -> Result<()> {
         println!("WARNING: Chart generation not yet implemented");
     }
     
+    if args.timings {
+        print_timings(&timings);
+    }
+    
     println!("\nAnalysis complete!\n");
     
     Ok(())
 }
 
-/// Display analysis results based on output format
-fn display_results(result: &models::AnalysisResult, args: &Cli) -> Result<()> {
-    use cli::OutputFormat;
-    
-    match args.output_format {
-        OutputFormat::Table | OutputFormat::Both => {
-            display_table(result)?;
-        }
-        _ => {}
+/// Print each stage's time and share of the total (`--timings`)
+fn print_timings(timings: &[models::StageTiming]) {
+    let total: f64 = timings.iter().map(|t| t.seconds).sum();
+    println!("\nTimings:");
+    for timing in timings {
+        let share = if total > 0.0 { timing.seconds / total * 100.0 } else { 0.0 };
+        println!("  {:<14} {:>9.3}s {:>5.1}%", timing.stage, timing.seconds, share);
     }
-    
-    if matches!(args.output_format, OutputFormat::Json | OutputFormat::Both) {
-        display_json(result)?;
-    }
-    
+    println!("  {:<14} {:>9.3}s", "total", total);
+}
+
+/// `diff-facts`: compare the classifications of two facts exports
+fn diff_facts(previous: &str, current: &str) -> Result<()> {
+    let read = |path: &str| {
+        std::fs::read_to_string(path).context(format!("Failed to read Prolog facts: {}", path))
+    };
+    let diff = prolog::diff_facts(&read(previous)?, &read(current)?);
+    print_facts_diff(&diff);
     Ok(())
 }
 
-/// Display results as a formatted table
-fn display_table(result: &models::AnalysisResult) -> Result<()> {
-    println!("═══════════════════════════════════════════════════════════════════════════");
-    println!("  CEO Tweet Impact Analysis");
-    println!("═══════════════════════════════════════════════════════════════════════════");
-    println!("  CEO: @{}", result.ceo_handle);
-    println!("  Ticker: {}", result.ticker);
-    println!("  Period: {} to {}", 
-             result.start_date.format("%Y-%m-%d"),
-             result.end_date.format("%Y-%m-%d"));
-    println!("  Total Tweets: {}", result.total_tweets);
-    println!("  Tweets with Price Data: {}", result.tweets_with_price_data);
-    println!("═══════════════════════════════════════════════════════════════════════════\n");
-    
-    // Summary statistics
-    println!("Summary Statistics:");
-    println!("  Correlation (sentiment vs 1d change): {:.4}", 
-             result.correlation_1d.unwrap_or(0.0));
-    println!("  Correlation (sentiment vs 3d change): {:.4}", 
-             result.correlation_3d.unwrap_or(0.0));
-    println!("  Positive tweets → >3% rise (1d): {:.1}%", 
-             result.positive_tweets_with_rise_1d);
-    println!("  Positive tweets → >3% rise (3d): {:.1}%", 
-             result.positive_tweets_with_rise_3d);
-    
-    // Top impactful tweets
-    println!("\nMost Impactful Tweets (by Prolog rules):");
-    let impactful: Vec<_> = result.impacts.iter()
-        .filter(|i| i.is_impactful)
-        .take(5)
-        .collect();
-    
-    if impactful.is_empty() {
-        println!("  No tweets classified as impactful");
-    } else {
-        for (idx, impact) in impactful.iter().enumerate() {
-            let text = if impact.tweet.text.len() > 60 {
-                format!("{}...", &impact.tweet.text[..60])
-            } else {
-                impact.tweet.text.clone()
-            };
-            
-            println!("\n  {}. {} ({})", 
-                     idx + 1,
-                     impact.tweet.created_at.format("%Y-%m-%d"),
-                     text);
-            println!("     Sentiment: {:.2} | 1d: {:+.2}% | 3d: {:+.2}%",
-                     impact.tweet.sentiment.unwrap_or(0.0),
-                     impact.change_1d.unwrap_or(0.0),
-                     impact.change_3d.unwrap_or(0.0));
-        }
+/// Print newly impactful tweets and changed classifications
+fn print_facts_diff(diff: &prolog::FactsDiff) {
+    if diff.new_impactful.is_empty() && diff.changed.is_empty() {
+        println!("  → No classification changes since the previous export");
+        return;
+    }
+    for (id, level) in &diff.new_impactful {
+        println!("  + tweet {} is new and {}", id, level.label());
+    }
+    for (id, before, after) in &diff.changed {
+        let label = |level: &models::ImpactLevel| match level.label() {
+            "" => "not impactful",
+            label => label,
+        };
+        println!("  ~ tweet {}: {} → {}", id, label(before), label(after));
     }
-    
-    println!("\n═══════════════════════════════════════════════════════════════════════════\n");
-    
-    Ok(())
 }
 
-/// Display results as JSON
-fn display_json(result: &models::AnalysisResult) -> Result<()> {
-    let json = serde_json::to_string_pretty(result)?;
-    println!("{}", json);
-    Ok(())
+/// Display analysis results based on output format
+fn display_results(result: &models::AnalysisResult, args: &Cli) -> Result<()> {
+    use cli::OutputFormat;
+    
+    // Display-only filter: statistics above were computed over every tweet
+    let filtered;
+    let result = if args.impactful_only {
+        filtered = output::impactful_only(result);
+        println!("Showing {} impactful of {} analyzed tweets (--impactful-only)\n",
+                 filtered.impacts.len(), result.impacts.len());
+        &filtered
+    } else {
+        result
+    };
+    
+    let json = || output::JsonRenderer { compact: args.compact_json, aggregate: args.aggregate };
+    let table = || output::TableRenderer { sort_by: args.sort_by, penny_stock_floor: args.penny_stock_floor };
+    let renderer: Box<dyn OutputRenderer> = match args.output_format {
+        OutputFormat::Table => Box::new(table()),
+        OutputFormat::Json => Box::new(json()),
+        OutputFormat::Both => Box::new(output::ChainRenderer(vec![Box::new(table()), Box::new(json())])),
+        OutputFormat::Csv => Box::new(output::CsvRenderer { aggregate: args.aggregate }),
+        OutputFormat::Markdown => Box::new(output::MarkdownRenderer),
+    };
+    
+    renderer.render(result, &mut std::io::stdout().lock())
 }
@@ -3,8 +3,10 @@
 //! This module defines the primary data structures used throughout the application,
 //! including tweets, stock prices, and analysis results.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
 
 /// Represents a single tweet from a CEO
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +30,112 @@ pub struct Tweet {
     /// Negative = bearish, Positive = bullish, 0 = neutral
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sentiment: Option<f64>,
+
+    /// Absolute gap between the keyword score and a remote model's score,
+    /// set only when a remote sentiment backend scored this tweet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment_disagreement: Option<f64>,
+
+    /// Positive lexicon words found in the text
+    #[serde(default)]
+    pub pos_hits: u32,
+
+    /// Negative lexicon words found in the text
+    #[serde(default)]
+    pub neg_hits: u32,
+
+    /// Substantial positive and negative signals at once (e.g. "great product,
+    /// terrible quarter"), which cancel to a near-neutral score
+    #[serde(default)]
+    pub mixed: bool,
+}
+
+impl Tweet {
+    /// Number of words in the text, not counting links
+    pub fn word_count(&self) -> usize {
+        preprocess_tweet_text(&self.text, TextOptions::WORDS)
+            .split_whitespace()
+            .count()
+    }
+}
+
+/// What `preprocess_tweet_text` does with emoji and other pictographs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmojiHandling {
+    Keep,
+    Strip,
+}
+
+/// Toggles for `preprocess_tweet_text`
+///
+/// Each consumer uses one of the presets below, so they all clean text the
+/// same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Drop http:// and https:// links
+    pub strip_urls: bool,
+    
+    /// Drop @mentions
+    pub strip_mentions: bool,
+    
+    pub emoji: EmojiHandling,
+    
+    pub lowercase: bool,
+}
+
+impl TextOptions {
+    /// Keyword sentiment scoring, and the text to send a remote sentiment backend
+    pub const SENTIMENT: Self = Self {
+        strip_urls: true,
+        strip_mentions: true,
+        emoji: EmojiHandling::Strip,
+        lowercase: true,
+    };
+    
+    /// Word counts for `--min-words`: links aren't words, everything else is
+    pub const WORDS: Self = Self {
+        strip_urls: true,
+        strip_mentions: false,
+        emoji: EmojiHandling::Keep,
+        lowercase: false,
+    };
+    
+    /// Repeat detection: the same post with another link or casing still matches
+    pub const REPEATS: Self = Self {
+        strip_urls: true,
+        strip_mentions: false,
+        emoji: EmojiHandling::Keep,
+        lowercase: true,
+    };
+}
+
+/// Clean tweet text for one consumer, with whitespace collapsed to single spaces
+pub fn preprocess_tweet_text(text: &str, options: TextOptions) -> String {
+    text.split_whitespace()
+        .filter(|word| !(options.strip_urls && (word.starts_with("http://") || word.starts_with("https://"))))
+        .filter(|word| !(options.strip_mentions && word.len() > 1 && word.starts_with('@')))
+        .map(|word| match options.emoji {
+            EmojiHandling::Keep => word.to_string(),
+            EmojiHandling::Strip => word.chars().filter(|c| !is_emoji(*c)).collect(),
+        })
+        .filter(|word| !word.is_empty())
+        .map(|word| if options.lowercase { word.to_lowercase() } else { word })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Canonical form of a ticker symbol, used for every internal key
+///
+/// Providers accept `tsla` but configs, caches and stored results are keyed
+/// by `TSLA`; normalizing where tickers enter keeps them from diverging.
+pub fn canonical_ticker(ticker: &str) -> String {
+    ticker.trim().to_uppercase()
+}
+
+/// Pictographs, dingbats, flags, and the joiners and selectors that combine them
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE00..=0xFE0F | 0x200D | 0x20E3)
 }
 
 /// Represents a stock price data point
@@ -55,7 +163,162 @@ pub struct PricePoint {
     pub volume: u64,
 }
 
+/// Which daily price to measure moves from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceBasis {
+    /// Closing price (default)
+    #[default]
+    Close,
+    /// Opening price
+    Open,
+}
+
+/// How repeated (copy-pasted) tweets are detected and collapsed before analysis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatMatching {
+    /// Every tweet counts separately (default)
+    #[default]
+    Off,
+    /// Identical text
+    Exact,
+    /// Same text ignoring case, extra whitespace and links
+    Normalized,
+}
+
+/// Which tweet gets credit for a price move when lookahead windows overlap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributionMode {
+    /// Every tweet keeps its own forward changes, even if they overlap (default)
+    #[default]
+    All,
+    /// Only the first tweet of a cluster keeps its changes
+    First,
+    /// Only the most-engaged tweet (retweets + likes) of a cluster keeps its changes
+    MostEngaged,
+}
+
+/// Whether a result holds an analysis or is a placeholder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultStatus {
+    #[default]
+    Ok,
+    /// No tweets were left to analyze; see `AnalysisResult::status_reason`
+    NoData,
+}
+
+/// Why a tweet's forward price windows are (or are not) populated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageReason {
+    /// Both the 1-day and 3-day changes were computed
+    #[default]
+    FullCoverage,
+    /// No price on the tweet day itself (weekend or market holiday)
+    WeekendBaseline,
+    /// The tweet day has a price but a forward target day does not
+    MissingFuturePrice,
+    /// The tweet predates the fetched price history
+    BeforePriceHistory,
+    /// A forward window runs past the end of the fetched price history
+    AfterPriceHistory,
+}
+
+impl CoverageReason {
+    /// Short human-readable explanation for verbose output
+    pub fn describe(&self) -> &'static str {
+        match self {
+            CoverageReason::FullCoverage => "full coverage",
+            CoverageReason::WeekendBaseline => "no price on tweet day (market closed)",
+            CoverageReason::MissingFuturePrice => "missing forward price",
+            CoverageReason::BeforePriceHistory => "before price history",
+            CoverageReason::AfterPriceHistory => "after price history",
+        }
+    }
+}
+
+/// Highest Prolog rule tier a tweet satisfied, weakest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImpactLevel {
+    /// No rule fired
+    #[default]
+    None,
+    /// `impactful_tweet/1`
+    Impactful,
+    /// `highly_impactful_tweet/1`: stronger sentiment and a bigger move
+    HighlyImpactful,
+    /// `viral_impactful_tweet/1`: impactful with viral engagement
+    Viral,
+}
+
+impl ImpactLevel {
+    /// Label for tables and exports ("" for `None`)
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImpactLevel::None => "",
+            ImpactLevel::Impactful => "impactful",
+            ImpactLevel::HighlyImpactful => "highly impactful",
+            ImpactLevel::Viral => "viral",
+        }
+    }
+}
+
+/// How a move between two prices is expressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnType {
+    /// Simple percent change, 100 * (p1 - p0) / p0 (default)
+    #[default]
+    Simple,
+    /// Log return in percent, 100 * ln(p1 / p0); additive across periods
+    Log,
+}
+
+impl ReturnType {
+    /// Move from `from` to `to` in percent (simple) or log-percent units
+    ///
+    /// None when a price is not positive, since neither form is defined there.
+    pub fn change(self, from: f64, to: f64) -> Option<f64> {
+        if from <= 0.0 {
+            return None;
+        }
+        match self {
+            ReturnType::Simple => Some((to - from) / from * 100.0),
+            ReturnType::Log if to > 0.0 => Some((to / from).ln() * 100.0),
+            ReturnType::Log => None,
+        }
+    }
+
+    /// A change in this type's units as a fraction of the starting price
+    pub fn fraction(self, change: f64) -> f64 {
+        match self {
+            ReturnType::Simple => change / 100.0,
+            ReturnType::Log => (change / 100.0).exp() - 1.0,
+        }
+    }
+
+    /// Unit suffix for displayed changes
+    pub fn unit(self) -> &'static str {
+        match self {
+            ReturnType::Simple => "%",
+            ReturnType::Log => " log%",
+        }
+    }
+}
+
 impl PricePoint {
+    /// Price for the chosen basis
+    pub fn price(&self, basis: PriceBasis) -> f64 {
+        match basis {
+            PriceBasis::Close => self.close,
+            PriceBasis::Open => self.open,
+        }
+    }
+
     /// Calculate the percentage change from open to close
     pub fn daily_change_percent(&self) -> f64 {
         if self.open == 0.0 {
@@ -64,6 +327,11 @@ impl PricePoint {
             ((self.close - self.open) / self.open) * 100.0
         }
     }
+
+    /// Overnight gap (%) from the previous session's close to this open
+    pub fn gap_percent(&self, prev: &PricePoint) -> Option<f64> {
+        (prev.close > 0.0).then(|| (self.open - prev.close) / prev.close * 100.0)
+    }
 }
 
 /// Represents the analysis of a single tweet's impact on stock price
@@ -75,14 +343,184 @@ pub struct TweetImpact {
     /// Stock price on the day of the tweet
     pub price_at_tweet: Option<f64>,
     
+    /// Same-day reaction: tweet day's open to its close (None when the
+    /// market was closed that day)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_0d: Option<f64>,
+
     /// Percentage change 1 day after tweet
     pub change_1d: Option<f64>,
+
+    /// Gap part of the 1d move: tweet day's close to the next session's open
+    /// (the rest of `change_1d` is intraday drift)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_1d: Option<f64>,
     
     /// Percentage change 3 days after tweet
     pub change_3d: Option<f64>,
     
     /// Whether this tweet is classified as "impactful" by Prolog rules
     pub is_impactful: bool,
+
+    /// Which rule tier made it impactful (`None` exactly when `is_impactful` is false)
+    #[serde(default)]
+    pub impact_level: ImpactLevel,
+
+    /// change_1d used a nearby trading day because the exact target day had no price
+    #[serde(default)]
+    pub change_1d_approximated: bool,
+
+    /// change_3d used a nearby trading day because the exact target day had no price
+    #[serde(default)]
+    pub change_3d_approximated: bool,
+
+    /// Next-day volume divided by the trailing average volume before the tweet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_ratio_1d: Option<f64>,
+
+    /// Next-day intraday range ((high - low) / close) divided by the trailing
+    /// average range before the tweet, a cheap realized-volatility proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_ratio_1d: Option<f64>,
+
+    /// Whether a known news event falls inside this tweet's lookahead window
+    #[serde(default)]
+    pub news_nearby: bool,
+
+    /// Left out of the correlations (news or mixed-signal exclusion, or a
+    /// stale price stretch); see `analysis::correlation_pairs`
+    #[serde(default)]
+    pub excluded_from_correlation: bool,
+
+    /// Composite notability score (0-100), see `analysis::surprise_score`
+    #[serde(default, with = "finite_f64")]
+    pub surprise_score: f64,
+
+    /// The normalized inputs behind `surprise_score`
+    #[serde(default)]
+    pub surprise_components: SurpriseComponents,
+
+    /// 1-day change in units of the stock's trailing daily return volatility
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_z: Option<f64>,
+
+    /// Sentiment standardized against this CEO's tweets in the same result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment_z: Option<f64>,
+
+    /// Cross-stock impact index, (|move_z| + |sentiment_z|) / 2, see `analysis::standardize_impacts`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_index: Option<f64>,
+
+    /// Whether the tweet was posted on a day of unusually high posting activity
+    #[serde(default)]
+    pub high_activity_day: bool,
+
+    /// Another tweet in the same lookahead window got credit for the move, so
+    /// this one's forward changes were cleared (`AttributionMode`)
+    #[serde(default)]
+    pub attribution_suppressed: bool,
+
+    /// Market cap gained or lost (USD) over the 1-day window; None without
+    /// shares outstanding or a 1-day change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub market_cap_change_1d: Option<f64>,
+
+    /// 0-100 likelihood that the tweet, rather than a confounder, moved the
+    /// stock; None without a 1-day change (see `analysis::CausalWeights`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub causal_score: Option<f64>,
+
+    /// Factors behind `causal_score`, each 0-1 (None when the data is missing)
+    #[serde(default)]
+    pub causal_components: CausalComponents,
+
+    /// How many posts of the same text this impact stands for (engagement is summed)
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+
+    /// Why the forward windows are incomplete (or `full_coverage` when they are not)
+    #[serde(default)]
+    pub coverage_reason: CoverageReason,
+}
+
+fn default_repeat_count() -> u32 {
+    1
+}
+
+/// Normalized (0-1) inputs of a tweet's surprise score
+///
+/// A component is None when its input wasn't available; it then counts as 0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SurpriseComponents {
+    /// |sentiment|
+    pub sentiment: Option<f64>,
+
+    /// |1-day move| in units of the trailing daily volatility, capped at 3 sigma
+    pub price_z: Option<f64>,
+
+    /// Next-day volume ratio above normal, capped at 3x
+    pub volume: Option<f64>,
+
+    /// log10(likes + retweets + 1), capped at one million interactions
+    pub engagement: Option<f64>,
+}
+
+/// Evidence that a tweet's next-day move was caused by the tweet, each 0-1
+///
+/// Higher always means "more likely causal". Also used for the per-factor
+/// contributions (in score points) reported next to a causal score.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CausalComponents {
+    /// How abnormal the 1-day move is for this stock: |move_z| / 3, capped at 1
+    pub abnormal_move: Option<f64>,
+
+    /// 1 when the move goes the way the sentiment points, 0 when it doesn't
+    pub direction: Option<f64>,
+
+    /// 1 unless the move just continues the stock's pre-tweet trend
+    pub no_momentum: Option<f64>,
+
+    /// 0 when the window overlaps a known news event (e.g. earnings); None
+    /// when no news events were loaded
+    pub no_news: Option<f64>,
+
+    /// Next-day volume above normal: (ratio - 1) / 2, so 3x = 1
+    pub volume: Option<f64>,
+}
+
+impl TweetImpact {
+    /// Create an impact for a tweet with no price data yet
+    pub fn new(tweet: Tweet) -> Self {
+        Self {
+            tweet,
+            price_at_tweet: None,
+            change_0d: None,
+            change_1d: None,
+            gap_1d: None,
+            change_3d: None,
+            is_impactful: false,
+            impact_level: ImpactLevel::None,
+            change_1d_approximated: false,
+            change_3d_approximated: false,
+            volume_ratio_1d: None,
+            range_ratio_1d: None,
+            news_nearby: false,
+            excluded_from_correlation: false,
+            surprise_score: 0.0,
+            surprise_components: SurpriseComponents::default(),
+            move_z: None,
+            sentiment_z: None,
+            impact_index: None,
+            high_activity_day: false,
+            attribution_suppressed: false,
+            market_cap_change_1d: None,
+            causal_score: None,
+            causal_components: CausalComponents::default(),
+            repeat_count: 1,
+            coverage_reason: CoverageReason::default(),
+        }
+    }
 }
 
 /// Overall analysis results
@@ -98,6 +536,14 @@ pub struct AnalysisResult {
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
     
+    /// `NoData` when nothing was left to analyze (every statistic is then empty)
+    #[serde(default)]
+    pub status: ResultStatus,
+    
+    /// Why the status isn't `Ok`, e.g. "all 12 tweets filtered by --min-words 5"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+    
     /// All tweet impacts
     pub impacts: Vec<TweetImpact>,
     
@@ -106,12 +552,73 @@ pub struct AnalysisResult {
     
     /// Pearson correlation coefficient between sentiment and 3-day price change
     pub correlation_3d: Option<f64>,
+
+    /// Pearson correlation between sentiment and the same-day open-to-close move
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_0d: Option<f64>,
+
+    /// The 1-day correlation with neutral tweets kept, when
+    /// `--exclude-neutral-from-correlation` left them out of `correlation_1d`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_1d_with_neutral: Option<f64>,
+
+    /// Neutral tweets left out of the correlations (still counted everywhere else)
+    #[serde(default)]
+    pub neutral_excluded_from_correlation: usize,
+
+    /// Every window's correlation with its sample size, and a reason when it
+    /// couldn't be computed (the bare `correlation_*` fields are just null)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub correlations: Vec<CorrelationDetail>,
     
-    /// Percentage of positive tweets followed by >3% rise (1 day)
-    pub positive_tweets_with_rise_1d: f64,
+    /// Percentage of positive tweets followed by >3% rise (1 day), None
+    /// when no positive tweet has a 1-day change
+    pub positive_tweets_with_rise_1d: Option<f64>,
     
-    /// Percentage of positive tweets followed by >3% rise (3 days)
-    pub positive_tweets_with_rise_3d: f64,
+    /// Percentage of positive tweets followed by >3% rise (3 days), None
+    /// when no positive tweet has a 3-day change
+    pub positive_tweets_with_rise_3d: Option<f64>,
+
+    /// 95% Wilson score interval (low %, high %) around the 1-day rise rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rise_1d_interval: Option<(f64, f64)>,
+
+    /// 95% Wilson score interval (low %, high %) around the 3-day rise rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rise_3d_interval: Option<(f64, f64)>,
+
+    /// Positive-tweet rise rates for every configured window × threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rise_matrix: Option<RiseMatrix>,
+
+    /// Values of the configured `analysis::Statistic`s, keyed "<name>_<window>d"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statistics: BTreeMap<String, f64>,
+
+    /// Realized forward returns per sentiment bucket, most negative first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calibration: Vec<CalibrationBucket>,
+
+    /// Close, average sentiment and tweet count for every calendar day, for charting
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub daily_timeseries: Vec<DailyPoint>,
+
+    /// Temporal patterns between tweets found by the Prolog rules, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<DetectedPattern>,
+
+    /// Fingerprint of the sentiment lexicon that scored these tweets
+    /// (None for results saved before it was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexicon_hash: Option<String>,
+
+    /// Name of that lexicon: the CEO's domain, or "default" for the built-in one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexicon: Option<String>,
+
+    /// Most recent close in the fetched price data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_close: Option<f64>,
 
     /// Stock performance over last 1 week
     pub performance_1w: Option<f64>,
@@ -122,6 +629,14 @@ pub struct AnalysisResult {
     /// Stock performance over last 3 months
     pub performance_3m: Option<f64>,
 
+    /// Why a 1w/1m/3m performance is missing, e.g. "1m: ..." (one line each)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub performance_notes: Vec<String>,
+
+    /// Problems with the inputs that left statistics empty, e.g. a flat price series
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+
     /// Count of positive tweets
     #[serde(default)]
     pub positive_tweets: usize,
@@ -139,6 +654,230 @@ pub struct AnalysisResult {
     
     /// Number of tweets with available price data
     pub tweets_with_price_data: usize,
+
+    /// Tweets dated after the last fetched price (e.g. today's, before the close posts)
+    #[serde(default)]
+    pub tweets_newer_than_prices: usize,
+
+    /// Tweets dated before the first fetched price
+    #[serde(default)]
+    pub tweets_older_than_prices: usize,
+
+    /// Tweets dropped for being older than `--max-tweet-age-days`
+    #[serde(default)]
+    pub tweets_past_max_age: usize,
+
+    /// Tweets whose forward changes were cleared because an earlier or
+    /// more-engaged tweet in the same lookahead window got the credit
+    #[serde(default)]
+    pub attribution_suppressed: usize,
+
+    /// Set to `--prolog-max-solutions` when `impactful_tweet/1` had more
+    /// solutions than that; tweets past the cap were left unclassified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_solutions_cap: Option<usize>,
+
+    /// Tweets whose lookahead window overlapped a known news event
+    #[serde(default)]
+    pub tweets_near_news: usize,
+
+    /// Tweets with both substantial positive and negative signals
+    #[serde(default)]
+    pub mixed_tweets: usize,
+
+    /// Repeated posts folded into an earlier tweet with the same text
+    #[serde(default)]
+    pub repeated_tweets_collapsed: usize,
+
+    /// Impacts left out of `impacts` by `--store-max-impacts`, and how many of
+    /// them were impactful; statistics still cover them
+    #[serde(default)]
+    pub impacts_trimmed: usize,
+    #[serde(default)]
+    pub impactful_trimmed: usize,
+
+    /// Pearson correlation between tweet length (words) and the 1-day change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_correlation_1d: Option<f64>,
+
+    /// Least-squares slope of the 1-day change on sentiment (% per unit of sentiment)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slope_1d: Option<f64>,
+
+    /// Intercept of the same fit: expected 1-day change for a neutral tweet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intercept_1d: Option<f64>,
+
+    /// R² of the 1-day fit (share of change variance explained by sentiment)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r_squared_1d: Option<f64>,
+
+    /// Least-squares slope of sentiment over time (sentiment per day);
+    /// positive when the CEO is becoming more positive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment_trend_slope: Option<f64>,
+
+    /// Most consecutive positive tweets (in posting order, neutral tweets break a run)
+    #[serde(default)]
+    pub longest_positive_streak: usize,
+    /// Most consecutive negative tweets
+    #[serde(default)]
+    pub longest_negative_streak: usize,
+    /// Percentage of streaks of `analysis::MIN_STREAK_LENGTH`+ tweets whose
+    /// last tweet was followed by a 1-day move against the streak
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streak_reversal_rate: Option<f64>,
+
+    /// Mean word count of the tweets classified as impactful
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_words_impactful: Option<f64>,
+
+    /// Pearson correlation between ln(1 + likes + retweets) and the absolute
+    /// change over `engagement_window_days`, regardless of sentiment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engagement_move_correlation: Option<f64>,
+
+    /// Forward window (days) of `engagement_move_correlation`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engagement_window_days: Option<i64>,
+
+    /// Pearson correlation between |sentiment| and next-day abnormal volume
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_correlation: Option<f64>,
+
+    /// Pearson correlation between |sentiment| and the next-day intraday range
+    /// ratio: does tweeting raise volatility regardless of direction?
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volatility_impact: Option<f64>,
+
+    /// Percentage of tweets (with volume data) followed by a next-day volume spike
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_spike_rate: Option<f64>,
+
+    /// Days with posting volume well above the CEO's baseline rate, with their tweet counts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub activity_spikes: Vec<(NaiveDate, usize)>,
+
+    /// Mean |1d change| after tweets on activity-spike days divided by the mean on other days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity_spike_move_ratio: Option<f64>,
+
+    /// Mean |opening gap| after tweets posted outside regular market hours
+    /// divided by the mean after tweets posted during them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub off_hours_gap_ratio: Option<f64>,
+
+    /// Dates where primary and secondary closes disagreed beyond the tolerance,
+    /// as (date, primary close, secondary close); empty without a secondary provider
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub price_discrepancies: Vec<(NaiveDate, f64, f64)>,
+
+    /// First and last day of each run of identical closes long enough to
+    /// suggest a halted, delisted or stale feed; tweets on those days are
+    /// left out of the correlations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_price_ranges: Vec<(NaiveDate, NaiveDate)>,
+
+    /// CEO's follower count at fetch time (None when unknown, e.g. scraper path)
+    #[serde(default)]
+    pub follower_count: Option<u64>,
+
+    /// Settings that produced this result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_config: Option<RunConfig>,
+
+    /// How long each pipeline stage took, with `--timings`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timings: Vec<StageTiming>,
+}
+
+/// Effective configuration of the run that produced a result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunConfig {
+    /// Analyzer version
+    pub tool_version: String,
+    
+    /// When the run happened
+    pub generated_at: DateTime<Utc>,
+    
+    /// Requested lookback window in days
+    pub days: u32,
+    
+    /// Extra trading days of prices fetched before the window (indicators only)
+    #[serde(default)]
+    pub price_buffer_days: u32,
+    
+    /// Daily price used for changes and performance
+    pub price_basis: PriceBasis,
+    
+    /// Whether changes and performance are simple or log returns
+    #[serde(default)]
+    pub return_type: ReturnType,
+    
+    /// Calendar days a forward price may be off its target day (0 = exact only)
+    #[serde(default)]
+    pub price_tolerance_days: u32,
+    
+    /// Calendar days a 1w/1m/3m performance start price may sit before its target day
+    #[serde(default)]
+    pub performance_tolerance_days: u32,
+    
+    /// How repeated tweets were collapsed
+    #[serde(default)]
+    pub repeat_matching: RepeatMatching,
+    
+    /// Which tweet got credit for moves in overlapping lookahead windows
+    #[serde(default)]
+    pub attribution: AttributionMode,
+    
+    /// Tweets shorter than this many words were left out (0 = none)
+    #[serde(default)]
+    pub min_words: usize,
+    
+    /// News-adjacent tweets were left out of the correlations
+    #[serde(default)]
+    pub exclude_news_from_correlation: bool,
+    
+    /// Mixed-signal tweets were left out of the correlations
+    #[serde(default)]
+    pub exclude_mixed_from_correlation: bool,
+    
+    /// Tweets older than this many days were left out (None = no cutoff)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tweet_age_days: Option<u32>,
+    
+    /// Shares outstanding used for market cap changes (None = percent only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shares_outstanding: Option<u64>,
+    
+    /// Forward windows (days) price changes were measured over
+    pub lookahead_days: Vec<i64>,
+    
+    /// Gain (%) a positive tweet needs to count as a "rise"
+    pub rise_threshold_pct: f64,
+    
+    /// Forward windows (days) of the rise matrix
+    #[serde(default)]
+    pub rise_windows: Vec<i64>,
+    
+    /// Rise thresholds (%) of the rise matrix
+    #[serde(default)]
+    pub rise_thresholds_pct: Vec<f64>,
+    
+    /// Minimum |sentiment| for the impactful rule
+    pub impact_sentiment_threshold: f64,
+    
+    /// Minimum |change| (%) for the impactful rule
+    pub impact_move_threshold_pct: f64,
+    
+    /// Fingerprint of the sentiment lexicon
+    pub lexicon_hash: String,
+    
+    /// Where tweets came from ("twitter_api" or "scraper")
+    pub tweet_source: String,
+    
+    /// Stock price endpoint used
+    pub stock_provider: String,
 }
 
 impl AnalysisResult {
@@ -149,19 +888,453 @@ impl AnalysisResult {
             ticker,
             start_date,
             end_date,
+            status: ResultStatus::Ok,
+            status_reason: None,
             impacts: Vec::new(),
             correlation_1d: None,
             correlation_3d: None,
-            positive_tweets_with_rise_1d: 0.0,
-            positive_tweets_with_rise_3d: 0.0,
+            correlation_0d: None,
+            correlation_1d_with_neutral: None,
+            neutral_excluded_from_correlation: 0,
+            correlations: Vec::new(),
+            positive_tweets_with_rise_1d: None,
+            positive_tweets_with_rise_3d: None,
+            rise_1d_interval: None,
+            rise_3d_interval: None,
+            rise_matrix: None,
+            statistics: BTreeMap::new(),
+            calibration: Vec::new(),
+            daily_timeseries: Vec::new(),
+            patterns: Vec::new(),
+            lexicon_hash: None,
+            lexicon: None,
+            last_close: None,
             performance_1w: None,
             performance_1m: None,
             performance_3m: None,
+            performance_notes: Vec::new(),
+            warnings: Vec::new(),
             positive_tweets: 0,
             negative_tweets: 0,
             neutral_tweets: 0,
             total_tweets: 0,
             tweets_with_price_data: 0,
+            tweets_newer_than_prices: 0,
+            tweets_older_than_prices: 0,
+            tweets_past_max_age: 0,
+            attribution_suppressed: 0,
+            impact_solutions_cap: None,
+            tweets_near_news: 0,
+            mixed_tweets: 0,
+            repeated_tweets_collapsed: 0,
+            impacts_trimmed: 0,
+            impactful_trimmed: 0,
+            length_correlation_1d: None,
+            slope_1d: None,
+            intercept_1d: None,
+            r_squared_1d: None,
+            sentiment_trend_slope: None,
+            longest_positive_streak: 0,
+            longest_negative_streak: 0,
+            streak_reversal_rate: None,
+            avg_words_impactful: None,
+            engagement_move_correlation: None,
+            engagement_window_days: None,
+            volume_correlation: None,
+            volatility_impact: None,
+            volume_spike_rate: None,
+            activity_spikes: Vec::new(),
+            activity_spike_move_ratio: None,
+            off_hours_gap_ratio: None,
+            price_discrepancies: Vec::new(),
+            stale_price_ranges: Vec::new(),
+            follower_count: None,
+            run_config: None,
+            timings: Vec::new(),
+        }
+    }
+
+    /// Reach-weighted influence score used to rank CEOs against each other
+    ///
+    /// Mean absolute 1-day move after tweets, scaled by log10 of the follower
+    /// count, so a large audience moving the stock outranks a small one moving
+    /// it the same amount. None when followers are unknown or no tweet has a
+    /// 1-day change (unknown is never treated as zero).
+    pub fn influence_score(&self) -> Option<f64> {
+        let followers = self.follower_count?;
+        let moves: Vec<f64> = self.impacts.iter()
+            .filter_map(|i| i.change_1d)
+            .map(f64::abs)
+            .collect();
+        
+        if moves.is_empty() || followers == 0 {
+            return None;
+        }
+        
+        let mean_move = moves.iter().sum::<f64>() / moves.len() as f64;
+        Some(mean_move * (followers as f64).log10())
+    }
+
+    /// Keep only the `max` most notable impacts, for storage
+    ///
+    /// Ranks by rule tier, then the larger of the 1d/3d moves, then
+    /// engagement; kept impacts stay in their current order. Statistics are
+    /// left as computed over every impact. Returns how many were dropped.
+    pub fn trim_impacts(&mut self, max: usize) -> usize {
+        if self.impacts.len() <= max {
+            return 0;
+        }
+        
+        let strength = |impact: &TweetImpact| {
+            let biggest_move = [impact.change_1d, impact.change_3d]
+                .into_iter()
+                .flatten()
+                .map(f64::abs)
+                .fold(0.0, f64::max);
+            let engagement = impact.tweet.retweet_count as u64 + impact.tweet.like_count as u64;
+            (impact.impact_level, biggest_move, engagement)
+        };
+        let mut ranked: Vec<usize> = (0..self.impacts.len()).collect();
+        ranked.sort_by(|a, b| {
+            let (level_a, move_a, engagement_a) = strength(&self.impacts[*a]);
+            let (level_b, move_b, engagement_b) = strength(&self.impacts[*b]);
+            level_b.cmp(&level_a)
+                .then(move_b.total_cmp(&move_a))
+                .then(engagement_b.cmp(&engagement_a))
+        });
+        let mut keep = vec![false; self.impacts.len()];
+        for idx in ranked.into_iter().take(max) {
+            keep[idx] = true;
+        }
+        
+        let mut kept = keep.into_iter();
+        let before = self.impacts.len();
+        let impactful_before = self.impacts.iter().filter(|i| i.is_impactful).count();
+        self.impacts.retain(|_| kept.next().unwrap_or(false));
+        
+        let dropped = before - self.impacts.len();
+        self.impacts_trimmed += dropped;
+        self.impactful_trimmed += impactful_before - self.impacts.iter().filter(|i| i.is_impactful).count();
+        dropped
+    }
+
+    /// Lightweight view for list pages and logs (no impact vector)
+    pub fn summary(&self) -> AnalysisSummary {
+        AnalysisSummary {
+            ceo_handle: self.ceo_handle.clone(),
+            ticker: self.ticker.clone(),
+            total_tweets: self.total_tweets,
+            correlation_1d: self.correlation_1d,
+            impactful_count: self.impacts.iter().filter(|i| i.is_impactful).count() + self.impactful_trimmed,
+            computed_at: self.run_config.as_ref().map(|c| c.generated_at),
+        }
+    }
+}
+
+/// Share of positive tweets followed by a rise, per forward window and threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiseMatrix {
+    /// Forward windows (days), in row order
+    pub windows: Vec<i64>,
+    
+    /// Rise thresholds (%), in column order
+    pub thresholds_pct: Vec<f64>,
+    
+    /// Percentage of positive tweets whose change over the window exceeded
+    /// the threshold (None when there were no positive tweets)
+    pub rates: Vec<Vec<Option<f64>>>,
+
+    /// 95% Wilson score interval (low %, high %) around each rate
+    #[serde(default)]
+    pub intervals: Vec<Vec<Option<(f64, f64)>>>,
+}
+
+/// Realized forward returns of the tweets whose sentiment fell in one range
+///
+/// One row of the sentiment calibration table: if the scores mean anything,
+/// average returns should rise from the most negative bucket to the most
+/// positive one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationBucket {
+    /// Bucket name, e.g. "strong positive"
+    pub label: String,
+
+    /// Sentiment range covered (the neutral bucket is exactly 0)
+    pub min_sentiment: f64,
+    pub max_sentiment: f64,
+
+    /// Tweets in the bucket, and how many of them have a 1-day change
+    pub tweets: usize,
+    pub tweets_with_change: usize,
+
+    /// Mean realized change over each window (None when no tweet has one)
+    pub avg_change_1d: Option<f64>,
+    pub avg_change_3d: Option<f64>,
+
+    /// Percentage of the bucket's tweets with a 1-day change that were followed by a gain
+    pub up_share_1d: Option<f64>,
+}
+
+/// One calendar day of `AnalysisResult::daily_timeseries`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyPoint {
+    pub date: NaiveDate,
+
+    /// Close price (None on days without trading)
+    pub close: Option<f64>,
+
+    /// Mean sentiment of the day's tweets (None on days without tweets)
+    pub avg_sentiment: Option<f64>,
+
+    pub tweet_count: usize,
+}
+
+/// One (sentiment, change) pair that fed a correlation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelationPair {
+    /// Forward window of the change in days (0 = same-day open to close)
+    pub window_days: i64,
+    pub tweet_id: String,
+    pub date: NaiveDate,
+    pub sentiment: f64,
+    /// Price change over the window (%, or log% for log returns)
+    pub change: f64,
+}
+
+/// Wall-clock time spent in one pipeline stage (`--timings`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    /// fetch_tweets, fetch_prices, analyze, apply_rules or render
+    pub stage: String,
+    pub seconds: f64,
+}
+
+impl StageTiming {
+    /// Time spent in `stage` since it `started`
+    pub fn since(stage: &str, started: Instant) -> Self {
+        StageTiming { stage: stage.to_string(), seconds: started.elapsed().as_secs_f64() }
+    }
+}
+
+/// Total seconds per stage across runs, in the order stages first appear
+pub fn sum_timings<'a>(runs: impl IntoIterator<Item = &'a [StageTiming]>) -> Vec<StageTiming> {
+    let mut totals: Vec<StageTiming> = Vec::new();
+    for timing in runs.into_iter().flatten() {
+        match totals.iter_mut().find(|t| t.stage == timing.stage) {
+            Some(total) => total.seconds += timing.seconds,
+            None => totals.push(timing.clone()),
+        }
+    }
+    totals
+}
+
+/// One window's correlation with its sample size and, when it's missing, why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelationDetail {
+    /// Forward window in days (0 = same-day open to close)
+    pub window_days: i64,
+    /// Pearson r, as in `correlation_0d` / `_1d` / `_3d`
+    pub value: Option<f64>,
+    /// (sentiment, change) pairs it was computed from
+    pub n: usize,
+    /// Why `value` is None, e.g. "only 1 tweet with a 1d price change"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Which temporal rule a pair of tweets matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternKind {
+    /// Two impactful tweets within a few days of each other (`tweet_cluster/2`)
+    TweetCluster,
+
+    /// A positive tweet followed by a negative one within a week (`sentiment_reversal/2`)
+    SentimentReversal,
+}
+
+/// A pair of tweets matched by one of the temporal Prolog rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedPattern {
+    pub kind: PatternKind,
+
+    /// Tweet ids of the earlier and the later tweet
+    pub first_tweet_id: String,
+    pub second_tweet_id: String,
+
+    /// Calendar days between the two tweets (0 = same day)
+    pub days_apart: i64,
+}
+
+/// Compact per-CEO summary of an analysis result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisSummary {
+    /// CEO handle analyzed
+    pub ceo_handle: String,
+    
+    /// Stock ticker analyzed
+    pub ticker: String,
+    
+    /// Total number of tweets analyzed
+    pub total_tweets: usize,
+    
+    /// Sentiment vs 1-day change correlation
+    pub correlation_1d: Option<f64>,
+    
+    /// Tweets classified as impactful by the Prolog rules
+    pub impactful_count: usize,
+    
+    /// When the result was computed (None for results saved before run configs existed)
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+/// Tweets and prices exactly as fetched for one CEO, so the analysis can be
+/// re-run later without network access
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawFetch {
+    pub ceo_handle: String,
+    pub ticker: String,
+    pub fetched_at: DateTime<Utc>,
+
+    /// Lookback window the tweets were fetched for
+    pub days: u32,
+
+    /// Where the tweets and prices came from (recorded in the run config)
+    pub tweet_source: String,
+    pub stock_provider: String,
+
+    #[serde(default)]
+    pub follower_count: Option<u64>,
+
+    pub tweets: Vec<Tweet>,
+    pub prices: Vec<PricePoint>,
+}
+
+impl RawFetch {
+    /// Tweets a new `days`-day timeline fetch can build on incrementally
+    ///
+    /// Only API timeline fetches covering at least that window qualify; the
+    /// scraper and archive paths don't return a contiguous newest-first run.
+    pub fn cached_timeline(&self, days: u32) -> Vec<Tweet> {
+        if self.tweet_source == "twitter_api" && self.days >= days {
+            self.tweets.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Subset of configured CEO handles to process in a batch
+///
+/// Matching ignores case and a leading `@`. An empty `only` list selects
+/// every handle; `skip` always wins.
+#[derive(Debug, Clone, Default)]
+pub struct HandleFilter {
+    pub only: Vec<String>,
+    pub skip: Vec<String>,
+}
+
+impl HandleFilter {
+    /// Build a filter from comma-separated lists (as passed in a query string)
+    pub fn from_lists(only: Option<&str>, skip: Option<&str>) -> Self {
+        let split = |list: Option<&str>| -> Vec<String> {
+            list.unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|h| !h.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self { only: split(only), skip: split(skip) }
+    }
+
+    /// Whether `handle` should be processed
+    pub fn selects(&self, handle: &str) -> bool {
+        let matches = |list: &[String]| list.iter().any(|h| h.trim_start_matches('@').eq_ignore_ascii_case(handle));
+        (self.only.is_empty() || matches(&self.only)) && !matches(&self.skip)
+    }
+
+    /// Handles in `only` that matched none of `handles` (likely typos)
+    pub fn unknown_handles<'a>(&'a self, handles: &[&str]) -> Vec<&'a str> {
+        self.only
+            .iter()
+            .map(|h| h.trim_start_matches('@'))
+            .filter(|h| !handles.iter().any(|known| known.eq_ignore_ascii_case(h)))
+            .collect()
+    }
+}
+
+/// Pairwise correlation of daily average sentiment across CEOs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentimentMatrix {
+    /// When the matrix was computed
+    pub generated_at: DateTime<Utc>,
+    
+    /// CEO handles, in row/column order
+    pub labels: Vec<String>,
+    
+    /// Pearson correlation for each pair over their common tweet dates
+    /// (None when fewer than two shared dates or no variance)
+    pub matrix: Vec<Vec<Option<f64>>>,
+    
+    /// Number of common dates each correlation was computed over
+    pub common_days: Vec<Vec<usize>>,
+}
+
+/// Serde adapter for plain `f64` fields that may end up NaN or infinite
+///
+/// serde_json writes non-finite floats as `null`, which a plain `f64` can't
+/// read back, so they are written as 0.0 instead and `null` reads as 0.0.
+/// `Option<f64>` fields need no adapter: non-finite values round-trip as None.
+mod finite_f64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(if value.is_finite() { *value } else { 0.0 })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(0.0))
+    }
+}
+
+/// Fixtures shared by the unit tests of every module
+#[cfg(test)]
+pub mod test_support {
+    use super::{PricePoint, Tweet};
+    use chrono::{DateTime, TimeZone, Utc};
+
+    /// 2025-01-`day` at `hour`:00 UTC, the month most fixtures are set in
+    pub fn jan(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap()
+    }
+
+    /// An unscored tweet without engagement; tests override the fields they need
+    pub fn test_tweet(id: &str, text: &str, created_at: DateTime<Utc>) -> Tweet {
+        Tweet {
+            id: id.to_string(),
+            text: text.to_string(),
+            created_at,
+            retweet_count: 0,
+            like_count: 0,
+            sentiment: None,
+            sentiment_disagreement: None,
+            pos_hits: 0,
+            neg_hits: 0,
+            mixed: false,
+        }
+    }
+
+    /// A flat TSLA bar (open = high = low = close) with a volume of 1000
+    pub fn test_price(date: DateTime<Utc>, close: f64) -> PricePoint {
+        PricePoint {
+            ticker: "TSLA".to_string(),
+            date,
+            open: close,
+            close,
+            high: close,
+            low: close,
+            volume: 1000,
         }
     }
 }
@@ -169,6 +1342,105 @@ impl AnalysisResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::test_support::test_tweet;
+
+    #[test]
+    fn test_sum_timings_keeps_stage_order() {
+        let timing = |stage: &str, seconds| StageTiming { stage: stage.to_string(), seconds };
+        let first = [timing("fetch_tweets", 1.0), timing("analyze", 0.5)];
+        let second = [timing("fetch_tweets", 2.0), timing("fetch_prices", 0.25), timing("analyze", 0.5)];
+        
+        assert_eq!(sum_timings([&first[..], &second[..]]), vec![
+            timing("fetch_tweets", 3.0),
+            timing("analyze", 1.0),
+            timing("fetch_prices", 0.25),
+        ]);
+        assert!(sum_timings(std::iter::empty()).is_empty());
+    }
+
+    #[test]
+    fn test_non_finite_fields_serialize_as_valid_json() {
+        let mut result = AnalysisResult::new("ceo".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        result.positive_tweets_with_rise_1d = Some(f64::NAN);
+        result.positive_tweets_with_rise_3d = Some(f64::INFINITY);
+        result.correlation_1d = Some(f64::NAN);
+        
+        let mut impact = TweetImpact::new(Tweet {
+            sentiment: Some(f64::NAN),
+            ..test_tweet("1", "great", Utc::now())
+        });
+        impact.surprise_score = f64::NAN;
+        impact.change_1d = Some(f64::NEG_INFINITY);
+        result.impacts.push(impact);
+        
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(!json.contains("NaN") && !json.contains("inf"));
+        
+        let restored: AnalysisResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.positive_tweets_with_rise_1d, None);
+        assert_eq!(restored.positive_tweets_with_rise_3d, None);
+        assert_eq!(restored.correlation_1d, None);
+        assert_eq!(restored.impacts[0].surprise_score, 0.0);
+        assert_eq!(restored.impacts[0].change_1d, None);
+        assert_eq!(restored.impacts[0].tweet.sentiment, None);
+    }
+
+    #[test]
+    fn test_preprocess_tweet_text() {
+        let text = "@SpaceX  Launch was GREAT 🚀🔥 https://x.com/launch";
+        
+        assert_eq!(preprocess_tweet_text(text, TextOptions::SENTIMENT), "launch was great");
+        assert_eq!(preprocess_tweet_text(text, TextOptions::WORDS), "@SpaceX Launch was GREAT 🚀🔥");
+        assert_eq!(preprocess_tweet_text(text, TextOptions::REPEATS), "@spacex launch was great 🚀🔥");
+        
+        // A lone "@" isn't a mention
+        assert_eq!(preprocess_tweet_text("meet @ noon", TextOptions::SENTIMENT), "meet @ noon");
+    }
+
+    #[test]
+    fn test_tweet_word_count_skips_links() {
+        let tweet = test_tweet("1", "Starship launch  today https://x.com/launch", Utc::now());
+        assert_eq!(tweet.word_count(), 3);
+    }
+
+    #[test]
+    fn test_trim_impacts_keeps_most_notable() {
+        let mut result = AnalysisResult::new("ceo".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        for (id, change, level) in [
+            ("quiet", 0.5, ImpactLevel::None),
+            ("impactful", 4.0, ImpactLevel::Impactful),
+            ("big_move", -9.0, ImpactLevel::None),
+            ("small", 1.0, ImpactLevel::None),
+        ] {
+            let mut impact = TweetImpact::new(test_tweet(id, "", Utc::now()));
+            impact.change_1d = Some(change);
+            impact.impact_level = level;
+            impact.is_impactful = level != ImpactLevel::None;
+            result.impacts.push(impact);
+        }
+        result.total_tweets = 4;
+        
+        assert_eq!(result.trim_impacts(2), 2);
+        let ids: Vec<&str> = result.impacts.iter().map(|i| i.tweet.id.as_str()).collect();
+        assert_eq!(ids, vec!["impactful", "big_move"]);
+        assert_eq!(result.impacts_trimmed, 2);
+        assert_eq!(result.summary().total_tweets, 4);
+        
+        assert_eq!(result.trim_impacts(0), 2);
+        assert_eq!(result.summary().impactful_count, 1);
+        assert_eq!(result.trim_impacts(5), 0);
+    }
+
+    #[test]
+    fn test_handle_filter_only_and_skip() {
+        let filter = HandleFilter::from_lists(Some("@ElonMusk, tim_cook"), Some("tim_cook"));
+
+        assert!(filter.selects("elonmusk"));
+        assert!(!filter.selects("tim_cook"));
+        assert!(!filter.selects("sundarpichai"));
+        assert!(HandleFilter::default().selects("sundarpichai"));
+        assert_eq!(filter.unknown_handles(&["elonmusk"]), vec!["tim_cook"]);
+    }
 
     #[test]
     fn test_price_point_daily_change() {
@@ -199,4 +1471,26 @@ mod tests {
         
         assert_eq!(price.daily_change_percent(), 0.0);
     }
+
+    #[test]
+    fn test_return_type_change_and_nonpositive_guard() {
+        assert!((ReturnType::Simple.change(100.0, 110.0).unwrap() - 10.0).abs() < 1e-9);
+        assert!((ReturnType::Log.change(100.0, 110.0).unwrap() - 9.531017980432486).abs() < 1e-9);
+        assert_eq!(ReturnType::Simple.change(0.0, 110.0), None);
+        assert_eq!(ReturnType::Log.change(100.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_influence_score_scales_with_followers() {
+        let mut result = AnalysisResult::new("ceo".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        let mut impact = TweetImpact::new(Tweet { sentiment: Some(0.5), ..test_tweet("1", "", Utc::now()) });
+        impact.price_at_tweet = Some(100.0);
+        impact.change_1d = Some(-3.0);
+        result.impacts.push(impact);
+        
+        assert_eq!(result.influence_score(), None);
+        
+        result.follower_count = Some(1_000_000);
+        assert!((result.influence_score().unwrap() - 18.0).abs() < 1e-9);
+    }
 }
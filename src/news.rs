@@ -0,0 +1,71 @@
+//! Known news events used as a confounder check.
+//!
+//! Price moves often coincide with news the CEO didn't tweet about. Users can
+//! supply a CSV of news dates so impacts whose lookahead window overlaps an
+//! event can be flagged (and optionally left out of the correlation).
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::fs;
+
+/// A dated news event
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewsEvent {
+    /// Day the news broke
+    pub date: NaiveDate,
+    
+    /// Short description (optional in the CSV)
+    pub headline: String,
+}
+
+/// Load news events from a `date,headline` CSV file
+///
+/// Dates are `YYYY-MM-DD`. Blank lines, `#` comments and a header row are skipped.
+pub fn load_news_csv(path: &str) -> Result<Vec<NewsEvent>> {
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read news file: {}", path))?;
+    
+    parse_news_csv(&content).context(format!("Failed to parse news file: {}", path))
+}
+
+/// Parse `date,headline` CSV content into news events
+fn parse_news_csv(content: &str) -> Result<Vec<NewsEvent>> {
+    let mut events = Vec::new();
+    
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let (date_str, headline) = match line.split_once(',') {
+            Some((date, headline)) => (date.trim(), headline.trim().trim_matches('"')),
+            None => (line, ""),
+        };
+        
+        match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+            Ok(date) => events.push(NewsEvent { date, headline: headline.to_string() }),
+            // Tolerate a header row
+            Err(_) if events.is_empty() && line_no == 0 => continue,
+            Err(_) => anyhow::bail!("Line {}: invalid date '{}'", line_no + 1, date_str),
+        }
+    }
+    
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_news_csv_skips_header_and_comments() {
+        let csv = "date,headline\n# earnings season\n2025-01-30,Q4 earnings\n2025-02-03\n";
+        let events = parse_news_csv(csv).unwrap();
+        
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].date, NaiveDate::from_ymd_opt(2025, 1, 30).unwrap());
+        assert_eq!(events[0].headline, "Q4 earnings");
+        assert_eq!(events[1].headline, "");
+    }
+}
@@ -0,0 +1,819 @@
+//! Text renderers for analysis results.
+//!
+//! Shared by the CLI (`--output-format csv|markdown`) and the web server's
+//! download endpoints so each format has exactly one implementation.
+
+use crate::models::{AnalysisResult, CorrelationPair, PatternKind, ResultStatus};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exchange timezone used for period boundaries (US listings)
+const MARKET_TIMEZONE: Tz = chrono_tz::America::New_York;
+
+/// Set when terminal output must avoid ANSI colors and non-ASCII symbols
+static PLAIN_TERMINAL: AtomicBool = AtomicBool::new(false);
+
+/// Unicode symbols used in terminal output and their ASCII fallbacks
+const ASCII_FALLBACKS: [(char, &str); 4] = [('═', "-"), ('→', "->"), ('×', "x"), ('≥', ">=")];
+
+/// Decide the terminal style once at startup
+///
+/// Output is plain (no colors, ASCII rules and arrows) with `--no-color`,
+/// when `NO_COLOR` is set, or when stdout isn't a terminal (CI logs, pipes).
+pub fn init_terminal_style(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let plain = no_color || no_color_env || !std::io::stdout().is_terminal();
+    PLAIN_TERMINAL.store(plain, Ordering::Relaxed);
+}
+
+/// Text for the terminal, with Unicode symbols swapped for ASCII in plain mode
+pub fn terminal_text(text: &str) -> Cow<'_, str> {
+    if !PLAIN_TERMINAL.load(Ordering::Relaxed) {
+        return Cow::Borrowed(text);
+    }
+    ascii_fallback(text)
+}
+
+fn ascii_fallback(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match ASCII_FALLBACKS.iter().find(|(symbol, _)| *symbol == c) {
+            Some((_, ascii)) => out.push_str(ascii),
+            None => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Default price (USD) below which a stock is treated as a penny stock
+pub const DEFAULT_PENNY_STOCK_FLOOR: f64 = 1.0;
+
+/// Format a price with precision suited to its magnitude
+///
+/// Sub-dollar stocks get four decimals so small moves stay visible.
+pub fn format_price(price: f64) -> String {
+    if price.abs() < 1.0 {
+        format!("${:.4}", price)
+    } else {
+        format!("${:.2}", price)
+    }
+}
+
+/// Signed dollar amount scaled to thousands, millions, billions or trillions
+pub fn format_usd_compact(amount: f64) -> String {
+    let sign = if amount < 0.0 { "-" } else { "+" };
+    let abs = amount.abs();
+    let (scaled, suffix) = match abs {
+        a if a >= 1e12 => (a / 1e12, "T"),
+        a if a >= 1e9 => (a / 1e9, "B"),
+        a if a >= 1e6 => (a / 1e6, "M"),
+        a if a >= 1e3 => (a / 1e3, "K"),
+        a => (a, ""),
+    };
+    format!("{}${:.2}{}", sign, scaled, suffix)
+}
+
+/// The first `max_chars` characters of `text`, with "..." when cut short
+///
+/// Counts characters rather than bytes, so it never splits a multibyte one.
+pub fn text_preview(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Bold heading, unless colors are disabled
+pub fn bold(text: &str) -> String {
+    paint(text, "1")
+}
+
+/// A signed change, green when up and red when down, unless colors are disabled
+pub fn colored_change(value: f64, unit: &str) -> String {
+    let text = format!("{:+.2}{}", value, unit);
+    match value.partial_cmp(&0.0) {
+        Some(std::cmp::Ordering::Greater) => paint(&text, "32"),
+        Some(std::cmp::Ordering::Less) => paint(&text, "31"),
+        _ => text,
+    }
+}
+
+fn paint(text: &str, sgr: &str) -> String {
+    if PLAIN_TERMINAL.load(Ordering::Relaxed) {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", sgr, text)
+    }
+}
+
+/// Period impacts are grouped into for aggregated exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregatePeriod {
+    /// Monday-based weeks
+    Week,
+    /// Calendar months
+    Month,
+}
+
+/// How the highlighted tweets are chosen and ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TweetSort {
+    /// Tweets flagged impactful by the Prolog rules
+    Impactful,
+    /// All tweets, highest surprise score first
+    Surprise,
+}
+
+/// One aggregated row: averages of sentiment and forward changes, summed engagement
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodRow {
+    /// First day of the period (market timezone)
+    pub period_start: NaiveDate,
+    pub tweets: usize,
+    pub avg_sentiment: Option<f64>,
+    pub avg_change_1d: Option<f64>,
+    pub avg_change_3d: Option<f64>,
+    pub retweets: u64,
+    pub likes: u64,
+    pub impactful: usize,
+}
+
+/// Group a result's impacts by week or month of their market-time posting date
+///
+/// Averages skip tweets missing the value, so a period with no priced tweets
+/// has no average change rather than zero.
+pub fn aggregate_impacts(result: &AnalysisResult, period: AggregatePeriod) -> Vec<PeriodRow> {
+    let mut groups: BTreeMap<NaiveDate, Vec<&crate::models::TweetImpact>> = BTreeMap::new();
+    for impact in &result.impacts {
+        groups.entry(period_start(impact.tweet.created_at, period)).or_default().push(impact);
+    }
+    
+    groups
+        .into_iter()
+        .map(|(period_start, impacts)| {
+            let average = |values: Vec<f64>| {
+                (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+            };
+            PeriodRow {
+                period_start,
+                tweets: impacts.len(),
+                avg_sentiment: average(impacts.iter().filter_map(|i| i.tweet.sentiment).collect()),
+                avg_change_1d: average(impacts.iter().filter_map(|i| i.change_1d).collect()),
+                avg_change_3d: average(impacts.iter().filter_map(|i| i.change_3d).collect()),
+                retweets: impacts.iter().map(|i| i.tweet.retweet_count as u64).sum(),
+                likes: impacts.iter().map(|i| i.tweet.like_count as u64).sum(),
+                impactful: impacts.iter().filter(|i| i.is_impactful).count(),
+            }
+        })
+        .collect()
+}
+
+/// First day of the week/month containing `at`, in the market timezone
+fn period_start(at: DateTime<Utc>, period: AggregatePeriod) -> NaiveDate {
+    let local = at.with_timezone(&MARKET_TIMEZONE).date_naive();
+    match period {
+        AggregatePeriod::Week => local - Duration::days(local.weekday().num_days_from_monday() as i64),
+        AggregatePeriod::Month => local.with_day(1).unwrap_or(local),
+    }
+}
+
+/// Copy of `result` listing only impactful tweets
+///
+/// Summary statistics are left as computed over every tweet.
+pub fn impactful_only(result: &AnalysisResult) -> AnalysisResult {
+    let mut filtered = result.clone();
+    filtered.impacts.retain(|i| i.is_impactful);
+    filtered
+}
+
+/// Render aggregated rows as CSV
+pub fn render_aggregate_csv(rows: &[PeriodRow]) -> String {
+    let mut out = String::from(
+        "period_start,tweets,avg_sentiment,avg_change_1d,avg_change_3d,retweets,likes,impactful\n",
+    );
+    
+    for row in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            row.period_start,
+            row.tweets,
+            optional(row.avg_sentiment),
+            optional(row.avg_change_1d),
+            optional(row.avg_change_3d),
+            row.retweets,
+            row.likes,
+            row.impactful,
+        ));
+    }
+    
+    out
+}
+
+/// Render correlation pairs as CSV, one row per (window, tweet)
+pub fn render_pairs_csv(pairs: &[CorrelationPair]) -> String {
+    let mut out = String::from("window_days,tweet_id,date,sentiment,change\n");
+    
+    for pair in pairs {
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            pair.window_days,
+            csv_escape(&pair.tweet_id),
+            pair.date,
+            pair.sentiment,
+            pair.change,
+        ));
+    }
+    
+    out
+}
+
+/// Render every tweet impact as CSV, one row per tweet
+pub fn render_csv(result: &AnalysisResult) -> String {
+    let mut out = String::from(
+        "tweet_id,created_at,sentiment,price_at_tweet,change_1d,change_3d,is_impactful,impact_level,retweets,likes,text\n",
+    );
+    
+    for impact in &result.impacts {
+        let row = [
+            impact.tweet.id.clone(),
+            impact.tweet.created_at.to_rfc3339(),
+            optional(impact.tweet.sentiment),
+            optional(impact.price_at_tweet),
+            optional(impact.change_1d),
+            optional(impact.change_3d),
+            impact.is_impactful.to_string(),
+            impact.impact_level.label().to_string(),
+            impact.tweet.retweet_count.to_string(),
+            impact.tweet.like_count.to_string(),
+            impact.tweet.text.clone(),
+        ];
+        
+        let escaped: Vec<String> = row.iter().map(|field| csv_escape(field)).collect();
+        out.push_str(&escaped.join(","));
+        out.push('\n');
+    }
+    
+    out
+}
+
+/// Render a summary and the impacts table as GitHub-flavored Markdown
+pub fn render_markdown(result: &AnalysisResult) -> String {
+    let mut out = format!(
+        "# @{} / {}\n\n\
+         Period: {} to {}\n\n\
+         | Metric | Value |\n|---|---|\n\
+         | Total tweets | {} |\n\
+         | Tweets with price data | {} |\n\
+         | Correlation (1d) | {} |\n\
+         | Correlation (3d) | {} |\n\
+         | Positive tweets → rise (1d) | {} |\n\
+         | Positive tweets → rise (3d) | {} |\n\n",
+        result.ceo_handle,
+        result.ticker,
+        result.start_date.format("%Y-%m-%d"),
+        result.end_date.format("%Y-%m-%d"),
+        result.total_tweets,
+        result.tweets_with_price_data,
+        optional_fixed(result.correlation_1d, 4),
+        optional_fixed(result.correlation_3d, 4),
+        rate_with_interval(result.positive_tweets_with_rise_1d, result.rise_1d_interval),
+        rate_with_interval(result.positive_tweets_with_rise_3d, result.rise_3d_interval),
+    );
+    
+    out.push_str("| Date | Sentiment | 1d % | 3d % | Impactful | Tweet |\n|---|---|---|---|---|---|\n");
+    for impact in &result.impacts {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            impact.tweet.created_at.format("%Y-%m-%d"),
+            optional_fixed(impact.tweet.sentiment, 2),
+            optional_fixed(impact.change_1d, 2),
+            optional_fixed(impact.change_3d, 2),
+            impact.impact_level.label(),
+            markdown_escape(&impact.tweet.text),
+        ));
+    }
+    
+    out
+}
+
+/// Writes one analysis result in some output format
+///
+/// `display_results` dispatches through a `Box<dyn OutputRenderer>`, so a new
+/// format only needs an implementation here.
+pub trait OutputRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()>;
+}
+
+/// The full result, or rows aggregated by period, as JSON
+pub struct JsonRenderer {
+    pub compact: bool,
+    pub aggregate: Option<AggregatePeriod>,
+}
+
+impl OutputRenderer for JsonRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match self.aggregate {
+            Some(period) => self.write_json(writer, &serde_json::json!({
+                "ceo_handle": result.ceo_handle,
+                "ticker": result.ticker,
+                "period": period,
+                "rows": aggregate_impacts(result, period),
+            })),
+            None => self.write_json(writer, result),
+        }
+    }
+}
+
+impl JsonRenderer {
+    fn write_json<T: Serialize>(&self, writer: &mut dyn Write, value: &T) -> anyhow::Result<()> {
+        if self.compact {
+            serde_json::to_writer(&mut *writer, value)?;
+        } else {
+            serde_json::to_writer_pretty(&mut *writer, value)?;
+        }
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+/// One CSV row per tweet, or per period when aggregating
+pub struct CsvRenderer {
+    pub aggregate: Option<AggregatePeriod>,
+}
+
+impl OutputRenderer for CsvRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let csv = match self.aggregate {
+            Some(period) => render_aggregate_csv(&aggregate_impacts(result, period)),
+            None => render_csv(result),
+        };
+        writer.write_all(csv.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Markdown summary and tweet table
+pub struct MarkdownRenderer;
+
+impl OutputRenderer for MarkdownRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writer.write_all(render_markdown(result).as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Runs several renderers in turn (e.g. the table followed by JSON)
+pub struct ChainRenderer(pub Vec<Box<dyn OutputRenderer>>);
+
+impl OutputRenderer for ChainRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()> {
+        for renderer in &self.0 {
+            renderer.render(result, writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// `writeln!` for the results table, honoring the plain/ASCII terminal style
+macro_rules! table_println {
+    ($w:expr) => {
+        writeln!($w)?
+    };
+    ($w:expr, $($arg:tt)*) => {
+        writeln!($w, "{}", terminal_text(&format!($($arg)*)))?
+    };
+}
+
+/// The human-readable results table
+pub struct TableRenderer {
+    pub sort_by: TweetSort,
+    pub penny_stock_floor: f64,
+}
+
+impl OutputRenderer for TableRenderer {
+    fn render(&self, result: &AnalysisResult, writer: &mut dyn Write) -> anyhow::Result<()> {
+        display_table(result, self.sort_by, self.penny_stock_floor, writer)
+    }
+}
+
+/// Display results as a formatted table
+fn display_table(
+    result: &AnalysisResult,
+    sort_by: TweetSort,
+    penny_stock_floor: f64,
+    w: &mut dyn Write,
+) -> anyhow::Result<()> {
+    table_println!(w, "═══════════════════════════════════════════════════════════════════════════");
+    table_println!(w, "  {}", bold("CEO Tweet Impact Analysis"));
+    table_println!(w, "═══════════════════════════════════════════════════════════════════════════");
+    table_println!(w, "  CEO: @{}", result.ceo_handle);
+    table_println!(w, "  Ticker: {}", result.ticker);
+    if result.status == ResultStatus::NoData {
+        table_println!(w, "  No data: {}", result.status_reason.as_deref().unwrap_or("nothing to analyze"));
+        table_println!(w, "═══════════════════════════════════════════════════════════════════════════");
+        return Ok(());
+    }
+    table_println!(w, "  Period: {} to {}", 
+             result.start_date.format("%Y-%m-%d"),
+             result.end_date.format("%Y-%m-%d"));
+    table_println!(w, "  Total Tweets: {}", result.total_tweets);
+    table_println!(w, "  Tweets with Price Data: {}", result.tweets_with_price_data);
+    if result.tweets_newer_than_prices > 0 {
+        table_println!(w, "  {} tweets newer than available prices", result.tweets_newer_than_prices);
+    }
+    if result.tweets_older_than_prices > 0 {
+        table_println!(w, "  {} tweets older than price history", result.tweets_older_than_prices);
+    }
+    if result.tweets_past_max_age > 0 {
+        table_println!(w, "  {} tweets dropped by --max-tweet-age-days", result.tweets_past_max_age);
+    }
+    if result.attribution_suppressed > 0 {
+        table_println!(w, "  {} tweets inside another tweet's window not credited (--attribution)",
+                 result.attribution_suppressed);
+    }
+    if let Some(cap) = result.impact_solutions_cap {
+        table_println!(w, "  Impact rules stopped after {} solutions (--prolog-max-solutions)", cap);
+    }
+    for note in &result.performance_notes {
+        table_println!(w, "  NOTE: No {} performance", note);
+    }
+    if let Some(close) = result.last_close {
+        table_println!(w, "  Last Close: {}", format_price(close));
+        if close < penny_stock_floor {
+            table_println!(w, "  NOTE: Below {}, where a 3% move is routine noise; consider --rise-thresholds 10,20",
+                     format_price(penny_stock_floor));
+        }
+    }
+    if result.repeated_tweets_collapsed > 0 {
+        table_println!(w, "  Repeated Tweets Collapsed: {}", result.repeated_tweets_collapsed);
+    }
+    if result.tweets_near_news > 0 {
+        table_println!(w, "  Tweets Near Known News: {}", result.tweets_near_news);
+    }
+    if result.mixed_tweets > 0 {
+        table_println!(w, "  Mixed-Signal Tweets: {}", result.mixed_tweets);
+    }
+    if let Some(followers) = result.follower_count {
+        table_println!(w, "  Followers: {}", followers);
+    }
+    table_println!(w, "═══════════════════════════════════════════════════════════════════════════\n");
+    
+    // Changes are log-percent when the run used log returns
+    let unit = result.run_config.as_ref().map_or("%", |c| c.return_type.unit());
+    
+    // Summary statistics
+    table_println!(w, "{}", bold("Summary Statistics:"));
+    if let Some(correlation) = result.correlation_0d {
+        table_println!(w, "  Correlation (sentiment vs same-day open→close): {:.4}", correlation);
+    }
+    table_println!(w, "  Correlation (sentiment vs 1d change): {}", correlation_text(result, 1, result.correlation_1d));
+    if result.neutral_excluded_from_correlation > 0 {
+        table_println!(w, "    without {} neutral tweets; {} with them",
+                 result.neutral_excluded_from_correlation,
+                 result.correlation_1d_with_neutral.map_or("n/a".to_string(), |c| format!("{:.4}", c)));
+    }
+    table_println!(w, "  Correlation (sentiment vs 3d change): {}", correlation_text(result, 3, result.correlation_3d));
+    if let (Some(slope), Some(r_squared)) = (result.slope_1d, result.r_squared_1d) {
+        table_println!(w, "  Regression (1d): each +0.1 sentiment → {:+.2}{} change (R² {:.3})",
+                 slope * 0.1, unit, r_squared);
+    }
+    if let Some(trend) = result.sentiment_trend_slope {
+        let direction = match trend {
+            t if t > 0.0 => "becoming more positive",
+            t if t < 0.0 => "becoming more negative",
+            _ => "no change",
+        };
+        table_println!(w, "  Sentiment trend: {:+.4} per day ({})", trend, direction);
+    }
+    if result.longest_positive_streak > 0 || result.longest_negative_streak > 0 {
+        table_println!(w, "  Longest streaks: {} positive, {} negative tweets in a row",
+                 result.longest_positive_streak, result.longest_negative_streak);
+        if let Some(rate) = result.streak_reversal_rate {
+            table_println!(w, "  Streaks of {}+ followed by a reversal (1d): {:.1}%",
+                     crate::analysis::MIN_STREAK_LENGTH, rate);
+        }
+    }
+    table_println!(w, "  Positive tweets → >{}{} rise (1d): {}", crate::analysis::RISE_THRESHOLD_PCT, unit,
+             rate_with_interval(result.positive_tweets_with_rise_1d, result.rise_1d_interval));
+    table_println!(w, "  Positive tweets → >{}{} rise (3d): {}", crate::analysis::RISE_THRESHOLD_PCT, unit,
+             rate_with_interval(result.positive_tweets_with_rise_3d, result.rise_3d_interval));
+    if let Some(matrix) = &result.rise_matrix {
+        table_println!(w, "  Positive tweets → rise, by window × threshold:");
+        let header: String = matrix.thresholds_pct.iter()
+            .map(|t| format!("{:>9}", format!(">{}{}", t, unit)))
+            .collect();
+        table_println!(w, "    {:>6} {}", "", header);
+        for (window, rates) in matrix.windows.iter().zip(&matrix.rates) {
+            let cells: String = rates.iter()
+                .map(|r| r.map_or(format!("{:>9}", "n/a"), |r| format!("{:>8.1}%", r)))
+                .collect();
+            table_println!(w, "    {:>5}d {}", window, cells);
+        }
+    }
+    if let Some(length_corr) = result.length_correlation_1d {
+        table_println!(w, "  Correlation (tweet length vs 1d change): {:.4}", length_corr);
+    }
+    if let Some(engagement_corr) = result.engagement_move_correlation {
+        table_println!(w, "  Correlation (log engagement vs |{}d change|): {:.4}",
+                 result.engagement_window_days.unwrap_or(crate::analysis::ENGAGEMENT_WINDOW_DAYS), engagement_corr);
+    }
+    if let Some(words) = result.avg_words_impactful {
+        table_println!(w, "  Avg words per impactful tweet: {:.1}", words);
+    }
+    if let Some(volume_corr) = result.volume_correlation {
+        table_println!(w, "  Correlation (|sentiment| vs next-day volume ratio): {:.4}", volume_corr);
+    }
+    if let Some(volatility) = result.volatility_impact {
+        table_println!(w, "  Correlation (|sentiment| vs next-day range ratio): {:.4}", volatility);
+    }
+    if let Some(spike_rate) = result.volume_spike_rate {
+        table_println!(w, "  Tweets → next-day volume spike (≥{:.1}x avg): {:.1}%",
+                 crate::analysis::VOLUME_SPIKE_RATIO, spike_rate);
+    }
+    
+    if !result.activity_spikes.is_empty() {
+        table_println!(w, "  High-activity days (≥{:.0}x daily tweet rate): {}",
+                 crate::analysis::ACTIVITY_SPIKE_RATIO, result.activity_spikes.len());
+        if let Some(ratio) = result.activity_spike_move_ratio {
+            table_println!(w, "  Avg |1d move| on those days vs others: {:.2}x", ratio);
+        }
+    }
+    
+    if let Some(ratio) = result.off_hours_gap_ratio {
+        table_println!(w, "  Avg |opening gap| after off-hours tweets vs market-hours tweets: {:.2}x", ratio);
+    }
+    
+    if result.calibration.iter().any(|b| b.tweets > 0) {
+        table_println!(w, "\n{}", bold("Sentiment Calibration (realized returns per sentiment bucket):"));
+        table_println!(w, "  {:<16} {:>6} {:>9} {:>9} {:>8}", "Bucket", "Tweets", "Avg 1d", "Avg 3d", "Up 1d");
+        let cell = |value: Option<f64>, suffix: &str| value.map_or("n/a".to_string(), |v| format!("{:+.2}{}", v, suffix));
+        for bucket in &result.calibration {
+            table_println!(w, "  {:<16} {:>6} {:>9} {:>9} {:>8}",
+                     bucket.label,
+                     bucket.tweets,
+                     cell(bucket.avg_change_1d, unit),
+                     cell(bucket.avg_change_3d, unit),
+                     bucket.up_share_1d.map_or("n/a".to_string(), |s| format!("{:.0}%", s)));
+        }
+    }
+    
+    let mut cap_moves: Vec<_> = result.impacts.iter()
+        .filter_map(|i| Some((i, i.market_cap_change_1d?)))
+        .collect();
+    if !cap_moves.is_empty() {
+        cap_moves.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        table_println!(w, "\n{}", bold("Biggest Market Cap Moves (1 day after the tweet):"));
+        for (impact, usd) in cap_moves.into_iter().take(3) {
+            table_println!(w, "  {} | {} | {} | {}",
+                     impact.tweet.created_at.format("%Y-%m-%d"),
+                     format_usd_compact(usd),
+                     colored_change(impact.change_1d.unwrap_or(0.0), unit),
+                     impact.tweet.id);
+        }
+    }
+    
+    if !result.patterns.is_empty() {
+        let count = |kind| result.patterns.iter().filter(|p| p.kind == kind).count();
+        table_println!(w, "\n{}", bold("Temporal Patterns (by Prolog rules):"));
+        table_println!(w, "  Impactful tweet clusters (within {} days): {}",
+                 crate::prolog::CLUSTER_WINDOW_DAYS, count(PatternKind::TweetCluster));
+        table_println!(w, "  Sentiment reversals (positive → negative within {} days): {}",
+                 crate::prolog::REVERSAL_WINDOW_DAYS, count(PatternKind::SentimentReversal));
+    }
+    
+    if !result.price_discrepancies.is_empty() {
+        table_println!(w, "\nPrice Provider Discrepancies (primary vs secondary close):");
+        for (date, primary, secondary) in result.price_discrepancies.iter().take(5) {
+            table_println!(w, "  {}: {} vs {}", date, format_price(*primary), format_price(*secondary));
+        }
+        if result.price_discrepancies.len() > 5 {
+            table_println!(w, "  ... and {} more", result.price_discrepancies.len() - 5);
+        }
+    }
+    
+    // Top impactful (or most surprising) tweets
+    let highlighted: Vec<_> = match sort_by {
+        TweetSort::Impactful => {
+            table_println!(w, "\n{}", bold("Most Impactful Tweets (by Prolog rules):"));
+            result.impacts.iter().filter(|i| i.is_impactful).take(5).collect()
+        }
+        TweetSort::Surprise => {
+            table_println!(w, "\n{}", bold("Most Surprising Tweets (by surprise score):"));
+            let mut ranked: Vec<_> = result.impacts.iter().collect();
+            ranked.sort_by(|a, b| b.surprise_score.total_cmp(&a.surprise_score));
+            ranked.into_iter().take(5).collect()
+        }
+    };
+    
+    if highlighted.is_empty() {
+        table_println!(w, "  No tweets classified as impactful");
+    } else {
+        for (idx, impact) in highlighted.iter().enumerate() {
+            let text = text_preview(&impact.tweet.text, 60);
+            
+            table_println!(w, "\n  {}. {} ({})", 
+                     idx + 1,
+                     impact.tweet.created_at.format("%Y-%m-%d"),
+                     text);
+            if impact.is_impactful {
+                table_println!(w, "     Rule: {}", impact.impact_level.label());
+            }
+            table_println!(w, "     Sentiment: {:.2} | 0d: {} | 1d: {} | 3d: {} | Surprise: {:.0}",
+                     impact.tweet.sentiment.unwrap_or(0.0),
+                     colored_change(impact.change_0d.unwrap_or(0.0), unit),
+                     colored_change(impact.change_1d.unwrap_or(0.0), unit),
+                     colored_change(impact.change_3d.unwrap_or(0.0), unit),
+                     impact.surprise_score);
+        }
+    }
+    
+    // Tweets where the keyword scorer and the remote model disagree
+    let review = crate::analysis::review_queue(&result.impacts, 5);
+    if !review.is_empty() {
+        table_println!(w, "\nReview Queue (keyword vs remote sentiment disagreement):");
+        for impact in review {
+            table_println!(w, "  {} | keyword {:.2} | gap {:.2} | {}",
+                     impact.tweet.created_at.format("%Y-%m-%d"),
+                     impact.tweet.sentiment.unwrap_or(0.0),
+                     impact.tweet.sentiment_disagreement.unwrap_or(0.0),
+                     impact.tweet.id);
+        }
+    }
+    
+    table_println!(w, "\n═══════════════════════════════════════════════════════════════════════════\n");
+    
+    Ok(())
+}
+
+/// A correlation to 4 places, or why it's unavailable
+fn correlation_text(result: &AnalysisResult, window_days: i64, value: Option<f64>) -> String {
+    if let Some(value) = value {
+        return format!("{:.4}", value);
+    }
+    match result.correlations.iter().find(|c| c.window_days == window_days).and_then(|c| c.reason.as_deref()) {
+        Some(reason) => format!("unavailable ({})", reason),
+        None => "unavailable".to_string(),
+    }
+}
+
+/// Format an optional number, leaving missing values empty
+fn optional(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Format a success rate with its confidence interval, e.g. `60.0% [23%, 88%]`,
+/// or "n/a" when there was nothing to count
+pub fn rate_with_interval(rate: Option<f64>, interval: Option<(f64, f64)>) -> String {
+    match (rate, interval) {
+        (None, _) => "n/a".to_string(),
+        (Some(rate), Some((low, high))) => format!("{:.1}% [{:.0}%, {:.0}%]", rate, low, high),
+        (Some(rate), None) => format!("{:.1}%", rate),
+    }
+}
+
+/// Format an optional number with fixed decimals, or "n/a"
+fn optional_fixed(value: Option<f64>, decimals: usize) -> String {
+    value
+        .map(|v| format!("{:.*}", decimals, v))
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+/// Quote a CSV field when it contains separators, quotes or newlines
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Keep tweet text on one table row and stop pipes from splitting cells
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::test_tweet;
+
+    #[test]
+    fn test_chain_renderer_writes_each_format() {
+        let result = AnalysisResult::new("ceo".to_string(), "ABC".to_string(), Utc::now(), Utc::now());
+        let chain = ChainRenderer(vec![
+            Box::new(CsvRenderer { aggregate: None }),
+            Box::new(JsonRenderer { compact: true, aggregate: None }),
+        ]);
+        
+        let mut out = Vec::new();
+        chain.render(&result, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        
+        assert!(out.starts_with("tweet_id,created_at,"));
+        assert!(out.trim_end().ends_with('}'));
+        assert!(out.contains("\"ticker\":\"ABC\""));
+    }
+
+    #[test]
+    fn test_table_labels_rise_rates_with_threshold() {
+        let result = AnalysisResult::new("ceo".to_string(), "ABC".to_string(), Utc::now(), Utc::now());
+        let table = TableRenderer { sort_by: TweetSort::Impactful, penny_stock_floor: DEFAULT_PENNY_STOCK_FLOOR };
+        
+        let mut out = Vec::new();
+        table.render(&result, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        
+        let label = format!(">{}% rise (1d)", crate::analysis::RISE_THRESHOLD_PCT);
+        assert!(out.contains(&label), "{}", out);
+    }
+
+    #[test]
+    fn test_format_price_precision() {
+        assert_eq!(format_price(123.456), "$123.46");
+        assert_eq!(format_price(1.0), "$1.00");
+        assert_eq!(format_price(0.04321), "$0.0432");
+    }
+
+    #[test]
+    fn test_format_usd_compact() {
+        assert_eq!(format_usd_compact(30e9), "+$30.00B");
+        assert_eq!(format_usd_compact(-1_250_000.0), "-$1.25M");
+        assert_eq!(format_usd_compact(2.4e12), "+$2.40T");
+        assert_eq!(format_usd_compact(512.0), "+$512.00");
+    }
+
+    #[test]
+    fn test_rate_with_interval_marks_missing_rates() {
+        assert_eq!(rate_with_interval(Some(60.0), Some((23.1, 88.2))), "60.0% [23%, 88%]");
+        assert_eq!(rate_with_interval(Some(0.0), None), "0.0%");
+        assert_eq!(rate_with_interval(None, None), "n/a");
+    }
+
+    #[test]
+    fn test_text_preview_cuts_on_characters() {
+        // Byte 60 falls inside an emoji here
+        let tweet = format!("{}🚀🚀 Über-Fahrzeug für alle", "a".repeat(58));
+        let preview = text_preview(&tweet, 60);
+        assert_eq!(preview, format!("{}🚀🚀...", "a".repeat(58)));
+        assert_eq!(text_preview("Tesla はすごい", 60), "Tesla はすごい");
+    }
+
+    #[test]
+    fn test_csv_escape_quotes_and_commas() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a, b"), "\"a, b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_period_start_uses_market_timezone() {
+        use chrono::TimeZone;
+        
+        // 02:00 UTC on Saturday 1 March is still Friday 28 February in New York
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 2, 0, 0).unwrap();
+        
+        assert_eq!(period_start(at, AggregatePeriod::Month), NaiveDate::from_ymd_opt(2025, 2, 1).unwrap());
+        assert_eq!(period_start(at, AggregatePeriod::Week), NaiveDate::from_ymd_opt(2025, 2, 24).unwrap());
+    }
+
+    #[test]
+    fn test_impactful_only_keeps_statistics() {
+        use crate::models::TweetImpact;
+        
+        let mut result = AnalysisResult::new("ceo".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        result.total_tweets = 2;
+        result.correlation_1d = Some(0.5);
+        for (id, impactful) in [("1", true), ("2", false)] {
+            let mut impact = TweetImpact::new(test_tweet(id, "text", Utc::now()));
+            impact.is_impactful = impactful;
+            result.impacts.push(impact);
+        }
+        
+        let filtered = impactful_only(&result);
+        
+        assert_eq!(filtered.impacts.len(), 1);
+        assert_eq!(filtered.impacts[0].tweet.id, "1");
+        assert_eq!(filtered.total_tweets, 2);
+        assert_eq!(filtered.correlation_1d, Some(0.5));
+    }
+
+    #[test]
+    fn test_ascii_fallback_replaces_symbols() {
+        assert_eq!(ascii_fallback("══ a → b (≥3x, 2 × 2)"), "-- a -> b (>=3x, 2 x 2)");
+        assert!(matches!(ascii_fallback("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_markdown_escape_pipes_and_newlines() {
+        assert_eq!(markdown_escape("a|b\nc"), "a\\|b c");
+    }
+}
@@ -3,19 +3,75 @@
 //! This module generates Prolog facts from analysis results and applies
 //! declarative rules to identify impactful tweets.
 
-use crate::models::AnalysisResult;
+use crate::models::{AnalysisResult, DetectedPattern, ImpactLevel, PatternKind, TweetImpact};
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Minimum |sentiment| for a tweet to count as strongly opinionated
+pub const IMPACT_SENTIMENT_THRESHOLD: f64 = 0.3;
+
+/// Minimum |price change| (%) within the lookahead for a tweet to be impactful
+pub const IMPACT_MOVE_THRESHOLD: f64 = 3.0;
+
+/// Minimum |sentiment| for `highly_impactful_tweet/1`
+pub const HIGH_IMPACT_SENTIMENT_THRESHOLD: f64 = 0.5;
+
+/// Minimum |price change| (%) for `highly_impactful_tweet/1`
+pub const HIGH_IMPACT_MOVE_THRESHOLD: f64 = 5.0;
+
+/// Retweets and likes an impactful tweet must exceed for `viral_impactful_tweet/1`
+pub const VIRAL_RETWEETS: u32 = 10_000;
+pub const VIRAL_LIKES: u32 = 50_000;
+
+/// Max calendar days between two impactful tweets for `tweet_cluster/2`
+pub const CLUSTER_WINDOW_DAYS: i64 = 3;
+
+/// Max calendar days between a positive and a negative tweet for `sentiment_reversal/2`
+pub const REVERSAL_WINDOW_DAYS: i64 = 7;
+
+/// Directory of dated facts exports (`--prolog-history`)
+pub const FACTS_HISTORY_DIR: &str = "prolog";
+
+/// Which tweets to include in exported Prolog facts, and how many rule
+/// solutions to retrieve
+///
+/// `limit` and `priced_only` only trim the export; rules are still applied
+/// to every tweet.
+#[derive(Debug, Clone, Default)]
+pub struct FactsFilter {
+    /// Keep only the N tweets with the largest price move (engagement breaks ties)
+    pub limit: Option<usize>,
+    
+    /// Skip tweets that have no price data at all
+    pub priced_only: bool,
+    
+    /// Stop `impactful_tweet/1` after N solutions; tweets past them stay
+    /// unclassified (None = all)
+    pub max_solutions: Option<usize>,
+}
 
 /// Apply Prolog rules to identify impactful tweets
 ///
 /// # Arguments
 /// * `result` - Analysis result to process (will be modified in place)
 /// * `export_path` - Optional path to export Prolog facts
-pub fn apply_rules(result: &mut AnalysisResult, export_path: Option<&str>) -> Result<()> {
-    // Generate Prolog facts
-    let facts = generate_facts(result);
+/// * `filter` - Which tweets to include in the exported facts
+pub fn apply_rules(
+    result: &mut AnalysisResult,
+    export_path: Option<&str>,
+    filter: &FactsFilter,
+) -> Result<()> {
+    // Apply rules using scryer-prolog
+    // Note: This is a simplified version. Full implementation would use scryer-prolog crate
+    // to actually query the facts. For now, we'll use a simple Rust-based rule engine.
+    apply_simple_rules(result, filter.max_solutions);
+    
+    // Generate Prolog facts (after the rules, so classified/2 records their outcome)
+    let facts = generate_facts(result, filter);
     
     // Export if requested
     if let Some(path) = export_path {
@@ -28,16 +84,157 @@ pub fn apply_rules(result: &mut AnalysisResult, export_path: Option<&str>) -> Re
         println!("  → Exported Prolog facts to {}", path);
     }
     
-    // Apply rules using scryer-prolog
-    // Note: This is a simplified version. Full implementation would use scryer-prolog crate
-    // to actually query the facts. For now, we'll use a simple Rust-based rule engine.
-    apply_simple_rules(result);
-    
     Ok(())
 }
 
+/// Path of the dated export for `handle` on `date`: `{dir}/{handle}_{date}.pl`
+pub fn versioned_facts_path(dir: &Path, handle: &str, date: NaiveDate) -> PathBuf {
+    dir.join(format!("{}_{}.pl", handle.to_lowercase(), date.format("%Y-%m-%d")))
+}
+
+/// The newest file name among `names` that is an export for `handle` dated before `date`
+fn latest_export_before(names: impl Iterator<Item = String>, handle: &str, date: NaiveDate) -> Option<String> {
+    let prefix = format!("{}_", handle.to_lowercase());
+    names
+        .filter_map(|name| {
+            let day = name.strip_prefix(&prefix)?.strip_suffix(".pl")?;
+            let day = NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()?;
+            (day < date).then_some((day, name))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+/// Write the facts (rules already applied) to today's dated export in `dir`
+/// and compare them with the newest earlier export, if there is one
+///
+/// Re-running on the same day overwrites that day's export.
+pub fn record_facts_history(
+    result: &AnalysisResult,
+    filter: &FactsFilter,
+    dir: &Path,
+    date: NaiveDate,
+) -> Result<(PathBuf, Option<FactsDiff>)> {
+    fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+    let names = fs::read_dir(dir)
+        .context(format!("Failed to list {}", dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok());
+    let previous = latest_export_before(names, &result.ceo_handle, date)
+        .map(|name| {
+            let path = dir.join(name);
+            fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))
+        })
+        .transpose()?;
+    
+    let facts = generate_facts(result, filter);
+    let path = versioned_facts_path(dir, &result.ceo_handle, date);
+    crate::storage::write_atomic(&path.to_string_lossy(), &facts)?;
+    
+    Ok((path, previous.map(|previous| diff_facts(&previous, &facts))))
+}
+
+/// How classifications changed between two facts exports, keyed by tweet ID
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FactsDiff {
+    /// Tweets absent from the previous export that are now impactful, with their level
+    pub new_impactful: Vec<(String, ImpactLevel)>,
+    /// Tweets in both exports whose level changed, as (id, before, after)
+    pub changed: Vec<(String, ImpactLevel, ImpactLevel)>,
+}
+
+/// Compare the `classified/2` facts of two exports
+///
+/// Facts ids (`tweet_N`) are only stable within one export, so tweets are
+/// matched through `source_id/2`. Exports written before classifications
+/// were exported have none and compare as empty.
+pub fn diff_facts(previous: &str, current: &str) -> FactsDiff {
+    let before = classifications(previous);
+    let after = classifications(current);
+    
+    let mut diff = FactsDiff::default();
+    for (id, &level) in &after {
+        match before.get(id) {
+            None if level != ImpactLevel::None => diff.new_impactful.push((id.clone(), level)),
+            Some(&old) if old != level => diff.changed.push((id.clone(), old, level)),
+            _ => {}
+        }
+    }
+    diff
+}
+
+/// Tweet ID -> impact level, from an export's `source_id/2` and `classified/2` facts
+fn classifications(facts: &str) -> BTreeMap<String, ImpactLevel> {
+    let args = |line: &str, predicate: &str| -> Option<(String, String)> {
+        let inner = line.strip_prefix(predicate)?.strip_prefix('(')?.strip_suffix(").")?;
+        let (first, second) = inner.split_once(", ")?;
+        Some((first.trim_matches('\'').to_string(), second.trim_matches('\'').to_string()))
+    };
+    let mut source_ids = BTreeMap::new();
+    let mut levels = Vec::new();
+    for line in facts.lines().map(str::trim) {
+        if let Some((fact_id, tweet_id)) = args(line, "source_id") {
+            source_ids.insert(fact_id, tweet_id);
+        } else if let Some((fact_id, atom)) = args(line, "classified") {
+            if let Some(level) = level_from_atom(&atom) {
+                levels.push((fact_id, level));
+            }
+        }
+    }
+    levels
+        .into_iter()
+        .filter_map(|(fact_id, level)| Some((source_ids.get(&fact_id)?.clone(), level)))
+        .collect()
+}
+
+/// Prolog atom for an impact level in `classified/2`
+fn level_atom(level: ImpactLevel) -> &'static str {
+    match level {
+        ImpactLevel::None => "none",
+        ImpactLevel::Impactful => "impactful",
+        ImpactLevel::HighlyImpactful => "highly_impactful",
+        ImpactLevel::Viral => "viral",
+    }
+}
+
+fn level_from_atom(atom: &str) -> Option<ImpactLevel> {
+    [ImpactLevel::None, ImpactLevel::Impactful, ImpactLevel::HighlyImpactful, ImpactLevel::Viral]
+        .into_iter()
+        .find(|level| level_atom(*level) == atom)
+}
+
+/// Indices of the impacts to export, in their original order
+///
+/// Ids stay `tweet_{index}` into `result.impacts`, so a trimmed export still
+/// maps back to the same impacts.
+fn select_fact_indices(result: &AnalysisResult, filter: &FactsFilter) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..result.impacts.len())
+        .filter(|&idx| {
+            let impact = &result.impacts[idx];
+            !filter.priced_only || impact.change_1d.is_some() || impact.change_3d.is_some()
+        })
+        .collect();
+    
+    if let Some(limit) = filter.limit {
+        let strength = |idx: &usize| {
+            let impact = &result.impacts[*idx];
+            let biggest_move = impact.change_1d.unwrap_or(0.0).abs()
+                .max(impact.change_3d.unwrap_or(0.0).abs());
+            let engagement = impact.tweet.retweet_count as u64 + impact.tweet.like_count as u64;
+            (biggest_move, engagement)
+        };
+        
+        indices.sort_by(|a, b| {
+            strength(b).partial_cmp(&strength(a)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        indices.truncate(limit);
+        indices.sort_unstable();
+    }
+    
+    indices
+}
+
 /// Generate Prolog facts from analysis results
-fn generate_facts(result: &AnalysisResult) -> String {
+fn generate_facts(result: &AnalysisResult, filter: &FactsFilter) -> String {
     let mut facts = String::new();
     
     // Header comment
@@ -54,12 +251,20 @@ fn generate_facts(result: &AnalysisResult) -> String {
     // Define predicates
     facts.push_str(
         "% tweet(TweetId, Date, Sentiment, Retweets, Likes).\n\
+         % tweet_time(TweetId, Timestamp).  % YYYYMMDDHHMMSS (UTC), orders tweets within a day\n\
+         % tweet_day(TweetId, Day).  % days since 1970-01-01 (UTC), for day arithmetic\n\
+         % next_tweet(Earlier, Later).  % consecutive tweets in time order\n\
+         % source_id(TweetId, StatusId).  % the tweet's Twitter ID, stable across runs\n\
+         % classified(TweetId, Level).  % rule outcome: none, impactful, highly_impactful or viral\n\
          % price_change(TweetId, Days, PercentChange).\n\
          % impactful_tweet(TweetId) :- ...\n\n"
     );
     
-    // Generate facts for each tweet impact
-    for (idx, impact) in result.impacts.iter().enumerate() {
+    let selected = select_fact_indices(result, filter);
+    
+    // Generate facts for each selected tweet impact
+    for &idx in &selected {
+        let impact = &result.impacts[idx];
         let tweet_id = format!("tweet_{}", idx);
         let date = impact.tweet.created_at.format("%Y%m%d");
         let sentiment = impact.tweet.sentiment.unwrap_or(0.0);
@@ -73,6 +278,26 @@ fn generate_facts(result: &AnalysisResult) -> String {
             impact.tweet.retweet_count,
             impact.tweet.like_count
         ));
+        facts.push_str(&format!(
+            "tweet_time('{}', {}).\n",
+            tweet_id,
+            impact.tweet.created_at.format("%Y%m%d%H%M%S")
+        ));
+        facts.push_str(&format!(
+            "tweet_day('{}', {}).\n",
+            tweet_id,
+            epoch_day(impact)
+        ));
+        facts.push_str(&format!(
+            "source_id('{}', '{}').\n",
+            tweet_id,
+            impact.tweet.id.replace('\'', "")
+        ));
+        facts.push_str(&format!(
+            "classified('{}', {}).\n",
+            tweet_id,
+            level_atom(impact.impact_level)
+        ));
         
         // Price change facts
         if let Some(change_1d) = impact.change_1d {
@@ -90,81 +315,237 @@ fn generate_facts(result: &AnalysisResult) -> String {
         }
     }
     
+    // Chronological chain of the exported tweets
+    let mut chronological = selected;
+    chronological.sort_by_key(|&idx| result.impacts[idx].tweet.created_at);
+    if chronological.len() > 1 {
+        facts.push('\n');
+    }
+    for pair in chronological.windows(2) {
+        facts.push_str(&format!("next_tweet('tweet_{}', 'tweet_{}').\n", pair[0], pair[1]));
+    }
+    
     // Add rules
     facts.push_str("\n% Rules for identifying impactful tweets\n");
-    facts.push_str(
+    facts.push_str(&format!(
         "% A tweet is impactful if:\n\
-         % 1. It has strong sentiment (|sentiment| > 0.3) AND\n\
-         % 2. It caused significant price movement (|change| > 3%) within 1-3 days\n\n"
-    );
+         % 1. It has strong sentiment (|sentiment| > {:.1}) AND\n\
+         % 2. It caused significant price movement (|change| > {}%) within 1-3 days\n\n",
+        IMPACT_SENTIMENT_THRESHOLD, IMPACT_MOVE_THRESHOLD
+    ));
     
-    facts.push_str(
+    facts.push_str(&format!(
         "impactful_tweet(TweetId) :-\n\
          \ttweet(TweetId, _, Sentiment, _, _),\n\
-         \tabs(Sentiment) > 0.3,\n\
+         \tabs(Sentiment) > {:.1},\n\
          \tprice_change(TweetId, Days, Change),\n\
          \tDays =< 3,\n\
-         \tabs(Change) > 3.0.\n\n"
-    );
+         \tabs(Change) > {:.1}.\n\n",
+        IMPACT_SENTIMENT_THRESHOLD, IMPACT_MOVE_THRESHOLD
+    ));
     
-    facts.push_str(
+    facts.push_str(&format!(
         "highly_impactful_tweet(TweetId) :-\n\
          \ttweet(TweetId, _, Sentiment, _, _),\n\
-         \tabs(Sentiment) > 0.5,\n\
+         \tabs(Sentiment) > {:.1},\n\
          \tprice_change(TweetId, Days, Change),\n\
          \tDays =< 3,\n\
-         \tabs(Change) > 5.0.\n\n"
-    );
+         \tabs(Change) > {:.1}.\n\n",
+        HIGH_IMPACT_SENTIMENT_THRESHOLD, HIGH_IMPACT_MOVE_THRESHOLD
+    ));
     
-    facts.push_str(
+    facts.push_str(&format!(
         "viral_impactful_tweet(TweetId) :-\n\
          \ttweet(TweetId, _, Sentiment, Retweets, Likes),\n\
-         \tRetweets > 10000,\n\
-         \tLikes > 50000,\n\
-         \timpactful_tweet(TweetId).\n"
+         \tRetweets > {},\n\
+         \tLikes > {},\n\
+         \timpactful_tweet(TweetId).\n\n",
+        VIRAL_RETWEETS, VIRAL_LIKES
+    ));
+
+    // Ordering within a day, via tweet_time/2
+    facts.push_str(
+        "earlier_same_day(Earlier, Later) :-\n\
+         \ttweet(Earlier, Date, _, _, _),\n\
+         \ttweet(Later, Date, _, _, _),\n\
+         \ttweet_time(Earlier, T1),\n\
+         \ttweet_time(Later, T2),\n\
+         \tT1 < T2.\n\n"
+    );
+
+    facts.push_str(
+        "follow_up_tweet(TweetId) :-\n\
+         \tearlier_same_day(Previous, TweetId),\n\
+         \timpactful_tweet(Previous).\n\n"
     );
+
+    // Temporal patterns across days, via tweet_day/2
+    facts.push_str("% Temporal patterns between pairs of tweets\n");
+    facts.push_str(&format!(
+        "tweet_cluster(T1, T2) :-\n\
+         \timpactful_tweet(T1),\n\
+         \timpactful_tweet(T2),\n\
+         \ttweet_time(T1, S1),\n\
+         \ttweet_time(T2, S2),\n\
+         \tS1 < S2,\n\
+         \ttweet_day(T1, D1),\n\
+         \ttweet_day(T2, D2),\n\
+         \tD2 - D1 =< {}.\n\n",
+        CLUSTER_WINDOW_DAYS
+    ));
+
+    facts.push_str(&format!(
+        "sentiment_reversal(T1, T2) :-\n\
+         \ttweet(T1, _, S1, _, _),\n\
+         \tS1 > 0,\n\
+         \ttweet(T2, _, S2, _, _),\n\
+         \tS2 < 0,\n\
+         \ttweet_time(T1, A1),\n\
+         \ttweet_time(T2, A2),\n\
+         \tA1 < A2,\n\
+         \ttweet_day(T1, D1),\n\
+         \ttweet_day(T2, D2),\n\
+         \tD2 - D1 =< {}.\n",
+        REVERSAL_WINDOW_DAYS
+    ));
     
     facts
 }
 
+/// UTC calendar day of the tweet, counted from 1970-01-01
+fn epoch_day(impact: &TweetImpact) -> i64 {
+    impact.tweet.created_at.timestamp().div_euclid(86_400)
+}
+
+/// Rust counterpart of `tweet_cluster/2` and `sentiment_reversal/2`
+///
+/// Expects `is_impactful` to be set already. Pairs come out ordered by the
+/// earlier tweet, then the later one.
+fn detect_patterns(impacts: &[TweetImpact]) -> Vec<DetectedPattern> {
+    let mut chronological: Vec<&TweetImpact> = impacts.iter().collect();
+    chronological.sort_by_key(|i| i.tweet.created_at);
+    
+    let max_window = CLUSTER_WINDOW_DAYS.max(REVERSAL_WINDOW_DAYS);
+    let mut patterns = Vec::new();
+    
+    for (pos, first) in chronological.iter().enumerate() {
+        for second in &chronological[pos + 1..] {
+            let days_apart = epoch_day(second) - epoch_day(first);
+            if days_apart > max_window {
+                break;
+            }
+            if first.tweet.created_at == second.tweet.created_at {
+                continue;
+            }
+            
+            let pattern = |kind| DetectedPattern {
+                kind,
+                first_tweet_id: first.tweet.id.clone(),
+                second_tweet_id: second.tweet.id.clone(),
+                days_apart,
+            };
+            
+            if first.is_impactful && second.is_impactful && days_apart <= CLUSTER_WINDOW_DAYS {
+                patterns.push(pattern(PatternKind::TweetCluster));
+            }
+            
+            let first_sentiment = first.tweet.sentiment.unwrap_or(0.0);
+            let second_sentiment = second.tweet.sentiment.unwrap_or(0.0);
+            if first_sentiment > 0.0 && second_sentiment < 0.0 && days_apart <= REVERSAL_WINDOW_DAYS {
+                patterns.push(pattern(PatternKind::SentimentReversal));
+            }
+        }
+    }
+    
+    patterns
+}
+
+/// Highest rule tier an impact satisfies, mirroring the three Prolog rules
+fn impact_level(impact: &TweetImpact) -> ImpactLevel {
+    let sentiment = impact.tweet.sentiment.unwrap_or(0.0).abs();
+    let biggest_move = [impact.change_1d, impact.change_3d]
+        .into_iter()
+        .flatten()
+        .map(f64::abs)
+        .fold(0.0, f64::max);
+    
+    // Rule: Strong sentiment + significant price movement
+    if sentiment <= IMPACT_SENTIMENT_THRESHOLD || biggest_move <= IMPACT_MOVE_THRESHOLD {
+        return ImpactLevel::None;
+    }
+    if impact.tweet.retweet_count > VIRAL_RETWEETS && impact.tweet.like_count > VIRAL_LIKES {
+        return ImpactLevel::Viral;
+    }
+    if sentiment > HIGH_IMPACT_SENTIMENT_THRESHOLD && biggest_move > HIGH_IMPACT_MOVE_THRESHOLD {
+        return ImpactLevel::HighlyImpactful;
+    }
+    ImpactLevel::Impactful
+}
+
+/// Solutions of `impactful_tweet(X)` in facts order, as `(fact id, level)`
+///
+/// Lazy like a Prolog query: each impact is only checked when the next
+/// solution is asked for, so taking the first N skips the rest.
+fn impactful_solutions(impacts: &[TweetImpact]) -> impl Iterator<Item = (String, ImpactLevel)> + '_ {
+    impacts
+        .iter()
+        .enumerate()
+        .map(|(idx, impact)| (format!("tweet_{}", idx), impact_level(impact)))
+        .filter(|(_, level)| *level != ImpactLevel::None)
+}
+
+/// Index into `result.impacts` of a `tweet_N` fact id
+fn fact_index(fact_id: &str) -> Option<usize> {
+    fact_id.strip_prefix("tweet_")?.parse().ok()
+}
+
 /// Apply simple rule-based logic to mark impactful tweets
 /// This is a Rust implementation of the Prolog rules for demonstration
-fn apply_simple_rules(result: &mut AnalysisResult) {
+///
+/// With `max_solutions`, only that many `impactful_tweet/1` solutions are
+/// retrieved and `impact_solutions_cap` records that the query was cut short.
+fn apply_simple_rules(result: &mut AnalysisResult, max_solutions: Option<usize>) {
+    // One extra solution tells a capped query from one that just fit
+    let mut solutions: Vec<(String, ImpactLevel)> = impactful_solutions(&result.impacts)
+        .take(max_solutions.map_or(usize::MAX, |max| max.saturating_add(1)))
+        .collect();
+    result.impact_solutions_cap = max_solutions.filter(|&max| solutions.len() > max);
+    solutions.truncate(max_solutions.unwrap_or(usize::MAX));
+    
     for impact in &mut result.impacts {
-        let sentiment = impact.tweet.sentiment.unwrap_or(0.0);
-        
-        // Rule: Strong sentiment + significant price movement
-        let has_strong_sentiment = sentiment.abs() > 0.3;
-        
-        let has_significant_movement = impact
-            .change_1d
-            .map(|c| c.abs() > 3.0)
-            .unwrap_or(false)
-            || impact
-                .change_3d
-                .map(|c| c.abs() > 3.0)
-                .unwrap_or(false);
-        
-        impact.is_impactful = has_strong_sentiment && has_significant_movement;
+        impact.impact_level = ImpactLevel::None;
+        impact.is_impactful = false;
     }
+    for (fact_id, level) in solutions {
+        if let Some(impact) = fact_index(&fact_id).and_then(|idx| result.impacts.get_mut(idx)) {
+            impact.impact_level = level;
+            impact.is_impactful = true;
+        }
+    }
+    
+    let impactful_words: Vec<f64> = result.impacts.iter()
+        .filter(|i| i.is_impactful)
+        .map(|i| i.tweet.word_count() as f64)
+        .collect();
+    result.avg_words_impactful = (!impactful_words.is_empty())
+        .then(|| impactful_words.iter().sum::<f64>() / impactful_words.len() as f64);
     
-    // Sort impacts by "impactfulness" (impactful first, then by sentiment strength)
+    result.patterns = detect_patterns(&result.impacts);
+    
+    // Sort impacts by "impactfulness" (highest tier first, then by sentiment strength)
     result.impacts.sort_by(|a, b| {
-        match (a.is_impactful, b.is_impactful) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => {
-                let a_sent = a.tweet.sentiment.unwrap_or(0.0).abs();
-                let b_sent = b.tweet.sentiment.unwrap_or(0.0).abs();
-                b_sent.partial_cmp(&a_sent).unwrap_or(std::cmp::Ordering::Equal)
-            }
-        }
+        b.impact_level.cmp(&a.impact_level).then_with(|| {
+            let a_sent = a.tweet.sentiment.unwrap_or(0.0).abs();
+            let b_sent = b.tweet.sentiment.unwrap_or(0.0).abs();
+            b_sent.partial_cmp(&a_sent).unwrap_or(std::cmp::Ordering::Equal)
+        })
     });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::test_support::test_tweet;
     use crate::models::{Tweet, TweetImpact};
     use chrono::Utc;
 
@@ -177,25 +558,166 @@ mod tests {
             Utc::now(),
         );
         
-        result.impacts.push(TweetImpact {
-            tweet: Tweet {
-                id: "123".to_string(),
-                text: "Great news!".to_string(),
-                created_at: Utc::now(),
-                retweet_count: 1000,
-                like_count: 5000,
-                sentiment: Some(0.8),
-            },
-            price_at_tweet: Some(100.0),
-            change_1d: Some(5.0),
-            change_3d: Some(7.0),
-            is_impactful: false,
+        let mut impact = TweetImpact::new(Tweet {
+            retweet_count: 1000,
+            like_count: 5000,
+            sentiment: Some(0.8),
+            ..test_tweet("123", "Great news!", Utc::now())
         });
+        impact.price_at_tweet = Some(100.0);
+        impact.change_1d = Some(5.0);
+        impact.change_3d = Some(7.0);
+        result.impacts.push(impact);
         
-        let facts = generate_facts(&result);
+        let facts = generate_facts(&result, &FactsFilter::default());
         
         assert!(facts.contains("tweet("));
+        let stamp = result.impacts[0].tweet.created_at.format("%Y%m%d%H%M%S");
+        assert!(facts.contains(&format!("tweet_time('tweet_0', {}).", stamp)));
         assert!(facts.contains("price_change("));
         assert!(facts.contains("impactful_tweet("));
     }
+
+    #[test]
+    fn test_generate_facts_limit_and_priced_only() {
+        let mut result = AnalysisResult::new(
+            "elonmusk".to_string(),
+            "TSLA".to_string(),
+            Utc::now(),
+            Utc::now(),
+        );
+        
+        for (id, change) in [("a", None), ("b", Some(1.0)), ("c", Some(-6.0))] {
+            let mut impact = TweetImpact::new(Tweet { sentiment: Some(0.5), ..test_tweet(id, "", Utc::now()) });
+            impact.change_1d = change;
+            result.impacts.push(impact);
+        }
+        
+        let priced = generate_facts(&result, &FactsFilter { priced_only: true, ..Default::default() });
+        assert!(!priced.contains("tweet('tweet_0'"));
+        assert!(priced.contains("tweet('tweet_1'"));
+        
+        let top = generate_facts(&result, &FactsFilter { limit: Some(1), ..Default::default() });
+        assert!(top.contains("tweet('tweet_2'"));
+        assert!(!top.contains("tweet('tweet_1'"));
+    }
+
+    #[test]
+    fn test_impact_levels() {
+        let mut result = AnalysisResult::new("elonmusk".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        
+        for (id, sentiment, change, retweets, likes) in [
+            ("none", 0.2, 8.0, 0, 0),
+            ("impactful", 0.4, 4.0, 0, 0),
+            ("highly", -0.6, -6.0, 0, 0),
+            ("viral", 0.4, 4.0, 20_000, 90_000),
+        ] {
+            let mut impact = TweetImpact::new(Tweet {
+                retweet_count: retweets,
+                like_count: likes,
+                sentiment: Some(sentiment),
+                ..test_tweet(id, "", Utc::now())
+            });
+            impact.change_3d = Some(change);
+            result.impacts.push(impact);
+        }
+        
+        apply_simple_rules(&mut result, None);
+        let levels: Vec<(&str, ImpactLevel, bool)> = result.impacts.iter()
+            .map(|i| (i.tweet.id.as_str(), i.impact_level, i.is_impactful))
+            .collect();
+        assert_eq!(levels, vec![
+            ("viral", ImpactLevel::Viral, true),
+            ("highly", ImpactLevel::HighlyImpactful, true),
+            ("impactful", ImpactLevel::Impactful, true),
+            ("none", ImpactLevel::None, false),
+        ]);
+    }
+
+    #[test]
+    fn test_max_solutions_caps_impactful_query() {
+        let mut result = AnalysisResult::new("elonmusk".to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        
+        for (id, change) in [("a", 4.0), ("b", 1.0), ("c", -4.0), ("d", 6.0)] {
+            let mut impact = TweetImpact::new(Tweet { sentiment: Some(0.4), ..test_tweet(id, "", Utc::now()) });
+            impact.change_1d = Some(change);
+            result.impacts.push(impact);
+        }
+        let solutions: Vec<String> = impactful_solutions(&result.impacts).map(|(id, _)| id).collect();
+        assert_eq!(solutions, ["tweet_0", "tweet_2", "tweet_3"]);
+        
+        // The first two solutions map back to "a" and "c"; "d" is never retrieved
+        apply_simple_rules(&mut result, Some(2));
+        assert_eq!(result.impact_solutions_cap, Some(2));
+        let mut impactful: Vec<&str> = result.impacts.iter()
+            .filter(|i| i.is_impactful)
+            .map(|i| i.tweet.id.as_str())
+            .collect();
+        impactful.sort_unstable();
+        assert_eq!(impactful, ["a", "c"]);
+        
+        apply_simple_rules(&mut result, Some(3));
+        assert_eq!(result.impact_solutions_cap, None);
+        assert_eq!(result.impacts.iter().filter(|i| i.is_impactful).count(), 3);
+    }
+
+    #[test]
+    fn test_diff_facts_matches_tweets_by_source_id() {
+        let previous = "source_id('tweet_0', '100').\nclassified('tweet_0', impactful).\n\
+                        source_id('tweet_1', '200').\nclassified('tweet_1', none).\n";
+        // Fact ids shift between runs; the tweet IDs don't
+        let current = "source_id('tweet_0', '300').\nclassified('tweet_0', viral).\n\
+                       source_id('tweet_1', '200').\nclassified('tweet_1', highly_impactful).\n\
+                       source_id('tweet_2', '100').\nclassified('tweet_2', impactful).\n\
+                       source_id('tweet_3', '400').\nclassified('tweet_3', none).\n";
+        
+        let diff = diff_facts(previous, current);
+        assert_eq!(diff.new_impactful, vec![("300".to_string(), ImpactLevel::Viral)]);
+        assert_eq!(diff.changed, vec![("200".to_string(), ImpactLevel::None, ImpactLevel::HighlyImpactful)]);
+        assert_eq!(diff_facts(current, current), FactsDiff::default());
+    }
+
+    #[test]
+    fn test_latest_export_before() {
+        let names = ["elonmusk_2025-03-01.pl", "elonmusk_2025-03-09.pl", "elonmusk_2025-03-10.pl",
+                     "elonmusk_notes.pl", "tim_2025-03-09.pl"];
+        let date = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let latest = latest_export_before(names.iter().map(|n| n.to_string()), "ElonMusk", date);
+        assert_eq!(latest.as_deref(), Some("elonmusk_2025-03-09.pl"));
+        assert_eq!(
+            versioned_facts_path(Path::new(FACTS_HISTORY_DIR), "ElonMusk", date),
+            Path::new("prolog/elonmusk_2025-03-10.pl")
+        );
+    }
+
+    #[test]
+    fn test_temporal_patterns() {
+        let start = Utc::now() - chrono::Duration::days(30);
+        let mut result = AnalysisResult::new("elonmusk".to_string(), "TSLA".to_string(), start, Utc::now());
+        
+        // Impactful positive, impactful negative two days later, then a quiet positive a week after
+        for (id, day, sentiment, change) in [("a", 0, 0.6, 5.0), ("b", 2, -0.6, -5.0), ("c", 9, 0.4, 0.5)] {
+            let mut impact = TweetImpact::new(Tweet {
+                sentiment: Some(sentiment),
+                ..test_tweet(id, "", start + chrono::Duration::days(day))
+            });
+            impact.change_1d = Some(change);
+            result.impacts.push(impact);
+        }
+        
+        let facts = generate_facts(&result, &FactsFilter::default());
+        assert!(facts.contains("next_tweet('tweet_0', 'tweet_1')."));
+        assert!(facts.contains("next_tweet('tweet_1', 'tweet_2')."));
+        assert!(facts.contains(&format!("tweet_day('tweet_1', {}).", epoch_day(&result.impacts[1]))));
+        assert!(facts.contains("tweet_cluster(T1, T2) :-"));
+        
+        apply_simple_rules(&mut result, None);
+        let found: Vec<(PatternKind, &str, &str, i64)> = result.patterns.iter()
+            .map(|p| (p.kind, p.first_tweet_id.as_str(), p.second_tweet_id.as_str(), p.days_apart))
+            .collect();
+        assert_eq!(found, vec![
+            (PatternKind::TweetCluster, "a", "b", 2),
+            (PatternKind::SentimentReversal, "a", "b", 2),
+        ]);
+    }
 }
@@ -0,0 +1,85 @@
+//! Optional remote sentiment model, scored alongside the keyword lexicon.
+//!
+//! The lexicon misses sarcasm and slang. When `--remote-sentiment-url` is
+//! set, tweet texts are POSTed to that endpoint as
+//! `{"texts": [{"id": "...", "text": "..."}]}` and it answers
+//! `{"scores": {"<id>": <score in [-1, 1]>}}`. Tweets where the two scorers
+//! disagree strongly go to the review queue.
+
+use crate::models::{preprocess_tweet_text, TextOptions, TweetImpact};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Most tweets sent to the remote model per request
+const REMOTE_BATCH_SIZE: usize = 50;
+
+/// Name the endpoint's rate-limit quota is tracked under (see `http::record_quota`)
+const REMOTE_QUOTA: &str = "remote_sentiment";
+
+#[derive(Debug, Deserialize)]
+struct ScoresResponse {
+    scores: HashMap<String, f64>,
+}
+
+/// Remote model scores for every scored impact, by tweet id
+///
+/// Texts go out preprocessed for sentiment, as the lexicon sees them.
+/// Scores outside [-1, 1] (or not numbers) are dropped.
+pub async fn fetch_scores(url: &str, impacts: &[TweetImpact], verbose: bool) -> Result<HashMap<String, f64>> {
+    let client = crate::http::client();
+    let mut scores = HashMap::new();
+
+    for batch in impacts.chunks(REMOTE_BATCH_SIZE) {
+        let texts: Vec<_> = batch
+            .iter()
+            .map(|i| serde_json::json!({
+                "id": i.tweet.id,
+                "text": preprocess_tweet_text(&i.tweet.text, TextOptions::SENTIMENT),
+            }))
+            .collect();
+
+        crate::http::wait_for_quota(REMOTE_QUOTA, verbose).await;
+        let response = client
+            .post(url)
+            .json(&serde_json::json!({ "texts": texts }))
+            .send()
+            .await
+            .context("Failed to reach the remote sentiment model")?;
+        crate::http::record_quota(REMOTE_QUOTA, response.headers(), verbose);
+
+        if !response.status().is_success() {
+            anyhow::bail!("Remote sentiment model returned {}", response.status());
+        }
+        let body: ScoresResponse = response
+            .json()
+            .await
+            .context("Failed to parse the remote sentiment response")?;
+        scores.extend(valid_scores(body.scores));
+    }
+
+    if verbose {
+        println!("  → Remote model scored {} of {} tweets", scores.len(), impacts.len());
+    }
+    Ok(scores)
+}
+
+/// Scores a model may return: finite and within [-1, 1]
+fn valid_scores(scores: HashMap<String, f64>) -> impl Iterator<Item = (String, f64)> {
+    scores.into_iter().filter(|(_, score)| score.is_finite() && (-1.0..=1.0).contains(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range_remote_scores_are_dropped() {
+        let body: ScoresResponse =
+            serde_json::from_str(r#"{"scores": {"1": 0.8, "2": -1.0, "3": 4.2}}"#).unwrap();
+        let mut scores: Vec<_> = valid_scores(body.scores).collect();
+        scores.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(scores, [("1".to_string(), 0.8), ("2".to_string(), -1.0)]);
+    }
+}
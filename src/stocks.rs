@@ -1,15 +1,48 @@
 //! Stock price data fetching from Alpha Vantage API.
 //!
 //! This module retrieves historical daily stock prices for correlation analysis.
+//! A secondary provider (Yahoo Finance) can be fetched alongside and reconciled
+//! against the primary series to catch bad data.
 
 use crate::models::PricePoint;
 use anyhow::{Context, Result};
-use chrono::{NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
-/// Alpha Vantage API base URL
-const ALPHA_VANTAGE_BASE: &str = "https://www.alphavantage.co/query";
+/// Default Alpha Vantage API base URL (override with `--stock-api-base`)
+pub const ALPHA_VANTAGE_BASE: &str = "https://www.alphavantage.co/query";
+
+/// Trading days of extra history fetched before the analysis window by default
+pub const DEFAULT_PRICE_BUFFER_DAYS: u32 = 60;
+
+/// Rows returned by `outputsize=compact`; larger requests need `outputsize=full`
+const COMPACT_OUTPUT_ROWS: u32 = 100;
+
+/// Yahoo Finance chart API used as a secondary price provider
+pub const YAHOO_CHART_BASE: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
+
+/// Default close-price disagreement (percent) reported as a discrepancy
+pub const DEFAULT_DISCREPANCY_PCT: f64 = 2.0;
+
+/// Names the providers' rate-limit quotas are tracked under (see `http::record_quota`)
+const ALPHA_VANTAGE_QUOTA: &str = "alpha_vantage";
+const YAHOO_QUOTA: &str = "yahoo";
+
+/// One cached price request: filled by whichever caller fetched it first
+type PriceSlot = Arc<tokio::sync::Mutex<Option<(Instant, Vec<PricePoint>)>>>;
+
+/// Process-wide price cache shared by concurrent tasks, keyed by request
+static SHARED_PRICES: LazyLock<Mutex<HashMap<String, PriceSlot>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Secondary price providers that can be reconciled against Alpha Vantage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PriceProvider {
+    /// Yahoo Finance chart API (no key required)
+    Yahoo,
+}
 
 /// Response from Alpha Vantage TIME_SERIES_DAILY endpoint
 #[derive(Debug, Deserialize)]
@@ -20,6 +53,20 @@ struct TimeSeriesResponse {
     error_message: Option<String>,
     #[serde(rename = "Note")]
     note: Option<String>,
+    #[serde(rename = "Information")]
+    information: Option<String>,
+}
+
+impl TimeSeriesResponse {
+    /// Whether the request was turned away because it needs a premium key
+    /// (e.g. `outputsize=full` on the free tier)
+    fn premium_refusal(&self) -> bool {
+        self.time_series.is_none()
+            && [&self.information, &self.note]
+                .into_iter()
+                .flatten()
+                .any(|message| message.to_lowercase().contains("premium"))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,7 +88,10 @@ struct DailyData {
 /// # Arguments
 /// * `ticker` - Stock ticker symbol (e.g., "TSLA")
 /// * `api_key` - Alpha Vantage API key
+/// * `base_url` - Alpha Vantage-compatible query endpoint (e.g. a caching proxy)
 /// * `days` - Number of days to look back (note: API returns up to 100 days for free tier)
+/// * `buffer_days` - Extra trading days kept before the window, so pre-tweet
+///   indicators (volume baseline, volatility) are defined from the first tweet
 /// * `verbose` - Enable verbose logging
 ///
 /// # Returns
@@ -49,43 +99,29 @@ struct DailyData {
 pub async fn fetch_prices(
     ticker: &str,
     api_key: &str,
+    base_url: &str,
     days: u32,
+    buffer_days: u32,
     verbose: bool,
 ) -> Result<Vec<PricePoint>> {
+    let wanted = days.saturating_add(buffer_days);
+
     if verbose {
         println!("  → Fetching daily prices for {}", ticker);
     }
     
-    let client = reqwest::Client::new();
-    
-    // Alpha Vantage TIME_SERIES_DAILY endpoint
-    // Note: Free tier gives last 100 days. For more, need premium or TIME_SERIES_DAILY_ADJUSTED with outputsize=full
-    let url = format!(
-        "{}?function=TIME_SERIES_DAILY&symbol={}&apikey={}&outputsize=compact",
-        ALPHA_VANTAGE_BASE, ticker, api_key
-    );
-    
-    if verbose {
-        println!("  → Requesting data from Alpha Vantage...");
+    // Free keys only get the last 100 days (`compact`); `full` is premium,
+    // so a refused `full` request falls back to what the key can get
+    let output_size = if wanted > COMPACT_OUTPUT_ROWS { "full" } else { "compact" };
+    let mut ts_response = request_daily_series(ticker, api_key, base_url, output_size, verbose).await?;
+    if output_size == "full" && ts_response.premium_refusal() {
+        println!(
+            "  WARNING: Alpha Vantage refused outputsize=full for {} (premium only); using the last {} trading days",
+            ticker, COMPACT_OUTPUT_ROWS
+        );
+        ts_response = request_daily_series(ticker, api_key, base_url, "compact", verbose).await?;
     }
     
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to fetch stock data from Alpha Vantage")?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Alpha Vantage API error ({}): {}", status, body);
-    }
-    
-    let ts_response: TimeSeriesResponse = response
-        .json()
-        .await
-        .context("Failed to parse Alpha Vantage response")?;
-    
     // Check for API errors
     if let Some(error) = ts_response.error_message {
         anyhow::bail!("Alpha Vantage error: {}", error);
@@ -97,9 +133,12 @@ pub async fn fetch_prices(
         }
     }
     
-    let time_series = ts_response
-        .time_series
-        .context("No time series data in response")?;
+    let Some(time_series) = ts_response.time_series else {
+        match ts_response.information {
+            Some(information) => anyhow::bail!("Alpha Vantage returned no prices: {}", information),
+            None => anyhow::bail!("No time series data in response"),
+        }
+    };
     
     if verbose {
         println!("  → Parsing {} daily records...", time_series.len());
@@ -110,9 +149,7 @@ pub async fn fetch_prices(
     
     for (date_str, daily_data) in time_series {
         // Parse date
-        let date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
-            .context(format!("Failed to parse date: {}", date_str))?;
-        let datetime = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+        let datetime = parse_price_date(&date_str)?;
         
         // Parse price values
         let open = daily_data.open.parse::<f64>()
@@ -123,8 +160,10 @@ pub async fn fetch_prices(
             .context(format!("Failed to parse low price: {}", daily_data.low))?;
         let close = daily_data.close.parse::<f64>()
             .context(format!("Failed to parse close price: {}", daily_data.close))?;
-        let volume = daily_data.volume.parse::<u64>()
-            .context(format!("Failed to parse volume: {}", daily_data.volume))?;
+        // Some feeds report fractional-share volume ("1234.5")
+        let volume = daily_data.volume.parse::<f64>()
+            .context(format!("Failed to parse volume: {}", daily_data.volume))?
+            .round() as u64;
         
         prices.push(PricePoint {
             ticker: ticker.to_string(),
@@ -138,11 +177,11 @@ pub async fn fetch_prices(
     }
     
     // Sort by date (oldest first)
-    prices.sort_by(|a, b| a.date.cmp(&b.date));
+    prices.sort_by_key(|p| p.date);
     
-    // Limit to requested days
-    if prices.len() > days as usize {
-        prices = prices.into_iter().rev().take(days as usize).rev().collect();
+    // Limit to requested days plus the lookback buffer
+    if prices.len() > wanted as usize {
+        prices = prices.into_iter().rev().take(wanted as usize).rev().collect();
     }
     
     if verbose {
@@ -152,12 +191,355 @@ pub async fn fetch_prices(
     Ok(prices)
 }
 
+/// One TIME_SERIES_DAILY request with the given `outputsize`
+async fn request_daily_series(
+    ticker: &str,
+    api_key: &str,
+    base_url: &str,
+    output_size: &str,
+    verbose: bool,
+) -> Result<TimeSeriesResponse> {
+    let url = format!(
+        "{}?function=TIME_SERIES_DAILY&symbol={}&apikey={}&outputsize={}",
+        base_url, ticker, api_key, output_size
+    );
+    
+    if verbose {
+        println!("  → Requesting data from {} (outputsize={})...", base_url, output_size);
+    }
+    
+    crate::http::wait_for_quota(ALPHA_VANTAGE_QUOTA, verbose).await;
+    let response = crate::http::client()
+        .get(&url)
+        .send()
+        .await
+        .context("Failed to fetch stock data from Alpha Vantage")?;
+    crate::http::record_quota(ALPHA_VANTAGE_QUOTA, response.headers(), verbose);
+    
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Alpha Vantage API error ({}): {}", status, body);
+    }
+    
+    response
+        .json()
+        .await
+        .context("Failed to parse Alpha Vantage response")
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooResponse {
+    chart: YahooChart,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooChart {
+    result: Option<Vec<YahooResult>>,
+    error: Option<YahooError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooError {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooResult {
+    #[serde(default)]
+    timestamp: Vec<i64>,
+    indicators: YahooIndicators,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooIndicators {
+    quote: Vec<YahooQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooQuote {
+    open: Vec<Option<f64>>,
+    high: Vec<Option<f64>>,
+    low: Vec<Option<f64>>,
+    close: Vec<Option<f64>>,
+    volume: Vec<Option<u64>>,
+}
+
+/// [`fetch_prices`] behind a process-wide, single-flight cache
+///
+/// Tasks asking for the same ticker, window and endpoint at once share one
+/// request: the first fetches while the rest wait for its result. Results
+/// younger than `max_age` are reused; failures are not cached.
+pub async fn fetch_prices_shared(
+    ticker: &str,
+    api_key: &str,
+    base_url: &str,
+    days: u32,
+    buffer_days: u32,
+    max_age: Duration,
+    verbose: bool,
+) -> Result<Vec<PricePoint>> {
+    let key = format!("{}|{}|{}|{}", base_url, ticker.to_uppercase(), days, buffer_days);
+    let slot = SHARED_PRICES.lock().unwrap().entry(key).or_default().clone();
+    
+    let mut cached = slot.lock().await;
+    if let Some((fetched_at, prices)) = cached.as_ref() {
+        if fetched_at.elapsed() < max_age {
+            if verbose {
+                println!("  → Using cached prices for {}", ticker);
+            }
+            return Ok(prices.clone());
+        }
+    }
+    
+    let prices = fetch_prices(ticker, api_key, base_url, days, buffer_days, verbose).await?;
+    *cached = Some((Instant::now(), prices.clone()));
+    Ok(prices)
+}
+
+/// Fetch historical daily prices from a secondary provider
+///
+/// Covers the same calendar span as [`fetch_prices`] (window plus buffer,
+/// with trading days converted generously to calendar days). Days the
+/// provider reports with missing values are skipped.
+pub async fn fetch_secondary_prices(
+    provider: PriceProvider,
+    ticker: &str,
+    days: u32,
+    buffer_days: u32,
+    verbose: bool,
+) -> Result<Vec<PricePoint>> {
+    match provider {
+        PriceProvider::Yahoo => fetch_yahoo_prices(ticker, days, buffer_days, verbose).await,
+    }
+}
+
+async fn fetch_yahoo_prices(
+    ticker: &str,
+    days: u32,
+    buffer_days: u32,
+    verbose: bool,
+) -> Result<Vec<PricePoint>> {
+    let now = Utc::now();
+    // Buffer days are trading days; 1.5x covers weekends and holidays
+    let span_days = i64::from(days) + i64::from(buffer_days) * 3 / 2;
+    let start = now - chrono::Duration::days(span_days);
+    let url = format!(
+        "{}/{}?interval=1d&period1={}&period2={}",
+        YAHOO_CHART_BASE,
+        ticker,
+        start.timestamp(),
+        now.timestamp()
+    );
+
+    if verbose {
+        println!("  → Requesting secondary prices from {}...", YAHOO_CHART_BASE);
+    }
+
+    crate::http::wait_for_quota(YAHOO_QUOTA, verbose).await;
+    let response = crate::http::client()
+        .get(&url)
+        .header(reqwest::header::USER_AGENT, "Mozilla/5.0")
+        .send()
+        .await
+        .context("Failed to fetch stock data from Yahoo Finance")?;
+    crate::http::record_quota(YAHOO_QUOTA, response.headers(), verbose);
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Yahoo Finance API error ({}): {}", status, body);
+    }
+
+    let parsed: YahooResponse = response
+        .json()
+        .await
+        .context("Failed to parse Yahoo Finance response")?;
+    let prices = yahoo_price_points(ticker, parsed)?;
+
+    if verbose {
+        println!("  → Returning {} secondary price points", prices.len());
+    }
+
+    Ok(prices)
+}
+
+/// Convert a Yahoo chart response into daily price points (oldest first)
+fn yahoo_price_points(ticker: &str, response: YahooResponse) -> Result<Vec<PricePoint>> {
+    if let Some(error) = response.chart.error {
+        anyhow::bail!("Yahoo Finance error: {}", error.description);
+    }
+    let result = response
+        .chart
+        .result
+        .and_then(|results| results.into_iter().next())
+        .context("No chart data in Yahoo Finance response")?;
+    let quote = result
+        .indicators
+        .quote
+        .into_iter()
+        .next()
+        .context("No quote data in Yahoo Finance response")?;
+
+    let mut prices = Vec::new();
+    for (i, timestamp) in result.timestamp.iter().enumerate() {
+        let values = (
+            quote.open.get(i).copied().flatten(),
+            quote.high.get(i).copied().flatten(),
+            quote.low.get(i).copied().flatten(),
+            quote.close.get(i).copied().flatten(),
+        );
+        let (Some(open), Some(high), Some(low), Some(close)) = values else {
+            continue;
+        };
+        let Some(datetime) = DateTime::from_timestamp(*timestamp, 0) else {
+            continue;
+        };
+        // Normalize to midnight UTC so dates line up with Alpha Vantage keys
+        let date = datetime.date_naive().and_hms_opt(0, 0, 0).unwrap();
+        prices.push(PricePoint {
+            ticker: ticker.to_string(),
+            date: Utc.from_utc_datetime(&date),
+            open,
+            close,
+            high,
+            low,
+            volume: quote.volume.get(i).copied().flatten().unwrap_or(0),
+        });
+    }
+
+    prices.sort_by_key(|p| p.date);
+    Ok(prices)
+}
+
+/// Merge a primary and secondary price series by date
+///
+/// The primary's point wins on every date both providers cover; dates only
+/// the secondary has fill gaps. Shared dates whose closes differ by more than
+/// `tolerance_pct` percent (relative to the primary close) are returned as
+/// `(date, primary_close, secondary_close)`.
+pub fn reconcile_prices(
+    primary: Vec<PricePoint>,
+    secondary: Vec<PricePoint>,
+    tolerance_pct: f64,
+) -> (Vec<PricePoint>, Vec<(NaiveDate, f64, f64)>) {
+    let mut by_date: std::collections::BTreeMap<NaiveDate, PricePoint> = secondary
+        .into_iter()
+        .map(|p| (p.date.date_naive(), p))
+        .collect();
+    let mut discrepancies = Vec::new();
+
+    for point in primary {
+        let date = point.date.date_naive();
+        if let Some(other) = by_date.get(&date) {
+            if point.close > 0.0 {
+                let diff_pct = ((other.close - point.close) / point.close).abs() * 100.0;
+                if diff_pct > tolerance_pct {
+                    discrepancies.push((date, point.close, other.close));
+                }
+            }
+        }
+        by_date.insert(date, point);
+    }
+
+    discrepancies.sort_by_key(|(date, _, _)| *date);
+    (by_date.into_values().collect(), discrepancies)
+}
+
+/// Timestamp formats used by Alpha Vantage endpoints (intraday/adjusted first)
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// Date-only format used by the daily endpoints
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parse a time series key, trying each known Alpha Vantage format in turn
+///
+/// Daily keys become midnight UTC; intraday keys keep their time of day.
+fn parse_price_date(date_str: &str) -> Result<DateTime<Utc>> {
+    let trimmed = date_str.trim();
+    
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, DATE_FORMAT) {
+        return Ok(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()));
+    }
+    
+    for format in DATETIME_FORMATS {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(trimmed, format) {
+            return Ok(Utc.from_utc_datetime(&datetime));
+        }
+    }
+    
+    anyhow::bail!(
+        "Failed to parse price date '{}' (expected {} or {})",
+        date_str,
+        DATE_FORMAT,
+        DATETIME_FORMATS.join(" or ")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn point(day: u32, close: f64) -> PricePoint {
+        PricePoint {
+            ticker: "TSLA".to_string(),
+            date: Utc.with_ymd_and_hms(2025, 3, day, 0, 0, 0).unwrap(),
+            open: close,
+            close,
+            high: close,
+            low: close,
+            volume: 1,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_prices_prefers_primary_and_flags_disagreement() {
+        let primary = vec![point(3, 100.0), point(4, 100.0)];
+        let secondary = vec![point(3, 100.5), point(4, 110.0), point(5, 105.0)];
+
+        let (merged, discrepancies) = reconcile_prices(primary, secondary, 2.0);
+
+        let closes: Vec<f64> = merged.iter().map(|p| p.close).collect();
+        assert_eq!(closes, vec![100.0, 100.0, 105.0]);
+        assert_eq!(
+            discrepancies,
+            vec![(NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(), 100.0, 110.0)]
+        );
+    }
+
+    #[test]
+    fn test_premium_only_output_size_is_recognized() {
+        let refused: TimeSeriesResponse = serde_json::from_str(
+            r#"{"Information": "Thank you for using Alpha Vantage! The outputsize=full parameter value is a premium feature for the TIME_SERIES_DAILY endpoint."}"#,
+        ).unwrap();
+        assert!(refused.premium_refusal());
+        
+        let limited: TimeSeriesResponse = serde_json::from_str(
+            r#"{"Note": "Thank you for using Alpha Vantage! Our standard API call frequency is 5 calls per minute."}"#,
+        ).unwrap();
+        assert!(!limited.premium_refusal());
+    }
+
     #[test]
     fn test_alpha_vantage_base_url() {
         assert_eq!(ALPHA_VANTAGE_BASE, "https://www.alphavantage.co/query");
     }
+
+    #[test]
+    fn test_parse_price_date_daily() {
+        let date = parse_price_date("2025-03-14").unwrap();
+        assert_eq!(date, Utc.with_ymd_and_hms(2025, 3, 14, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_price_date_intraday() {
+        let date = parse_price_date("2025-03-14 15:30:00").unwrap();
+        assert_eq!(date, Utc.with_ymd_and_hms(2025, 3, 14, 15, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_price_date_names_bad_input() {
+        let err = parse_price_date("14/03/2025").unwrap_err();
+        assert!(err.to_string().contains("14/03/2025"));
+    }
 }
@@ -1,29 +1,492 @@
-use crate::models::AnalysisResult;
-use anyhow::Result;
-use std::fs;
-use std::path::Path;
+use crate::models::{AnalysisResult, HandleFilter, RawFetch, SentimentMatrix};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 pub const DATA_FILE: &str = "data/results.json";
 
-/// Save analysis results to JSON file
-pub fn save_results(results: &[AnalysisResult]) -> Result<()> {
-    // Ensure data directory exists
-    if let Some(parent) = Path::new(DATA_FILE).parent() {
+/// CEO/ticker list read when neither `--config-path` nor CEO_CONFIG_PATH is set
+pub const DEFAULT_CEO_CONFIG: &str = "ceo_config.json";
+pub const SENTIMENT_MATRIX_FILE: &str = "data/sentiment_matrix.json";
+
+/// Raw fetched tweets and prices, one `{handle}.json` per CEO
+pub const RAW_DATA_DIR: &str = "data/raw";
+
+/// Results of the CEOs a batch run has finished so far (removed once a run completes cleanly)
+pub const BATCH_CHECKPOINT_FILE: &str = "data/batch_checkpoint.json";
+
+/// Default time to wait for another writer's lock (override with STORAGE_LOCK_TIMEOUT_SECS)
+const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 30;
+
+/// First and longest pause between attempts to take a held lock
+const LOCK_RETRY_BASE: Duration = Duration::from_millis(100);
+const LOCK_RETRY_CAP: Duration = Duration::from_secs(2);
+
+/// Where the CEO config lives: `flag`, else CEO_CONFIG_PATH, else `DEFAULT_CEO_CONFIG`
+///
+/// Relative paths are resolved against the working directory.
+pub fn ceo_config_path(flag: Option<&str>) -> PathBuf {
+    let path = flag
+        .map(str::to_string)
+        .or_else(|| std::env::var("CEO_CONFIG_PATH").ok().filter(|p| !p.is_empty()))
+        .unwrap_or_else(|| DEFAULT_CEO_CONFIG.to_string());
+    let path = PathBuf::from(path);
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+/// Parse the CEO config at `path` into each binary's own entry type
+pub fn load_ceo_config<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let json = fs::read_to_string(path).context(format!(
+        "Failed to read CEO config {} (set --config-path or CEO_CONFIG_PATH)",
+        path.display()
+    ))?;
+    serde_json::from_str(&json).context(format!("Failed to parse CEO config {}", path.display()))
+}
+
+/// Exclusive hold on a file's `<path>.lock`, released when dropped
+pub struct FileLock {
+    _file: File,
+}
+
+/// Take the advisory lock that serializes writers of `path`
+///
+/// Waiting gives up after STORAGE_LOCK_TIMEOUT_SECS (default 30). The lock
+/// is per open file, so don't call [`write_atomic`] on `path` while holding it.
+pub fn lock(path: &str) -> Result<FileLock> {
+    if let Some(parent) = Path::new(path).parent() {
         fs::create_dir_all(parent)?;
     }
+    let lock_path = format!("{}.lock", path);
+    let file = File::create(&lock_path)
+        .context(format!("Failed to open lock file: {}", lock_path))?;
+    let timeout = std::env::var("STORAGE_LOCK_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT_SECS);
+    acquire_lock(&file, &lock_path, Duration::from_secs(timeout))?;
+    Ok(FileLock { _file: file })
+}
+
+/// Write a file atomically while holding an advisory lock
+///
+/// The contents go to a temp file in the same directory which is synced and
+/// renamed over `path`, so a crash never leaves a truncated file behind.
+/// A `<path>.lock` file serializes concurrent writers (see [`lock`]).
+pub fn write_atomic(path: &str, contents: &str) -> Result<()> {
+    let _lock = lock(path)?;
+    write_locked(path, contents)
+}
+
+/// [`write_atomic`] for a caller that already holds `path`'s lock
+pub fn write_locked(path: &str, contents: &str) -> Result<()> {
+    let target = Path::new(path);
+    let tmp_path = format!("{}.tmp.{}", path, std::process::id());
+    let write_result = (|| -> Result<()> {
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(contents.as_bytes())?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, target)?;
+        Ok(())
+    })();
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    write_result.context(format!("Failed to write {}", path))
+}
+
+/// Take an exclusive lock, retrying with backoff until `timeout` has passed
+fn acquire_lock(file: &File, lock_path: &str, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+
+    let mut attempt = 0;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                let pause = crate::jitter::backoff(LOCK_RETRY_BASE, attempt, LOCK_RETRY_CAP);
+                std::thread::sleep(pause.min(deadline.saturating_duration_since(Instant::now())));
+                attempt += 1;
+            }
+            Err(TryLockError::WouldBlock) => {
+                anyhow::bail!("Timed out after {}s waiting for lock {}", timeout.as_secs(), lock_path)
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).context(format!("Failed to lock {}", lock_path));
+            }
+        }
+    }
+}
+
+/// Serialize as indented JSON (diff-friendly) or compact JSON (smaller, faster)
+pub fn to_json<T: Serialize + ?Sized>(value: &T, compact: bool) -> Result<String> {
+    let json = if compact {
+        serde_json::to_string(value)?
+    } else {
+        serde_json::to_string_pretty(value)?
+    };
+    Ok(json)
+}
+
+/// Load the stored results, let `update` change them, and save them, all
+/// under the results file's lock
+///
+/// Another writer's results saved in between a plain load and save would be
+/// lost; here they can't land until this update is written. Nothing is
+/// saved when `update` fails. Blocks while waiting for the lock, so async
+/// callers should run it on a blocking thread.
+pub fn update_results<T>(
+    compact: bool,
+    update: impl FnOnce(&mut Vec<AnalysisResult>) -> Result<T>,
+) -> Result<T> {
+    update_json(DATA_FILE, compact, update)
+}
+
+/// [`update_results`] for any JSON file, starting from `V::default()` when
+/// `path` doesn't exist yet
+///
+/// A file that exists but doesn't parse is an error, never replaced.
+pub fn update_json<V, T>(path: &str, compact: bool, update: impl FnOnce(&mut V) -> Result<T>) -> Result<T>
+where
+    V: Serialize + DeserializeOwned + Default,
+{
+    let _lock = lock(path)?;
+    let mut value = load_json(path)?.unwrap_or_default();
+    let outcome = update(&mut value)?;
+    write_locked(path, &to_json(&value, compact)?)?;
+    Ok(outcome)
+}
+
+/// Parse the JSON file at `path`, or None when it doesn't exist
+pub fn load_json<V: DeserializeOwned>(path: &str) -> Result<Option<V>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
 
-    let json = serde_json::to_string_pretty(results)?;
-    fs::write(DATA_FILE, json)?;
-    Ok(())
+    let json = fs::read_to_string(path).context(format!("Failed to read {}", path))?;
+    let value = serde_json::from_str(&json).context(format!("Failed to parse {}", path))?;
+    Ok(Some(value))
 }
 
 /// Load analysis results from JSON file
 pub fn load_results() -> Result<Vec<AnalysisResult>> {
-    if !Path::new(DATA_FILE).exists() {
+    Ok(load_json(DATA_FILE)?.unwrap_or_default())
+}
+
+/// `stored` results with those of the handles in `processed` replaced
+///
+/// Stored results of CEOs `filter` didn't select are kept, so an
+/// `--only`/`--skip` run leaves the other CEOs' results in place. Selected
+/// CEOs that produced no result this run are dropped, as in a full run.
+pub fn merge_processed_results(
+    stored: Vec<AnalysisResult>,
+    processed: Vec<AnalysisResult>,
+    filter: &HandleFilter,
+) -> Vec<AnalysisResult> {
+    let mut merged: Vec<AnalysisResult> = stored
+        .into_iter()
+        .filter(|r| !filter.selects(&r.ceo_handle))
+        .filter(|r| !processed.iter().any(|p| p.ceo_handle.eq_ignore_ascii_case(&r.ceo_handle)))
+        .collect();
+    merged.extend(processed);
+    merged
+}
+
+/// Store one CEO's raw fetch, replacing the previous one
+pub fn save_raw_fetch(raw: &RawFetch) -> Result<()> {
+    let path = format!("{}/{}.json", RAW_DATA_DIR, raw.ceo_handle.to_lowercase());
+    write_atomic(&path, &to_json(raw, true)?)
+}
+
+/// The stored raw fetch for one CEO, if any
+pub fn load_raw_fetch(handle: &str) -> Result<Option<RawFetch>> {
+    let path = format!("{}/{}.json", RAW_DATA_DIR, handle.to_lowercase());
+    if !Path::new(&path).exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&json).context(format!("Failed to parse {}", path))?))
+}
+
+/// Every stored raw fetch (empty when none have been saved)
+pub fn load_raw_fetches() -> Result<Vec<RawFetch>> {
+    if !Path::new(RAW_DATA_DIR).exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut raws = Vec::new();
+    for entry in fs::read_dir(RAW_DATA_DIR)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let json = fs::read_to_string(&path)?;
+        raws.push(serde_json::from_str(&json).context(format!("Failed to parse {}", path.display()))?);
+    }
+    Ok(raws)
+}
+
+/// Record the results a batch run has completed so far
+///
+/// Always compact: the checkpoint is a scratch file, never committed.
+pub fn save_checkpoint(completed: &[AnalysisResult]) -> Result<()> {
+    write_atomic(BATCH_CHECKPOINT_FILE, &to_json(completed, true)?)
+}
+
+/// Results completed by an interrupted batch run (empty when there is none)
+pub fn load_checkpoint() -> Result<Vec<AnalysisResult>> {
+    if !Path::new(BATCH_CHECKPOINT_FILE).exists() {
         return Ok(Vec::new());
     }
 
-    let json = fs::read_to_string(DATA_FILE)?;
-    let results: Vec<AnalysisResult> = serde_json::from_str(&json)?;
-    Ok(results)
+    let json = fs::read_to_string(BATCH_CHECKPOINT_FILE)?;
+    serde_json::from_str(&json).context(format!("Failed to parse {}", BATCH_CHECKPOINT_FILE))
+}
+
+/// Remove the batch checkpoint after a fully successful run
+pub fn clear_checkpoint() -> Result<()> {
+    match fs::remove_file(BATCH_CHECKPOINT_FILE) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).context(format!("Failed to remove {}", BATCH_CHECKPOINT_FILE))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Save the cross-CEO sentiment correlation matrix
+pub fn save_sentiment_matrix(matrix: &SentimentMatrix, compact: bool) -> Result<()> {
+    write_atomic(SENTIMENT_MATRIX_FILE, &to_json(matrix, compact)?)
+}
+
+/// Fold results stored under an old handle into the CEO's current handle
+///
+/// `aliases` maps a lowercased old handle to the current one. A result under
+/// an alias is renamed; if the current handle already has a result, the old
+/// result's tweets it doesn't have yet are appended to it instead, unless the
+/// two were scored with different sentiment lexicons, in which case the old
+/// result is left under its own handle. Statistics are left as stored, so
+/// callers recompute those of results that took in tweets. Returns the
+/// `(old, current)` pairs that were merged so callers can report them.
+pub fn merge_handle_aliases(
+    results: &mut Vec<AnalysisResult>,
+    aliases: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut merged = Vec::new();
+    let mut kept: Vec<AnalysisResult> = Vec::with_capacity(results.len());
+
+    // Current-handle results first, so alias results can fold into them
+    let (aliased, mut current): (Vec<_>, Vec<_>) = results
+        .drain(..)
+        .partition(|r| aliases.contains_key(&r.ceo_handle.to_lowercase()));
+    kept.append(&mut current);
+
+    for mut result in aliased {
+        let old = result.ceo_handle.clone();
+        let canonical = aliases[&old.to_lowercase()].clone();
+
+        match kept.iter_mut().find(|r| r.ceo_handle.eq_ignore_ascii_case(&canonical)) {
+            Some(target) if !same_lexicon(target, &result) => {
+                kept.push(result);
+                continue;
+            }
+            Some(target) => {
+                let known: HashSet<String> = target.impacts.iter().map(|i| i.tweet.id.clone()).collect();
+                let before = target.impacts.len();
+                target.impacts.extend(result.impacts.into_iter().filter(|i| !known.contains(&i.tweet.id)));
+                target.impacts.sort_by_key(|i| i.tweet.created_at);
+                // A trimmed target counts more tweets than it stores; add only the new ones
+                target.total_tweets += target.impacts.len() - before;
+            }
+            None => {
+                result.ceo_handle = canonical.clone();
+                kept.push(result);
+            }
+        }
+        merged.push((old, canonical));
+    }
+
+    *results = kept;
+    merged
+}
+
+/// Whether two results' sentiments are comparable (unknown lexicons are assumed to be)
+fn same_lexicon(a: &AnalysisResult, b: &AnalysisResult) -> bool {
+    match (&a.lexicon_hash, &b.lexicon_hash) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::test_tweet;
+    use crate::models::TweetImpact;
+    use chrono::Utc;
+
+    fn result_with_tweets(handle: &str, ids: &[&str]) -> AnalysisResult {
+        let mut result = AnalysisResult::new(handle.to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        result.impacts = ids
+            .iter()
+            .map(|id| TweetImpact::new(test_tweet(id, "", Utc::now())))
+            .collect();
+        result.total_tweets = ids.len();
+        result
+    }
+
+    #[test]
+    fn test_merge_handle_aliases_folds_old_handle_into_current() {
+        let mut results = vec![
+            result_with_tweets("OldHandle", &["1", "2"]),
+            result_with_tweets("newhandle", &["2", "3"]),
+        ];
+        let aliases = HashMap::from([("oldhandle".to_string(), "newhandle".to_string())]);
+
+        let merged = merge_handle_aliases(&mut results, &aliases);
+
+        assert_eq!(merged, vec![("OldHandle".to_string(), "newhandle".to_string())]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].ceo_handle, "newhandle");
+        assert_eq!(results[0].total_tweets, 3);
+    }
+
+    #[test]
+    fn test_merge_handle_aliases_counts_tweets_trimmed_from_target() {
+        let mut current = result_with_tweets("newhandle", &["2", "3"]);
+        current.total_tweets = 10;
+        current.impacts_trimmed = 8;
+        let mut results = vec![result_with_tweets("oldhandle", &["1", "2"]), current];
+        let aliases = HashMap::from([("oldhandle".to_string(), "newhandle".to_string())]);
+
+        merge_handle_aliases(&mut results, &aliases);
+
+        assert_eq!(results[0].impacts.len(), 3);
+        assert_eq!(results[0].total_tweets, 11);
+    }
+
+    #[test]
+    fn test_merge_handle_aliases_keeps_results_from_other_lexicons_apart() {
+        let mut old = result_with_tweets("oldhandle", &["1"]);
+        old.lexicon_hash = Some("aaaa".to_string());
+        let mut current = result_with_tweets("newhandle", &["2"]);
+        current.lexicon_hash = Some("bbbb".to_string());
+        let mut results = vec![old, current];
+        let aliases = HashMap::from([("oldhandle".to_string(), "newhandle".to_string())]);
+
+        let merged = merge_handle_aliases(&mut results, &aliases);
+
+        assert!(merged.is_empty());
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.impacts.len() == 1));
+    }
+
+    #[test]
+    fn test_filtered_run_keeps_unselected_results() {
+        let stored = vec![
+            result_with_tweets("alice", &["1"]),
+            result_with_tweets("bob", &["2"]),
+            result_with_tweets("carol", &["3"]),
+        ];
+        let processed = vec![result_with_tweets("Bob", &["4", "5"])];
+        let filter = HandleFilter { only: vec!["bob".to_string(), "carol".to_string()], skip: Vec::new() };
+
+        let merged = merge_processed_results(stored, processed, &filter);
+
+        // carol was selected but produced nothing, so only alice is carried over
+        let handles: Vec<&str> = merged.iter().map(|r| r.ceo_handle.as_str()).collect();
+        assert_eq!(handles, ["alice", "Bob"]);
+        assert_eq!(merged[1].impacts.len(), 2);
+    }
+
+    /// A fresh scratch directory for one test's files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("storage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file_without_leftovers() {
+        let dir = scratch_dir("atomic");
+        let path = dir.join("results.json");
+        let path = path.to_str().unwrap();
+
+        write_atomic(path, "first").unwrap();
+        write_atomic(path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "second");
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["results.json", "results.json.lock"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_held_lock_times_out_other_writers() {
+        let dir = scratch_dir("lock");
+        let path = dir.join("results.json");
+        let path = path.to_str().unwrap();
+        let lock_path = format!("{}.lock", path);
+
+        let held = lock(path).unwrap();
+        let other = File::create(&lock_path).unwrap();
+        let err = acquire_lock(&other, &lock_path, Duration::ZERO).unwrap_err();
+        assert!(err.to_string().contains("Timed out"), "{}", err);
+
+        drop(held);
+        acquire_lock(&other, &lock_path, Duration::ZERO).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_json_holds_lock_and_saves_only_on_success() {
+        let dir = scratch_dir("update");
+        let path = dir.join("results.json");
+        let path = path.to_str().unwrap();
+        let lock_path = format!("{}.lock", path);
+
+        // A missing file starts from the default, and the lock is held throughout
+        let count = update_json(path, true, |results: &mut Vec<AnalysisResult>| {
+            let other = File::create(&lock_path)?;
+            assert!(acquire_lock(&other, &lock_path, Duration::ZERO).is_err());
+            results.push(result_with_tweets("alice", &["1"]));
+            Ok(results.len())
+        }).unwrap();
+        assert_eq!(count, 1);
+
+        // A failed update leaves the stored results as they were
+        let failed: Result<()> = update_json(path, true, |results: &mut Vec<AnalysisResult>| {
+            results.clear();
+            anyhow::bail!("rejected")
+        });
+        assert!(failed.is_err());
+        let stored: Vec<AnalysisResult> = load_json(path).unwrap().unwrap();
+        assert_eq!(stored[0].ceo_handle, "alice");
+
+        // A file that doesn't parse is an error, not a fresh start
+        fs::write(path, "{ not json").unwrap();
+        let err = update_json(path, true, |_: &mut Vec<AnalysisResult>| Ok(())).unwrap_err();
+        assert!(err.to_string().contains("Failed to parse"), "{}", err);
+        assert_eq!(fs::read_to_string(path).unwrap(), "{ not json");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_ceo_config_names_absolute_path() {
+        let path = ceo_config_path(Some("no/such/ceo_config.json"));
+        assert!(path.is_absolute());
+        assert!(path.ends_with("no/such/ceo_config.json"));
+
+        let err = load_ceo_config::<serde_json::Value>(&path).unwrap_err();
+        assert!(err.to_string().contains(&path.display().to_string()), "{}", err);
+    }
 }
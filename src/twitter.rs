@@ -2,14 +2,40 @@
 //!
 //! This module handles authentication and fetching tweets from the Twitter API v2.
 //! It uses reqwest for HTTP requests and handles rate limiting gracefully.
+//! With an academic/elevated token, the full-archive search endpoint can be
+//! used instead of the user timeline to reach past its ~3200-tweet cap.
 
 use crate::models::Tweet;
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Deserialize;
 
-/// Twitter API v2 base URL
-const TWITTER_API_BASE: &str = "https://api.twitter.com/2";
+/// Default Twitter API v2 base URL (override with `--twitter-api-base`)
+pub const TWITTER_API_BASE: &str = "https://api.twitter.com/2";
+
+/// Default tweet cap for the timeline and scraper paths
+pub const DEFAULT_MAX_TWEETS: usize = 50;
+
+/// Default tweet cap for full-archive search
+const ARCHIVE_MAX_TWEETS: usize = 10_000;
+
+/// Most IDs the tweet lookup endpoint accepts per request
+const LOOKUP_BATCH_SIZE: usize = 100;
+
+/// Full-archive search allows one request per second
+const ARCHIVE_REQUEST_DELAY_MS: u64 = 1100;
+
+/// Single pause before retrying a rejected API fetch through the scraper
+/// (there's no further retry); jittered, so runs that hit the same quota
+/// together don't all reach the scraper at once
+const SCRAPER_FALLBACK_DELAY_MS: u64 = 2000;
+
+/// Names each endpoint's rate-limit quota is tracked under (see
+/// `http::record_quota`); Twitter limits every endpoint separately
+const USER_LOOKUP_QUOTA: &str = "twitter:/users/by/username/:username";
+const TIMELINE_QUOTA: &str = "twitter:/users/:id/tweets";
+const SEARCH_QUOTA: &str = "twitter:/tweets/search/all";
+const TWEET_LOOKUP_QUOTA: &str = "twitter:/tweets";
 
 /// Response from Twitter API user lookup
 #[derive(Debug, Deserialize)]
@@ -21,6 +47,162 @@ struct UserLookupResponse {
 struct UserData {
     id: String,
     username: String,
+    public_metrics: Option<UserMetrics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserMetrics {
+    followers_count: u64,
+}
+
+/// A resolved Twitter account
+#[derive(Debug, Clone)]
+pub struct TwitterUser {
+    pub id: String,
+    /// Follower count, when the lookup returned public metrics
+    pub follower_count: Option<u64>,
+}
+
+/// What to fetch for an account
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Only tweets from the last `days` days are kept
+    pub days: u32,
+    /// Stop after this many tweets (None = `DEFAULT_MAX_TWEETS`, or
+    /// `ARCHIVE_MAX_TWEETS` for archive search)
+    pub max_tweets: Option<usize>,
+    /// Use full-archive search instead of the user timeline (API token only)
+    pub search_archive: bool,
+    /// Stop once tweets predate this day (UTC), even inside the `days` window
+    pub until_date: Option<NaiveDate>,
+    /// Stop after this many API pages (None = no page limit)
+    pub max_pages: Option<usize>,
+    /// Tweets from an earlier timeline fetch of the same account; the API
+    /// path then asks only for newer tweets (`since_id`) and merges these in
+    pub cached_tweets: Vec<Tweet>,
+    /// When a timeline fetch through the API fails on auth or quota, retry
+    /// it through the scraper (needs scraper credentials)
+    pub scraper_fallback: bool,
+}
+
+impl FetchOptions {
+    /// Last `days` days, default limits
+    pub fn days(days: u32) -> Self {
+        FetchOptions {
+            days,
+            max_tweets: None,
+            search_archive: false,
+            until_date: None,
+            max_pages: None,
+            cached_tweets: Vec::new(),
+            scraper_fallback: false,
+        }
+    }
+}
+
+/// Bounds that end a fetch, checked tweet by tweet (and page by page)
+#[derive(Clone)]
+struct FetchLimits {
+    max_tweets: usize,
+    /// Start of the `days` window
+    cutoff: DateTime<Utc>,
+    /// Start of `FetchOptions::until_date`
+    until: Option<DateTime<Utc>>,
+    max_pages: Option<usize>,
+    /// Only tweets newer than this ID are requested (incremental fetch)
+    since_id: Option<String>,
+}
+
+impl FetchLimits {
+    /// Why a tweet created at `created_at` ends the fetch, if it does
+    fn date_stop(&self, created_at: DateTime<Utc>) -> Option<FetchStop> {
+        if self.until.is_some_and(|until| created_at < until) {
+            Some(FetchStop::UntilDate)
+        } else if created_at < self.cutoff {
+            Some(FetchStop::DateWindow)
+        } else {
+            None
+        }
+    }
+}
+
+/// Which limit ended a fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchStop {
+    /// The tweet cap was reached before the start of the date window
+    MaxTweets,
+    /// Tweets older than the date window were reached
+    DateWindow,
+    /// The source had no more tweets to return
+    Exhausted,
+    /// Tweets older than `FetchOptions::until_date` were reached
+    UntilDate,
+    /// The page cap was reached
+    MaxPages,
+}
+
+impl FetchStop {
+    /// Short explanation for progress output
+    pub fn describe(&self) -> &'static str {
+        match self {
+            FetchStop::MaxTweets => "tweet limit reached",
+            FetchStop::DateWindow => "reached the start of the date window",
+            FetchStop::Exhausted => "no older tweets available",
+            FetchStop::UntilDate => "reached the --fetch-until-date boundary",
+            FetchStop::MaxPages => "page limit reached",
+        }
+    }
+}
+
+/// Tweets fetched for an account plus what we learned about it
+#[derive(Debug, Clone)]
+pub struct TweetFetch {
+    pub tweets: Vec<Tweet>,
+    /// Follower count (None on the scraper path, where it isn't fetched)
+    pub follower_count: Option<u64>,
+    /// Tweets discarded because they had no usable timestamp
+    pub dropped_without_timestamp: usize,
+    /// Why fetching stopped
+    pub stop: FetchStop,
+    /// The API failed and these tweets came from the scraper instead
+    pub fell_back_to_scraper: bool,
+}
+
+/// A non-success response from the Twitter API
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Twitter API error ({}): {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ApiError {
+    /// Rejected token, unenrolled app, or rate limit / monthly cap hit;
+    /// another credential source may still work, unlike e.g. a missing account
+    pub fn is_credential_error(&self) -> bool {
+        matches!(self.status.as_u16(), 401 | 403 | 429)
+    }
+}
+
+/// Whether `error` came from an API auth or quota rejection
+fn is_credential_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| cause.downcast_ref::<ApiError>().is_some_and(ApiError::is_credential_error))
+}
+
+/// Turn a non-success API response into an `ApiError`
+async fn api_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    ApiError { status, body }.into()
 }
 
 /// Response from Twitter API tweets endpoint
@@ -51,30 +233,94 @@ struct Meta {
 }
 
 /// Fetch tweets from a CEO's Twitter account (via API or Scraper)
+///
+/// Fetching pages back from the newest tweet until either the tweet cap or
+/// the start of the `days` window is reached; `TweetFetch::stop` says which.
+/// With `search_archive` (API token only) tweets come from full-archive
+/// search instead of the user timeline. With `scraper_fallback` and scraper
+/// credentials, a timeline fetch the API rejects on auth or quota is retried
+/// through the scraper.
 pub async fn fetch_tweets(
     handle: &str,
+    api_base: &str,
     bearer_token: Option<&str>,
     username: Option<&str>,
     password: Option<&str>,
-    _days: u32,
+    options: &FetchOptions,
     verbose: bool,
-) -> Result<Vec<Tweet>> {
+) -> Result<TweetFetch> {
+    let default_max = if options.search_archive { ARCHIVE_MAX_TWEETS } else { DEFAULT_MAX_TWEETS };
+    let limits = FetchLimits {
+        max_tweets: options.max_tweets.unwrap_or(default_max),
+        cutoff: Utc::now() - chrono::Duration::days(i64::from(options.days)),
+        until: options.until_date.map(|d| d.and_time(chrono::NaiveTime::MIN).and_utc()),
+        max_pages: options.max_pages,
+        since_id: None,
+    };
+    
     if let Some(token) = bearer_token {
+        if options.search_archive {
+            if verbose { println!("  → Using Twitter API v2 full-archive search"); }
+            return fetch_tweets_archive(handle, api_base, token, &limits, verbose).await;
+        }
         if verbose { println!("  → Using Twitter API v2"); }
-        return fetch_tweets_api(handle, token, verbose).await;
+        
+        let error = match fetch_timeline_api(handle, api_base, token, &options.cached_tweets, &limits, verbose).await {
+            Ok(fetch) => return Ok(fetch),
+            Err(e) => e,
+        };
+        let can_fall_back = options.scraper_fallback && username.is_some() && password.is_some();
+        if !(can_fall_back && is_credential_error(&error)) {
+            return Err(error);
+        }
+        println!("  → Twitter API unavailable for @{} ({}); falling back to the scraper", handle, error);
+        let pause = tokio::time::Duration::from_millis(SCRAPER_FALLBACK_DELAY_MS);
+        tokio::time::sleep(crate::jitter::delay(pause)).await;
+        let mut fetch = fetch_tweets_scraper(handle, username, password, &limits, verbose).await?;
+        fetch.fell_back_to_scraper = true;
+        return Ok(fetch);
+    }
+    if options.search_archive {
+        anyhow::bail!("Full-archive search requires a Twitter API bearer token");
     }
     
     if verbose { println!("  → Using Twitter Scraper"); }
-    fetch_tweets_scraper(handle, username, password, verbose).await
+    fetch_tweets_scraper(handle, username, password, &limits, verbose).await
+}
+
+/// Timeline fetch through the API, asking only for tweets newer than
+/// `cached` and merging those back in when there are any
+async fn fetch_timeline_api(
+    handle: &str,
+    api_base: &str,
+    bearer_token: &str,
+    cached: &[Tweet],
+    limits: &FetchLimits,
+    verbose: bool,
+) -> Result<TweetFetch> {
+    let Some(since_id) = newest_tweet_id(cached) else {
+        return fetch_tweets_api(handle, api_base, bearer_token, limits, verbose).await;
+    };
+    if verbose { println!("  → Fetching only tweets newer than {} (cached)", since_id); }
+    let limits = FetchLimits { since_id: Some(since_id), ..limits.clone() };
+    let mut fetch = fetch_tweets_api(handle, api_base, bearer_token, &limits, verbose).await?;
+    let fetched = fetch.tweets.len();
+    merge_cached_tweets(&mut fetch, cached, &limits);
+    if verbose {
+        println!("  → {} new tweets, {} reused from the cache", fetched, fetch.tweets.len() - fetched);
+    }
+    Ok(fetch)
 }
 
 async fn fetch_tweets_scraper(
     handle: &str,
     username: Option<&str>,
     password: Option<&str>,
+    limits: &FetchLimits,
     verbose: bool
-) -> Result<Vec<Tweet>> {
+) -> Result<TweetFetch> {
     use agent_twitter_client::scraper::Scraper;
+    let max_tweets = limits.max_tweets;
 
     let mut scraper = Scraper::new().await?;
 
@@ -90,7 +336,6 @@ async fn fetch_tweets_scraper(
         if verbose { println!("  → Attempting guest access (may have limits)"); }
     }
 
-    let max_tweets = 50;
     if verbose { println!("  → Scraping latest {} tweets...", max_tweets); }
 
     // Get user profile to obtain user_id
@@ -104,13 +349,21 @@ async fn fetch_tweets_scraper(
         None  // cursor
     ).await.context("Failed to scrape tweets")?;
 
+    let returned = scraper_tweets.tweets.len();
     let mut tweets = Vec::new();
+    let mut dropped = 0;
+    let mut date_stop = None;
     for t in scraper_tweets.tweets {
-         let created_at = if let Some(ts) = t.timestamp {
-             DateTime::<Utc>::from_timestamp(ts, 0).unwrap_or(Utc::now())
-         } else {
-             Utc::now()
+         // Without a real timestamp the tweet can't be aligned to prices;
+         // guessing "now" would pin it to today's move, so drop it instead
+         let Some(created_at) = t.timestamp.and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0)) else {
+             dropped += 1;
+             continue;
          };
+         if let Some(stop) = limits.date_stop(created_at) {
+             date_stop.get_or_insert(stop);
+             continue;
+         }
 
          tweets.push(Tweet {
              id: t.id.unwrap_or_default(),
@@ -119,57 +372,182 @@ async fn fetch_tweets_scraper(
              retweet_count: t.retweets.unwrap_or(0) as u32,
              like_count: t.likes.unwrap_or(0) as u32,
              sentiment: None,
+             sentiment_disagreement: None,
+             pos_hits: 0,
+             neg_hits: 0,
+             mixed: false,
          });
     }
 
-    Ok(tweets)
+    if verbose && dropped > 0 {
+        println!("  → Dropped {} tweets without a timestamp", dropped);
+    }
+
+    let stop = if let Some(stop) = date_stop {
+        stop
+    } else if returned >= max_tweets {
+        FetchStop::MaxTweets
+    } else {
+        FetchStop::Exhausted
+    };
+
+    Ok(TweetFetch { tweets, follower_count: None, dropped_without_timestamp: dropped, stop, fell_back_to_scraper: false })
 }
 
 async fn fetch_tweets_api(
     handle: &str,
+    api_base: &str,
     bearer_token: &str,
+    limits: &FetchLimits,
     verbose: bool,
-) -> Result<Vec<Tweet>> {
+) -> Result<TweetFetch> {
     if verbose {
         println!("  → Looking up user ID for @{}", handle);
     }
     
-    // Step 1: Get user ID from handle
-    let user_id = get_user_id(handle, bearer_token).await?;
+    // Step 1: Get user ID (and follower count) from handle
+    let user = lookup_user(handle, api_base, bearer_token).await?;
+    let user_id = user.id;
     
     if verbose {
         println!("  → User ID: {}", user_id);
+        if let Some(followers) = user.follower_count {
+            println!("  → Followers: {}", followers);
+        }
+    }
+    
+    // Step 2: Page back through the timeline (STRICT LIMIT: max_tweets)
+    if verbose {
+        let since = limits.until.map_or(limits.cutoff, |until| until.max(limits.cutoff));
+        println!("  → Fetching up to {} tweets since {}...", limits.max_tweets, since.format("%Y-%m-%d"));
     }
     
-    // Step 2: Fetch latest tweets (STRICT LIMIT: 50 tweets)
-    let max_tweets = 50; 
+    let (tweets, stop) = fetch_user_tweets_api(&user_id, api_base, bearer_token, limits, verbose).await?;
+    
+    Ok(TweetFetch { tweets, follower_count: user.follower_count, dropped_without_timestamp: 0, stop, fell_back_to_scraper: false })
+}
+
+/// Fetch original tweets from the last `days` days via `/tweets/search/all`
+async fn fetch_tweets_archive(
+    handle: &str,
+    api_base: &str,
+    bearer_token: &str,
+    limits: &FetchLimits,
+    verbose: bool,
+) -> Result<TweetFetch> {
+    // Resolves the handle (fails early on typos) and gets the follower count
+    let user = lookup_user(handle, api_base, bearer_token).await?;
+    
+    // end_time must be at least 10 seconds before the request
+    let end_time = Utc::now() - chrono::Duration::seconds(30);
+    let start_time = limits.cutoff;
+    let url = format!(
+        "{}/tweets/search/all?query=from:{}%20-is:retweet%20-is:reply&start_time={}&end_time={}&max_results=100&tweet.fields=created_at,public_metrics",
+        api_base,
+        handle,
+        start_time.format("%Y-%m-%dT%H:%M:%SZ"),
+        end_time.format("%Y-%m-%dT%H:%M:%SZ"),
+    );
     
     if verbose {
-        println!("  → Fetching latest {} tweets...", max_tweets);
+        println!("  → Searching archive from {} (up to {} tweets)...", start_time.format("%Y-%m-%d"), limits.max_tweets);
     }
     
-    let tweets = fetch_user_tweets_api(&user_id, bearer_token, max_tweets, verbose).await?;
+    let (tweets, stop) = fetch_paginated(
+        &url,
+        "next_token",
+        SEARCH_QUOTA,
+        bearer_token,
+        limits,
+        ARCHIVE_REQUEST_DELAY_MS,
+        verbose,
+    ).await?;
     
-    Ok(tweets)
+    Ok(TweetFetch { tweets, follower_count: user.follower_count, dropped_without_timestamp: 0, stop, fell_back_to_scraper: false })
 }
 
 
-/// Get user ID from Twitter handle (API)
-async fn get_user_id(handle: &str, bearer_token: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/users/by/username/{}", TWITTER_API_BASE, handle);
+/// Hydrate specific tweets by ID via `/2/tweets?ids=` (API token only)
+///
+/// IDs the API can't return (deleted, protected) are skipped and counted
+/// in verbose output; the rest come back oldest first.
+pub async fn fetch_tweets_by_id(
+    ids: &[String],
+    api_base: &str,
+    bearer_token: &str,
+    verbose: bool,
+) -> Result<TweetFetch> {
+    let client = crate::http::client();
+    let mut tweets = Vec::new();
+    
+    for batch in ids.chunks(LOOKUP_BATCH_SIZE) {
+        let url = format!(
+            "{}/tweets?ids={}&tweet.fields=created_at,public_metrics",
+            api_base,
+            batch.join(",")
+        );
+        crate::http::wait_for_quota(TWEET_LOOKUP_QUOTA, verbose).await;
+        let response = client
+            .get(&url)
+            .bearer_auth(bearer_token)
+            .send()
+            .await
+            .context("Failed to look up tweets from Twitter API")?;
+        crate::http::record_quota(TWEET_LOOKUP_QUOTA, response.headers(), verbose);
+        
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        
+        let tweets_response: TweetsResponse = response
+            .json()
+            .await
+            .context("Failed to parse tweet lookup response")?;
+        for tweet_data in tweets_response.data.unwrap_or_default() {
+            tweets.push(tweet_from_data(tweet_data)?);
+        }
+    }
     
+    if verbose && tweets.len() < ids.len() {
+        println!("  → {} of {} tweet IDs could not be found", ids.len() - tweets.len(), ids.len());
+    }
+    tweets.sort_by_key(|t| t.created_at);
+    
+    Ok(TweetFetch {
+        tweets,
+        follower_count: None,
+        dropped_without_timestamp: 0,
+        stop: FetchStop::Exhausted,
+        fell_back_to_scraper: false,
+    })
+}
+
+/// Tweet ID from a bare ID or a tweet URL (`https://x.com/user/status/123?s=20`)
+pub fn parse_tweet_id(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let id = match trimmed.split_once("/status/") {
+        Some((_, rest)) => rest.split(['?', '/', '#']).next().unwrap_or_default(),
+        None => trimmed,
+    };
+    (!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())).then(|| id.to_string())
+}
+
+/// Look up a user's ID and follower count from their Twitter handle (API)
+pub async fn lookup_user(handle: &str, api_base: &str, bearer_token: &str) -> Result<TwitterUser> {
+    let client = crate::http::client();
+    let url = format!("{}/users/by/username/{}?user.fields=public_metrics", api_base, handle);
+    
+    crate::http::wait_for_quota(USER_LOOKUP_QUOTA, false).await;
     let response = client
         .get(&url)
         .bearer_auth(bearer_token)
         .send()
         .await
         .context("Failed to fetch user data from Twitter API")?;
+    crate::http::record_quota(USER_LOOKUP_QUOTA, response.headers(), false);
     
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Twitter API error ({}): {}", status, body);
+        return Err(api_error(response).await);
     }
     
     let user_response: UserLookupResponse = response
@@ -177,47 +555,74 @@ async fn get_user_id(handle: &str, bearer_token: &str) -> Result<String> {
         .await
         .context("Failed to parse user lookup response")?;
     
-    Ok(user_response.data.id)
+    Ok(TwitterUser {
+        id: user_response.data.id,
+        follower_count: user_response.data.public_metrics.map(|m| m.followers_count),
+    })
 }
 
-/// Fetch tweets for a user with a strict count limit (API)
+/// Fetch tweets for a user back to the date bounds, with strict count and
+/// page limits (API)
 async fn fetch_user_tweets_api(
     user_id: &str,
+    api_base: &str,
     bearer_token: &str,
-    max_tweets: usize,
+    limits: &FetchLimits,
+    verbose: bool,
+) -> Result<(Vec<Tweet>, FetchStop)> {
+    let fetch_count = std::cmp::min(limits.max_tweets, 100);
+    let mut url = format!(
+        "{}/users/{}/tweets?max_results={}&tweet.fields=created_at,public_metrics&exclude=retweets,replies",
+        api_base,
+        user_id,
+        fetch_count
+    );
+    if let Some(since_id) = &limits.since_id {
+        url.push_str(&format!("&since_id={}", since_id));
+    }
+    
+    fetch_paginated(&url, "pagination_token", TIMELINE_QUOTA, bearer_token, limits, 100, verbose).await
+}
+
+/// Follow a tweets endpoint's `next_token` (newest first) until one of
+/// `limits` is hit or the last page, returning whatever was gathered
+///
+/// `token_param` is the query parameter that carries the token back
+/// (`pagination_token` for timelines, `next_token` for search), and `quota`
+/// the name the endpoint's rate limit is tracked under.
+async fn fetch_paginated(
+    base_url: &str,
+    token_param: &str,
+    quota: &str,
+    bearer_token: &str,
+    limits: &FetchLimits,
+    delay_ms: u64,
     verbose: bool,
-) -> Result<Vec<Tweet>> {
-    let client = reqwest::Client::new();
+) -> Result<(Vec<Tweet>, FetchStop)> {
+    let client = crate::http::client();
     let mut all_tweets = Vec::new();
     let mut next_token: Option<String> = None;
+    let mut pages = 0;
     
-    let fetch_count = std::cmp::min(max_tweets, 100);
-    
-    while all_tweets.len() < max_tweets {
-        let mut url = format!(
-            "{}/users/{}/tweets?max_results={}&tweet.fields=created_at,public_metrics",
-            TWITTER_API_BASE,
-            user_id,
-            fetch_count
-        );
+    let stop = 'pages: loop {
+        let mut url = base_url.to_string();
         
         if let Some(token) = &next_token {
-            url.push_str(&format!("&pagination_token={}", token));
+            url.push_str(&format!("&{}={}", token_param, token));
         }
         
-        url.push_str("&exclude=retweets,replies");
-        
+        // Waits out a used-up quota here rather than getting a 429 mid-pagination
+        crate::http::wait_for_quota(quota, verbose).await;
         let response = client
             .get(&url)
             .bearer_auth(bearer_token)
             .send()
             .await
             .context("Failed to fetch tweets from Twitter API")?;
+        crate::http::record_quota(quota, response.headers(), verbose);
         
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Twitter API error ({}): {}", status, body);
+            return Err(api_error(response).await);
         }
         
         let tweets_response: TweetsResponse = response
@@ -225,54 +630,182 @@ async fn fetch_user_tweets_api(
             .await
             .context("Failed to parse tweets response")?;
         
+        pages += 1;
+        
         if let Some(data) = tweets_response.data {
             for tweet_data in data {
-                if all_tweets.len() >= max_tweets {
-                    break;
+                if all_tweets.len() >= limits.max_tweets {
+                    break 'pages FetchStop::MaxTweets;
                 }
                 
-                let created_at = DateTime::parse_from_rfc3339(&tweet_data.created_at)
-                    .context("Failed to parse tweet timestamp")?
-                    .with_timezone(&Utc);
+                let tweet = tweet_from_data(tweet_data)?;
                 
-                let metrics = tweet_data.public_metrics.unwrap_or(PublicMetrics {
-                    retweet_count: 0,
-                    like_count: 0,
-                });
+                // Pages run newest to oldest, so everything after this is older too
+                if let Some(stop) = limits.date_stop(tweet.created_at) {
+                    break 'pages stop;
+                }
                 
-                all_tweets.push(Tweet {
-                    id: tweet_data.id,
-                    text: tweet_data.text,
-                    created_at,
-                    retweet_count: metrics.retweet_count,
-                    like_count: metrics.like_count,
-                    sentiment: None,
-                });
+                all_tweets.push(tweet);
             }
         }
         
-        if let Some(meta) = tweets_response.meta {
-            if verbose {
-                println!("  → Fetched {} tweets so far...", all_tweets.len());
-            }
-            
-            next_token = meta.next_token;
-            if next_token.is_none() || all_tweets.len() >= max_tweets {
-                break;
-            }
-        } else {
-            break;
+        if verbose {
+            println!("  → Fetched {} tweets so far...", all_tweets.len());
         }
         
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        next_token = tweets_response.meta.and_then(|meta| meta.next_token);
+        if next_token.is_none() {
+            break FetchStop::Exhausted;
+        }
+        if all_tweets.len() >= limits.max_tweets {
+            break FetchStop::MaxTweets;
+        }
+        if limits.max_pages.is_some_and(|max| pages >= max) {
+            break FetchStop::MaxPages;
+        }
+        
+        tokio::time::sleep(crate::jitter::delay(tokio::time::Duration::from_millis(delay_ms))).await;
+    };
+    
+    if verbose {
+        println!("  → Stopped after {} pages: {}", pages, stop.describe());
     }
+    Ok((all_tweets, stop))
+}
+
+/// ID of the newest tweet (by timestamp) that has a numeric ID
+fn newest_tweet_id(tweets: &[Tweet]) -> Option<String> {
+    tweets
+        .iter()
+        .filter(|t| t.id.parse::<u64>().is_ok())
+        .max_by_key(|t| t.created_at)
+        .map(|t| t.id.clone())
+}
+
+/// Append cached tweets (still inside the date bounds) behind an incremental fetch
+///
+/// The cap still applies to the merged list; when it cuts off cached tweets
+/// the fetch reports `MaxTweets`. Cached engagement counts are as of the
+/// earlier fetch.
+fn merge_cached_tweets(fetch: &mut TweetFetch, cached: &[Tweet], limits: &FetchLimits) {
+    let mut older: Vec<Tweet> = cached
+        .iter()
+        .filter(|t| limits.date_stop(t.created_at).is_none())
+        .filter(|t| !fetch.tweets.iter().any(|new| new.id == t.id))
+        .cloned()
+        .collect();
+    older.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+    fetch.tweets.extend(older);
+    
+    if fetch.tweets.len() > limits.max_tweets {
+        fetch.tweets.truncate(limits.max_tweets);
+        fetch.stop = FetchStop::MaxTweets;
+    }
+}
+
+/// Convert an API tweet object into a `Tweet`
+fn tweet_from_data(tweet_data: TweetData) -> Result<Tweet> {
+    let created_at = DateTime::parse_from_rfc3339(&tweet_data.created_at)
+        .context("Failed to parse tweet timestamp")?
+        .with_timezone(&Utc);
     
-    Ok(all_tweets)
+    let metrics = tweet_data.public_metrics.unwrap_or(PublicMetrics {
+        retweet_count: 0,
+        like_count: 0,
+    });
+    
+    Ok(Tweet {
+        id: tweet_data.id,
+        text: tweet_data.text,
+        created_at,
+        retweet_count: metrics.retweet_count,
+        like_count: metrics.like_count,
+        sentiment: None,
+        sentiment_disagreement: None,
+        pos_hits: 0,
+        neg_hits: 0,
+        mixed: false,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::test_support::test_tweet;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_tweet_id_from_id_or_url() {
+        assert_eq!(parse_tweet_id(" 1234567890 ").as_deref(), Some("1234567890"));
+        assert_eq!(
+            parse_tweet_id("https://x.com/elonmusk/status/1519480761749016577?s=20").as_deref(),
+            Some("1519480761749016577")
+        );
+        assert_eq!(parse_tweet_id("https://twitter.com/elonmusk"), None);
+        assert_eq!(parse_tweet_id(""), None);
+    }
+
+    #[test]
+    fn test_date_stop_reports_the_bound_hit_first() {
+        let at = |day: u32| Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap();
+        let limits = FetchLimits {
+            max_tweets: 50,
+            cutoff: at(1),
+            until: Some(at(10)),
+            max_pages: None,
+            since_id: None,
+        };
+        
+        assert_eq!(limits.date_stop(at(12)), None);
+        assert_eq!(limits.date_stop(at(5)), Some(FetchStop::UntilDate));
+        
+        let limits = FetchLimits { until: None, ..limits };
+        assert_eq!(limits.date_stop(at(5)), None);
+        assert_eq!(limits.date_stop(Utc.with_ymd_and_hms(2025, 2, 20, 0, 0, 0).unwrap()), Some(FetchStop::DateWindow));
+    }
+
+    #[test]
+    fn test_merge_cached_tweets_after_incremental_fetch() {
+        let tweet = |id: &str, day: u32| test_tweet(id, "", Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap());
+        let cached = vec![tweet("100", 2), tweet("300", 8), tweet("200", 5)];
+        assert_eq!(newest_tweet_id(&cached).as_deref(), Some("300"));
+        
+        // Day 2 is outside the window; "300" came back in the new fetch too
+        let limits = FetchLimits {
+            max_tweets: 50,
+            cutoff: Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap(),
+            until: None,
+            max_pages: None,
+            since_id: Some("300".to_string()),
+        };
+        let mut fetch = TweetFetch {
+            tweets: vec![tweet("400", 9), tweet("300", 8)],
+            follower_count: None,
+            dropped_without_timestamp: 0,
+            stop: FetchStop::Exhausted,
+            fell_back_to_scraper: false,
+        };
+        merge_cached_tweets(&mut fetch, &cached, &limits);
+        let ids: Vec<_> = fetch.tweets.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["400", "300", "200"]);
+        assert_eq!(fetch.stop, FetchStop::Exhausted);
+        
+        merge_cached_tweets(&mut fetch, &cached, &FetchLimits { max_tweets: 2, ..limits });
+        assert_eq!(fetch.tweets.len(), 2);
+        assert_eq!(fetch.stop, FetchStop::MaxTweets);
+    }
+
+    #[test]
+    fn test_only_auth_and_quota_errors_allow_fallback() {
+        let api = |status: u16| -> anyhow::Error {
+            ApiError { status: reqwest::StatusCode::from_u16(status).unwrap(), body: String::new() }.into()
+        };
+        assert!(is_credential_error(&api(429)));
+        assert!(is_credential_error(&api(401)));
+        assert!(is_credential_error(&api(403).context("Failed to fetch user data")));
+        assert!(!is_credential_error(&api(404)));
+        assert!(!is_credential_error(&anyhow::anyhow!("Failed to parse user lookup response")));
+    }
 
     #[test]
     fn test_twitter_api_base_url() {
@@ -4,15 +4,119 @@
 //! Data is updated daily via CI/CD and stored in data/tracking.json
 //! Tracks MONTHLY metrics - tweets this month and stock change since month start.
 
+#[path = "analysis.rs"]
+mod analysis;
+#[path = "http.rs"]
+mod http;
+#[path = "jitter.rs"]
+mod jitter;
+#[path = "lexicon.rs"]
+mod lexicon;
+#[path = "models.rs"]
+mod models;
+#[path = "news.rs"]
+mod news;
+#[path = "output.rs"]
+mod output;
+#[path = "prolog.rs"]
+mod prolog;
+#[path = "stocks.rs"]
+mod stocks;
+#[path = "storage.rs"]
+mod storage;
+#[path = "twitter.rs"]
+mod twitter;
+
 use axum::{
-    http::StatusCode,
-    response::{Html, IntoResponse, Json},
-    routing::get,
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
+use clap::Parser;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use tower_http::cors::CorsLayer;
 
+/// Lookback window for on-demand refreshes (matches the batch runner)
+const REFRESH_DAYS: u32 = 90;
+
+/// How long fetched daily prices are reused before refetching
+const PRICE_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Pause between preload requests (Alpha Vantage free tier allows 5 per minute)
+const PRELOAD_DELAY: Duration = Duration::from_secs(12);
+
+/// Web server options
+#[derive(Parser, Debug)]
+#[command(name = "web-server", about = "Serve the CEO tweet tracker dashboard")]
+struct ServerArgs {
+    /// Fetch prices for every configured ticker in the background at startup,
+    /// so the first refresh doesn't wait on the stock API
+    #[arg(long)]
+    preload_prices: bool,
+
+    /// Idle HTTP connections kept alive per API host for reuse
+    #[arg(long, default_value_t = http::DEFAULT_POOL_SIZE, value_name = "N")]
+    http_pool_size: usize,
+
+    /// Stretch each pause between API requests (and quota waits) by a random
+    /// 0-25% and randomize retry backoffs, so scheduled runs don't hit the
+    /// APIs in lockstep
+    #[arg(long)]
+    request_jitter: bool,
+
+    /// Seed for --request-jitter (reproducible delays)
+    #[arg(long, requires = "request_jitter", value_name = "N")]
+    jitter_seed: Option<u64>,
+
+    /// CEO config file (default: CEO_CONFIG_PATH, then ceo_config.json)
+    #[arg(long, value_name = "PATH")]
+    config_path: Option<String>,
+
+    /// Store at most N impacts per refreshed CEO (impactful, then biggest
+    /// moves, then most engaged); statistics still cover every tweet
+    #[arg(long, value_name = "N")]
+    store_max_impacts: Option<usize>,
+
+    /// Domain -> lexicon file mapping for CEOs with a `domain` (default:
+    /// LEXICONS_PATH, then lexicons.json)
+    #[arg(long, value_name = "PATH")]
+    lexicons: Option<String>,
+}
+
+/// Resolved CEO config path, set once at startup
+static CEO_CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// `--store-max-impacts`, set once at startup
+static STORE_MAX_IMPACTS: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Domain lexicons, loaded once at startup
+static LEXICONS: OnceLock<lexicon::DomainLexicons> = OnceLock::new();
+
+/// Held while a single-CEO refresh runs, so refreshes never overlap
+static REFRESH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Read briefly by handlers loading data/results.json and written while an
+/// update saves it, so a handler never reads results mid-update
+static RESULTS_LOCK: tokio::sync::RwLock<()> = tokio::sync::RwLock::const_new(());
+
+/// CEO/Ticker configuration
+#[derive(Debug, Deserialize)]
+struct CeoConfig {
+    ceo_handle: String,
+    ticker: String,
+    /// Shares outstanding, for market cap changes in USD
+    #[serde(default)]
+    shares_outstanding: Option<u64>,
+    /// Sector whose lexicon scores this CEO's tweets (None = built-in lexicon)
+    #[serde(default)]
+    domain: Option<String>,
+}
+
 /// Tracking data for a single CEO/stock pair (MONTHLY)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TrackingEntry {
@@ -21,14 +125,43 @@ struct TrackingEntry {
     ceo_handle: String,
     current_month: String,
     month_start_price: f64,
+    #[serde(default)]
+    month_start_date: Option<String>,
     current_price: f64,
+    #[serde(default)]
+    price_date: Option<String>,
+    #[serde(default)]
+    price_stale: bool,
     monthly_price_change_pct: f64,
     price_direction: String,
+    #[serde(default)]
+    flat_band_pct: Option<f64>,
     tweets_this_month: u32,
     positive_tweets: u32,
     negative_tweets: u32,
     neutral_tweets: u32,
     last_updated: String,
+    /// Finished months, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<MonthRecord>,
+}
+
+/// One finished month of a tracking entry (tweet counts are null when backfilled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MonthRecord {
+    month: String,
+    start_price: f64,
+    start_date: Option<String>,
+    end_price: f64,
+    end_date: Option<String>,
+    price_change_pct: f64,
+    price_direction: String,
+    tweets: Option<u32>,
+    positive_tweets: Option<u32>,
+    negative_tweets: Option<u32>,
+    neutral_tweets: Option<u32>,
+    #[serde(default)]
+    backfilled: bool,
 }
 
 /// Full tracking database
@@ -42,12 +175,39 @@ struct TrackingDatabase {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = ServerArgs::parse();
+    http::init(args.http_pool_size)?;
+    jitter::init(args.request_jitter, args.jitter_seed);
+    let config_path = CEO_CONFIG_PATH.get_or_init(|| storage::ceo_config_path(args.config_path.as_deref()));
+    STORE_MAX_IMPACTS.get_or_init(|| args.store_max_impacts);
+    let lexicons_path = lexicon::lexicons_path(args.lexicons.as_deref());
+    let lexicons = lexicon::DomainLexicons::load(&lexicons_path)?;
+    LEXICONS.get_or_init(|| lexicons);
     println!("Starting CEO Tweet Tracker Web Server...\n");
+    println!("CEO config: {}", config_path.display());
+
+    if args.preload_prices {
+        // Runs alongside the server; requests are served while it works
+        tokio::spawn(preload_prices());
+    }
 
     let app = Router::new()
         .route("/", get(serve_index))
         .route("/api/data", get(get_tracking_data))
         .route("/api/status", get(get_status))
+        .route("/api/sentiment-matrix", get(get_sentiment_matrix))
+        .route("/api/summaries", get(get_summaries))
+        .route("/api/leaderboard", get(get_leaderboard))
+        .route("/api/top-tweets", get(get_top_tweets))
+        .route("/api/causal/:handle", get(get_causal_tweets))
+        .route("/api/timeseries/:handle", get(get_timeseries))
+        .route("/api/pairs/:handle", get(get_correlation_pairs))
+        .route("/api/stats/global", get(get_global_stats))
+        .route("/api/refresh", post(refresh_batch))
+        .route("/api/refresh/:handle", post(refresh_ceo))
+        .route("/api/recompute", post(recompute_results))
+        .route("/api/rescore", post(rescore_results))
+        .route("/api/results/:file", get(export_result))
         .layer(CorsLayer::permissive());
 
     let addr = "127.0.0.1:3000";
@@ -60,6 +220,54 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Warm the price cache for every configured ticker, one request at a time
+async fn preload_prices() {
+    let configs = match load_ceo_configs().await {
+        Ok(configs) => configs,
+        Err(message) => {
+            eprintln!("Price preload skipped: {}", message);
+            return;
+        }
+    };
+    let mut tickers: Vec<String> = configs.into_iter().map(|c| c.ticker).collect();
+    tickers.sort();
+    tickers.dedup();
+    println!("Preloading prices for {} tickers in the background...", tickers.len());
+    
+    let mut loaded = 0;
+    for (idx, ticker) in tickers.iter().enumerate() {
+        if idx > 0 {
+            tokio::time::sleep(jitter::delay(PRELOAD_DELAY)).await;
+        }
+        match cached_prices(ticker, PRICE_CACHE_TTL).await {
+            Ok(_) => loaded += 1,
+            Err(e) => eprintln!("Price preload failed for {}: {}", ticker, e),
+        }
+    }
+    println!("Price preload complete ({}/{} tickers)", loaded, tickers.len());
+}
+
+/// Daily prices for `ticker`, from the cache when fetched within `max_age`
+///
+/// Batch refreshes pass `PRICE_CACHE_TTL` and reuse what the preload task
+/// fetched; single-CEO refreshes pass `Duration::ZERO`, so they always fetch
+/// current prices (and leave them in the cache for the next batch).
+async fn cached_prices(ticker: &str, max_age: Duration) -> anyhow::Result<Vec<models::PricePoint>> {
+    let stock_api_key = std::env::var("STOCK_API_KEY")
+        .map_err(|_| anyhow::anyhow!("STOCK_API_KEY is not set"))?;
+    let stock_api_base = std::env::var("STOCK_API_BASE")
+        .unwrap_or_else(|_| stocks::ALPHA_VANTAGE_BASE.to_string());
+    stocks::fetch_prices_shared(
+        ticker,
+        &stock_api_key,
+        &stock_api_base,
+        REFRESH_DAYS,
+        stocks::DEFAULT_PRICE_BUFFER_DAYS,
+        max_age,
+        false,
+    ).await
+}
+
 async fn serve_index() -> impl IntoResponse {
     Html(include_str!("../web/index.html"))
 }
@@ -93,9 +301,837 @@ async fn get_tracking_data() -> impl IntoResponse {
     }
 }
 
+async fn get_sentiment_matrix() -> impl IntoResponse {
+    match std::fs::read_to_string("data/sentiment_matrix.json") {
+        Ok(content) => {
+            match serde_json::from_str::<serde_json::Value>(&content) {
+                Ok(matrix) => (StatusCode::OK, Json(serde_json::json!({
+                    "success": true,
+                    "matrix": matrix
+                }))),
+                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                    "success": false,
+                    "error": format!("Failed to parse sentiment matrix: {}", e)
+                }))),
+            }
+        }
+        Err(_) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "success": true,
+                "matrix": null
+            })))
+        }
+    }
+}
+
+/// Load, change and save data/results.json under its lock
+///
+/// Runs on a blocking thread, since waiting for the lock would stall the
+/// async runtime.
+async fn update_stored_results<T: Send + 'static>(
+    update: impl FnOnce(&mut Vec<models::AnalysisResult>) -> anyhow::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let _guard = RESULTS_LOCK.write().await;
+    tokio::task::spawn_blocking(move || storage::update_results(false, update))
+        .await
+        .map_err(|e| format!("Results update failed: {}", e))?
+        .map_err(|e| format!("Failed to save results: {}", e))
+}
+
+/// Replace the stored results of the CEOs in `fresh` with these ones
+async fn store_fresh_results(fresh: Vec<models::AnalysisResult>) -> Result<(), String> {
+    update_stored_results(move |results| {
+        results.retain(|r| !fresh.iter().any(|f| f.ceo_handle.eq_ignore_ascii_case(&r.ceo_handle)));
+        results.extend(fresh);
+        Ok(())
+    }).await
+}
+
+/// Stored batch results (empty when none have been saved yet), read under
+/// the results read lock
+async fn load_stored_results() -> Result<Vec<models::AnalysisResult>, String> {
+    let _guard = RESULTS_LOCK.read().await;
+    match std::fs::read_to_string("data/results.json") {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse results: {}", e)),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// One compact summary per stored result, for list views
+async fn get_summaries() -> Response {
+    match load_stored_results().await {
+        Ok(results) => {
+            let summaries: Vec<models::AnalysisSummary> = results.iter().map(|r| r.summary()).collect();
+            Json(serde_json::json!({
+                "success": true,
+                "summaries": summaries
+            })).into_response()
+        }
+        Err(message) => internal_error(&message),
+    }
+}
+
+/// Query parameters for `/api/leaderboard`
+#[derive(Debug, Deserialize)]
+struct LeaderboardQuery {
+    /// `correlation` (default), `impactful_count` or `alpha`
+    metric: Option<String>,
+}
+
+/// What the leaderboard ranks CEOs by, highest first
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LeaderboardMetric {
+    Correlation,
+    ImpactfulCount,
+}
+
+impl LeaderboardMetric {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "correlation" => Ok(Self::Correlation),
+            "impactful_count" => Ok(Self::ImpactfulCount),
+            "alpha" => Err("metric=alpha needs benchmark-adjusted returns, which results don't record yet".to_string()),
+            other => Err(format!("Unknown leaderboard metric '{}' (use correlation or impactful_count)", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Correlation => "correlation",
+            Self::ImpactfulCount => "impactful_count",
+        }
+    }
+
+    /// The CEO's value, None when the result can't provide one
+    fn value(self, summary: &models::AnalysisSummary) -> Option<f64> {
+        match self {
+            Self::Correlation => summary.correlation_1d,
+            Self::ImpactfulCount => Some(summary.impactful_count as f64),
+        }
+    }
+}
+
+/// Stored CEOs ranked by `?metric=`, CEOs without a value last
+async fn get_leaderboard(Query(query): Query<LeaderboardQuery>) -> Response {
+    let metric = match LeaderboardMetric::parse(query.metric.as_deref().unwrap_or("correlation")) {
+        Ok(metric) => metric,
+        Err(message) => return bad_request(&message),
+    };
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+    
+    Json(serde_json::json!({
+        "success": true,
+        "metric": metric.name(),
+        "entries": leaderboard(&results, metric)
+    })).into_response()
+}
+
+/// Leaderboard entries (rank, value, summary) of `results` ranked by `metric`
+fn leaderboard(results: &[models::AnalysisResult], metric: LeaderboardMetric) -> Vec<serde_json::Value> {
+    let mut ranked: Vec<(models::AnalysisSummary, Option<f64>)> = results
+        .iter()
+        .map(|r| {
+            let summary = r.summary();
+            let value = metric.value(&summary);
+            (summary, value)
+        })
+        .collect();
+    // Missing values sort after every real one instead of counting as zero
+    ranked.sort_by(|a, b| match (a.1, b.1) {
+        (Some(x), Some(y)) => y.total_cmp(&x),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (summary, value))| serde_json::json!({
+            "rank": idx + 1,
+            "value": value,
+            "summary": summary,
+        }))
+        .collect()
+}
+
+/// Query parameters for `/api/top-tweets`
+#[derive(Debug, Deserialize)]
+struct TopTweetsQuery {
+    limit: Option<usize>,
+}
+
+/// Most impactful tweets across every stored CEO, ranked by impact index
+async fn get_top_tweets(Query(query): Query<TopTweetsQuery>) -> Response {
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+    
+    let mut ranked: Vec<(&models::AnalysisResult, &models::TweetImpact, f64)> = results
+        .iter()
+        .flat_map(|r| r.impacts.iter().filter_map(move |i| Some((r, i, i.impact_index?))))
+        .collect();
+    ranked.sort_by(|a, b| b.2.total_cmp(&a.2));
+    ranked.truncate(query.limit.unwrap_or(20).min(200));
+    
+    let tweets: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .map(|(result, impact, index)| ranked_tweet_json(result, impact, index))
+        .collect();
+    
+    Json(serde_json::json!({
+        "success": true,
+        "tweets": tweets
+    })).into_response()
+}
+
+/// One tweet in an impact-index ranking, with its CEO and ticker
+fn ranked_tweet_json(result: &models::AnalysisResult, impact: &models::TweetImpact, index: f64) -> serde_json::Value {
+    serde_json::json!({
+        "ceo_handle": result.ceo_handle,
+        "ticker": result.ticker,
+        "tweet_id": impact.tweet.id,
+        "created_at": impact.tweet.created_at,
+        "text": impact.tweet.text,
+        "sentiment": impact.tweet.sentiment,
+        "change_1d": impact.change_1d,
+        "move_z": impact.move_z,
+        "sentiment_z": impact.sentiment_z,
+        "impact_index": index,
+    })
+}
+
+/// Query parameters for `/api/causal/{handle}`
+#[derive(Debug, Deserialize)]
+struct CausalQuery {
+    limit: Option<usize>,
+    /// Comma-separated weights in `analysis::CausalWeights::from_list` order
+    weights: Option<String>,
+}
+
+/// One CEO's tweets ranked by causal score, with each factor's contribution
+///
+/// Scores are recomputed from the stored components, so `?weights=` can
+/// re-rank without re-running the analysis.
+async fn get_causal_tweets(Path(handle): Path<String>, Query(query): Query<CausalQuery>) -> Response {
+    let weights = match query.weights.as_deref().map(parse_causal_weights) {
+        None => analysis::CausalWeights::default(),
+        Some(Ok(weights)) => weights,
+        Some(Err(e)) => return bad_request(&e.to_string()),
+    };
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+    let handle = handle.trim_start_matches('@');
+    let Some(result) = results.iter().find(|r| r.ceo_handle.eq_ignore_ascii_case(handle)) else {
+        return not_found(&format!("No stored result for @{}", handle));
+    };
+    
+    let mut ranked: Vec<(&models::TweetImpact, f64, models::CausalComponents)> = result
+        .impacts
+        .iter()
+        .filter(|i| i.change_1d.is_some())
+        .filter_map(|i| {
+            let contributions = weights.contributions(&i.causal_components)?;
+            Some((i, weights.score(&i.causal_components)?, contributions))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(query.limit.unwrap_or(20).min(200));
+    
+    let tweets: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .map(|(impact, score, contributions)| serde_json::json!({
+            "tweet_id": impact.tweet.id,
+            "created_at": impact.tweet.created_at,
+            "text": impact.tweet.text,
+            "sentiment": impact.tweet.sentiment,
+            "change_1d": impact.change_1d,
+            "causal_score": score,
+            "components": impact.causal_components,
+            "contributions": contributions,
+        }))
+        .collect();
+    
+    Json(serde_json::json!({
+        "success": true,
+        "ceo_handle": result.ceo_handle,
+        "ticker": result.ticker,
+        "weights": weights,
+        "tweets": tweets
+    })).into_response()
+}
+
+/// One CEO's per-day close, average sentiment and tweet count, for charting
+async fn get_timeseries(Path(handle): Path<String>) -> Response {
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+    let handle = handle.trim_start_matches('@');
+    let Some(result) = results.iter().find(|r| r.ceo_handle.eq_ignore_ascii_case(handle)) else {
+        return not_found(&format!("No stored result for @{}", handle));
+    };
+    
+    Json(serde_json::json!({
+        "success": true,
+        "ceo_handle": result.ceo_handle,
+        "ticker": result.ticker,
+        "points": result.daily_timeseries
+    })).into_response()
+}
+
+/// `?window=` for `/api/pairs/:handle`
+#[derive(Deserialize)]
+struct PairsQuery {
+    window: Option<i64>,
+}
+
+/// The (sentiment, change) pairs behind one CEO's stored correlations, for
+/// plotting the scatter; `?window=0|1|3` keeps one window
+async fn get_correlation_pairs(Path(handle): Path<String>, Query(query): Query<PairsQuery>) -> Response {
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+    let handle = handle.trim_start_matches('@');
+    let Some(result) = results.iter().find(|r| r.ceo_handle.eq_ignore_ascii_case(handle)) else {
+        return not_found(&format!("No stored result for @{}", handle));
+    };
+    
+    let mut pairs = analysis::correlation_pairs(&result.impacts);
+    if let Some(window) = query.window {
+        pairs.retain(|p| p.window_days == window);
+    }
+    Json(serde_json::json!({
+        "success": true,
+        "ceo_handle": result.ceo_handle,
+        "ticker": result.ticker,
+        "impacts_trimmed": result.impacts_trimmed,
+        "pairs": pairs
+    })).into_response()
+}
+
+/// Parse `?weights=0.35,0.2,...`
+fn parse_causal_weights(list: &str) -> anyhow::Result<analysis::CausalWeights> {
+    let values = list
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|e| anyhow::anyhow!("Invalid causal weight: {}", e))?;
+    analysis::CausalWeights::from_list(&values)
+}
+
+/// Headline numbers across every stored CEO for the dashboard overview
+///
+/// Significance is a 5% two-sided test of each CEO's 1-day correlation over
+/// the pairs it was computed from (the `n` of its 1-day `correlations` entry).
+async fn get_global_stats() -> Response {
+    match load_stored_results().await {
+        Ok(results) => Json(global_stats(&results)).into_response(),
+        Err(message) => internal_error(&message),
+    }
+}
+
+/// The `/api/stats/global` body for `results`
+fn global_stats(results: &[models::AnalysisResult]) -> serde_json::Value {
+    let mut correlations: Vec<f64> = results.iter().filter_map(|r| r.correlation_1d).collect();
+    correlations.sort_by(f64::total_cmp);
+    let mean_correlation = (!correlations.is_empty())
+        .then(|| correlations.iter().sum::<f64>() / correlations.len() as f64);
+    let median_correlation = match correlations.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(correlations[n / 2]),
+        n => Some((correlations[n / 2 - 1] + correlations[n / 2]) / 2.0),
+    };
+    let significant = results
+        .iter()
+        .filter(|r| {
+            let pairs = r.correlations.iter().find(|c| c.window_days == 1).map_or(0, |c| c.n);
+            r.correlation_1d.is_some_and(|c| analysis::correlation_is_significant(c, pairs))
+        })
+        .count();
+    let top_tweet = results
+        .iter()
+        .flat_map(|r| r.impacts.iter().filter_map(move |i| Some((r, i, i.impact_index?))))
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(result, impact, index)| ranked_tweet_json(result, impact, index));
+    
+    serde_json::json!({
+        "success": true,
+        "ceos_tracked": results.len(),
+        "tweets_analyzed": results.iter().map(|r| r.total_tweets).sum::<usize>(),
+        "mean_correlation_1d": mean_correlation,
+        "median_correlation_1d": median_correlation,
+        "significant_correlations": significant,
+        "most_impactful_tweet": top_tweet
+    })
+}
+
+/// Query parameters for `POST /api/refresh` (comma-separated handle lists)
+#[derive(Debug, Deserialize)]
+struct RefreshQuery {
+    only: Option<String>,
+    skip: Option<String>,
+}
+
+/// Load the configured CEOs from the CEO config file
+async fn load_ceo_configs() -> Result<Vec<CeoConfig>, String> {
+    let path = CEO_CONFIG_PATH.get_or_init(|| storage::ceo_config_path(None));
+    let mut configs: Vec<CeoConfig> = tokio::task::spawn_blocking(move || storage::load_ceo_config(path))
+        .await
+        .map_err(|e| format!("Config load failed: {}", e))?
+        .map_err(|e| format!("{:#}", e))?;
+    for config in &mut configs {
+        config.ticker = models::canonical_ticker(&config.ticker);
+    }
+    Ok(configs)
+}
+
+/// Re-analyze every configured CEO selected by `?only=a,b` / `?skip=c`
+///
+/// CEOs are refreshed one at a time; failures are reported per handle and
+/// leave that CEO's stored result untouched. Prices fetched within
+/// `PRICE_CACHE_TTL` (e.g. by the preload) are reused. Shares the single-CEO lock.
+async fn refresh_batch(Query(query): Query<RefreshQuery>) -> Response {
+    let Ok(_guard) = REFRESH_LOCK.try_lock() else {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({
+            "success": false,
+            "error": "A refresh is already in progress"
+        }))).into_response();
+    };
+    
+    let configs = match load_ceo_configs().await {
+        Ok(configs) => configs,
+        Err(message) => return internal_error(&message),
+    };
+    let filter = models::HandleFilter::from_lists(query.only.as_deref(), query.skip.as_deref());
+    
+    let mut fresh = Vec::new();
+    let mut failed = Vec::new();
+    for config in configs.iter().filter(|c| filter.selects(&c.ceo_handle)) {
+        match analyze_ceo(config, false).await {
+            Ok(result) => fresh.push(result),
+            Err(e) => failed.push(serde_json::json!({
+                "handle": config.ceo_handle,
+                "error": e.to_string()
+            })),
+        }
+    }
+    
+    let refreshed: Vec<String> = fresh.iter().map(|r| r.ceo_handle.clone()).collect();
+    if !fresh.is_empty() {
+        if let Err(message) = store_fresh_results(fresh).await {
+            return internal_error(&message);
+        }
+    }
+    
+    Json(serde_json::json!({
+        "success": failed.is_empty(),
+        "refreshed": refreshed,
+        "failed": failed
+    })).into_response()
+}
+
+/// Re-fetch tweets and prices for one configured CEO, re-analyze, and store the result
+///
+/// Always fetches fresh data (nothing is served from earlier results) and
+/// replaces that CEO's entry in data/results.json. Returns 409 while another
+/// refresh is running.
+async fn refresh_ceo(Path(handle): Path<String>) -> Response {
+    let handle = handle.trim_start_matches('@');
+    let Ok(_guard) = REFRESH_LOCK.try_lock() else {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({
+            "success": false,
+            "error": "A refresh is already in progress"
+        }))).into_response();
+    };
+    
+    let configs = match load_ceo_configs().await {
+        Ok(configs) => configs,
+        Err(message) => return internal_error(&message),
+    };
+    let Some(config) = configs.iter().find(|c| c.ceo_handle.eq_ignore_ascii_case(handle)) else {
+        return not_found(&format!("@{} is not in ceo_config.json", handle));
+    };
+    
+    match analyze_ceo(config, true).await {
+        Ok(result) => {
+            if let Err(message) = store_fresh_results(vec![result.clone()]).await {
+                return internal_error(&message);
+            }
+            
+            Json(serde_json::json!({
+                "success": true,
+                "result": result
+            })).into_response()
+        }
+        Err(e) => internal_error(&format!("Refresh of @{} failed: {}", config.ceo_handle, e)),
+    }
+}
+
+/// Re-run analysis and rules over the stored raw fetches, with no network access
+///
+/// Honors `?only=` / `?skip=` like `/api/refresh`. Lets analysis changes be
+/// tried against the last fetched data without spending API quota.
+async fn recompute_results(Query(query): Query<RefreshQuery>) -> Response {
+    let Ok(_guard) = REFRESH_LOCK.try_lock() else {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({
+            "success": false,
+            "error": "A refresh is already in progress"
+        }))).into_response();
+    };
+    
+    let loaded = tokio::task::spawn_blocking(storage::load_raw_fetches).await;
+    let raws = match loaded.map_err(anyhow::Error::from).and_then(|raws| raws) {
+        Ok(raws) => raws,
+        Err(e) => return internal_error(&format!("Failed to load raw data: {}", e)),
+    };
+    let filter = models::HandleFilter::from_lists(query.only.as_deref(), query.skip.as_deref());
+    // Raw fetches don't record share counts; take them from the current config
+    let configs = load_ceo_configs().await.unwrap_or_default();
+    
+    let mut fresh = Vec::new();
+    let mut failed = Vec::new();
+    for raw in raws.into_iter().filter(|r| filter.selects(&r.ceo_handle)) {
+        let handle = raw.ceo_handle.clone();
+        let config = configs.iter().find(|c| c.ceo_handle.eq_ignore_ascii_case(&handle));
+        let shares = config.and_then(|c| c.shares_outstanding);
+        match ceo_lexicon(config).and_then(|lexicon| analyze_raw(raw, shares, lexicon)) {
+            Ok(result) => fresh.push(result),
+            Err(e) => failed.push(serde_json::json!({
+                "handle": handle,
+                "error": e.to_string()
+            })),
+        }
+    }
+    if fresh.is_empty() && failed.is_empty() {
+        return not_found("No stored raw data; refresh first");
+    }
+    
+    let recomputed: Vec<String> = fresh.iter().map(|r| r.ceo_handle.clone()).collect();
+    if !fresh.is_empty() {
+        if let Err(message) = store_fresh_results(fresh).await {
+            return internal_error(&message);
+        }
+    }
+    
+    Json(serde_json::json!({
+        "success": failed.is_empty(),
+        "recomputed": recomputed,
+        "failed": failed
+    })).into_response()
+}
+
+/// Re-score stored results with each CEO's lexicon, keeping their price changes
+///
+/// Honors `?only=` / `?skip=`. Each result's statistics are re-derived from
+/// the prices in its stored raw fetch, with the settings it was run with;
+/// results without one are reported as failed and left unchanged. The
+/// sentiment matrix is rebuilt from the saved results.
+async fn rescore_results(Query(query): Query<RefreshQuery>) -> Response {
+    let Ok(_guard) = REFRESH_LOCK.try_lock() else {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({
+            "success": false,
+            "error": "A refresh is already in progress"
+        }))).into_response();
+    };
+    
+    let filter = models::HandleFilter::from_lists(query.only.as_deref(), query.skip.as_deref());
+    match load_stored_results().await {
+        Ok(results) if results.iter().any(|r| filter.selects(&r.ceo_handle)) => {}
+        Ok(_) => return not_found("No stored results; refresh first"),
+        Err(message) => return internal_error(&message),
+    }
+    let configs = load_ceo_configs().await.unwrap_or_default();
+    
+    // The whole read-modify-write holds the results lock
+    let outcome = update_stored_results(move |results| {
+        let mut rescored = Vec::new();
+        let mut failed = Vec::new();
+        for result in results.iter_mut().filter(|r| filter.selects(&r.ceo_handle)) {
+            let raw = match storage::load_raw_fetch(&result.ceo_handle) {
+                Ok(Some(raw)) => raw,
+                Ok(None) => {
+                    failed.push(serde_json::json!({ "handle": result.ceo_handle, "error": "no stored raw data" }));
+                    continue;
+                }
+                Err(e) => {
+                    failed.push(serde_json::json!({ "handle": result.ceo_handle, "error": e.to_string() }));
+                    continue;
+                }
+            };
+            let config = configs.iter().find(|c| c.ceo_handle.eq_ignore_ascii_case(&result.ceo_handle));
+            let lexicon = match ceo_lexicon(config) {
+                Ok(lexicon) => lexicon,
+                Err(e) => {
+                    failed.push(serde_json::json!({ "handle": result.ceo_handle, "error": e.to_string() }));
+                    continue;
+                }
+            };
+            let options = analysis::stored_options(result, lexicon);
+            // Trimmed results are analyzed again from raw data, so statistics cover every tweet
+            let changed = match analysis::rescore_stored(result, raw, &options, &prolog::FactsFilter::default()) {
+                Ok(changed) => changed,
+                Err(e) => {
+                    failed.push(serde_json::json!({ "handle": result.ceo_handle, "error": e.to_string() }));
+                    continue;
+                }
+            };
+            rescored.push(serde_json::json!({
+                "handle": result.ceo_handle,
+                "changed": changed,
+                "lexicon": result.lexicon,
+                "lexicon_hash": result.lexicon_hash
+            }));
+        }
+        
+        storage::save_sentiment_matrix(&analysis::sentiment_correlation_matrix(results), false)?;
+        Ok((rescored, failed))
+    }).await;
+    let (rescored, failed) = match outcome {
+        Ok(outcome) => outcome,
+        Err(message) => return internal_error(&message),
+    };
+    
+    Json(serde_json::json!({
+        "success": failed.is_empty(),
+        "rescored": rescored,
+        "failed": failed
+    })).into_response()
+}
+
+/// Fetch, analyze and apply the Prolog rules for one CEO, as the batch runner does
+///
+/// The raw tweets and prices are stored first, for `/api/recompute`. With
+/// `fresh`, the whole timeline and current prices are fetched; otherwise only
+/// tweets newer than the stored fetch are requested and prices may come from
+/// the price cache.
+async fn analyze_ceo(config: &CeoConfig, fresh: bool) -> anyhow::Result<models::AnalysisResult> {
+    let lexicon = ceo_lexicon(Some(config))?;
+    let twitter_token = std::env::var("TWITTER_BEARER_TOKEN").ok();
+    let stock_api_base = std::env::var("STOCK_API_BASE")
+        .unwrap_or_else(|_| stocks::ALPHA_VANTAGE_BASE.to_string());
+    let twitter_api_base = std::env::var("TWITTER_API_BASE")
+        .unwrap_or_else(|_| twitter::TWITTER_API_BASE.to_string());
+    
+    // Scheduled batch refreshes only request tweets newer than the last stored fetch
+    let cached_tweets = if fresh {
+        Vec::new()
+    } else {
+        let handle = config.ceo_handle.clone();
+        tokio::task::spawn_blocking(move || storage::load_raw_fetch(&handle))
+            .await
+            .ok()
+            .and_then(|loaded| loaded.ok())
+            .flatten()
+            .map(|raw| raw.cached_timeline(REFRESH_DAYS))
+            .unwrap_or_default()
+    };
+    let fetch = twitter::fetch_tweets(
+        &config.ceo_handle,
+        &twitter_api_base,
+        twitter_token.as_deref(),
+        std::env::var("TWITTER_USERNAME").ok().as_deref(),
+        std::env::var("TWITTER_PASSWORD").ok().as_deref(),
+        &twitter::FetchOptions { cached_tweets, ..twitter::FetchOptions::days(REFRESH_DAYS) },
+        false,
+    ).await?;
+    if fetch.tweets.is_empty() {
+        anyhow::bail!("No tweets found");
+    }
+    
+    let max_age = if fresh { Duration::ZERO } else { PRICE_CACHE_TTL };
+    let prices = cached_prices(&config.ticker, max_age).await?;
+    if prices.is_empty() {
+        anyhow::bail!("No price data found");
+    }
+    
+    let raw = models::RawFetch {
+        ceo_handle: config.ceo_handle.clone(),
+        ticker: config.ticker.clone(),
+        fetched_at: chrono::Utc::now(),
+        days: REFRESH_DAYS,
+        tweet_source: if twitter_token.is_some() { "twitter_api" } else { "scraper" }.to_string(),
+        stock_provider: stock_api_base,
+        follower_count: fetch.follower_count,
+        tweets: fetch.tweets,
+        prices,
+    };
+    // Waiting for the file lock mustn't stall the runtime's other tasks
+    let (raw, saved) = tokio::task::spawn_blocking(move || {
+        let saved = storage::save_raw_fetch(&raw);
+        (raw, saved)
+    }).await?;
+    if let Err(e) = saved {
+        eprintln!("WARNING: Failed to store raw data for @{}: {}", raw.ceo_handle, e);
+    }
+    
+    analyze_raw(raw, config.shares_outstanding, lexicon)
+}
+
+/// Lexicon for a configured CEO's domain; the built-in one for CEOs without
+/// a domain or no longer in the config
+fn ceo_lexicon(config: Option<&CeoConfig>) -> anyhow::Result<Arc<lexicon::Lexicon>> {
+    LEXICONS
+        .get_or_init(lexicon::DomainLexicons::default)
+        .get(config.and_then(|c| c.domain.as_deref()))
+}
+
+/// Analyze one raw fetch with the current settings and apply the Prolog rules
+fn analyze_raw(
+    raw: models::RawFetch,
+    shares_outstanding: Option<u64>,
+    lexicon: Arc<lexicon::Lexicon>,
+) -> anyhow::Result<models::AnalysisResult> {
+    let options = analysis::AnalysisOptions { shares_outstanding, lexicon, ..Default::default() };
+    let mut result = analysis::analyze(&raw.ceo_handle, &raw.ticker, raw.tweets, raw.prices, &options, false)?;
+    result.follower_count = raw.follower_count;
+    result.run_config = Some(analysis::run_config(
+        &options,
+        raw.days,
+        stocks::DEFAULT_PRICE_BUFFER_DAYS,
+        &raw.tweet_source,
+        &raw.stock_provider,
+    ));
+    prolog::apply_rules(&mut result, None, &prolog::FactsFilter::default())?;
+    if let Some(max) = STORE_MAX_IMPACTS.get().copied().flatten() {
+        result.trim_impacts(max);
+    }
+    
+    Ok(result)
+}
+
+/// Download one CEO's stored result as `{handle}.csv` or `{handle}.md`
+async fn export_result(Path(file): Path<String>) -> Response {
+    let Some((handle, extension)) = file.rsplit_once('.') else {
+        return not_found(&format!("Unknown export: {}", file));
+    };
+
+    let (content_type, render): (&str, fn(&models::AnalysisResult) -> String) = match extension {
+        "csv" => ("text/csv; charset=utf-8", output::render_csv),
+        "md" => ("text/markdown; charset=utf-8", output::render_markdown),
+        _ => return not_found(&format!("Unsupported export format: {}", extension)),
+    };
+
+    let results = match load_stored_results().await {
+        Ok(results) => results,
+        Err(message) => return internal_error(&message),
+    };
+
+    match results.iter().find(|r| r.ceo_handle.eq_ignore_ascii_case(handle)) {
+        Some(result) => (
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file)),
+            ],
+            render(result),
+        ).into_response(),
+        None => not_found(&format!("No stored result for @{}", handle)),
+    }
+}
+
+fn internal_error(message: &str) -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+        "success": false,
+        "error": message
+    }))).into_response()
+}
+
+fn bad_request(message: &str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+        "success": false,
+        "error": message
+    }))).into_response()
+}
+
+fn not_found(message: &str) -> Response {
+    (StatusCode::NOT_FOUND, Json(serde_json::json!({
+        "success": false,
+        "error": message
+    }))).into_response()
+}
+
 async fn get_status() -> impl IntoResponse {
     Json(serde_json::json!({
         "status": "running",
-        "version": "0.3.0"
+        "version": "0.3.0",
+        "quotas": http::quotas()
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use models::test_support::test_tweet;
+
+    /// A stored result whose 1-day correlation `r` came from `n` pairs, with
+    /// no impacts kept (as after `--store-max-impacts 0`)
+    fn result_with_correlation(handle: &str, r: Option<f64>, n: usize) -> models::AnalysisResult {
+        let mut result = models::AnalysisResult::new(handle.to_string(), "TSLA".to_string(), Utc::now(), Utc::now());
+        result.correlation_1d = r;
+        result.correlations = vec![models::CorrelationDetail { window_days: 1, value: r, n, reason: None }];
+        result
+    }
+
+    #[test]
+    fn test_global_stats_use_stored_pair_counts() {
+        let mut results = vec![
+            result_with_correlation("a", Some(0.6), 40),
+            result_with_correlation("b", Some(0.6), 4),
+            result_with_correlation("c", Some(-0.2), 40),
+            result_with_correlation("d", Some(0.1), 40),
+            result_with_correlation("e", None, 1),
+        ];
+        let mut impact = models::TweetImpact::new(test_tweet("42", "great", Utc::now()));
+        impact.impact_index = Some(3.5);
+        results[3].impacts.push(impact);
+
+        let stats = global_stats(&results);
+        assert_eq!(stats["ceos_tracked"], 5);
+        // Only @a's correlation holds up over its 40 pairs; @b's has just 4
+        assert_eq!(stats["significant_correlations"], 1);
+        assert!((stats["mean_correlation_1d"].as_f64().unwrap() - 0.275).abs() < 1e-12);
+        // Even count: the mean of the middle two of -0.2, 0.1, 0.6, 0.6
+        assert!((stats["median_correlation_1d"].as_f64().unwrap() - 0.35).abs() < 1e-12);
+        assert_eq!(stats["most_impactful_tweet"]["tweet_id"], "42");
+        assert_eq!(stats["most_impactful_tweet"]["ceo_handle"], "d");
+
+        // Odd count: the middle value
+        let odd = global_stats(&results[1..4]);
+        assert!((odd["median_correlation_1d"].as_f64().unwrap() - 0.1).abs() < 1e-12);
+
+        let empty = global_stats(&[]);
+        assert_eq!((empty["ceos_tracked"].as_u64(), empty["median_correlation_1d"].is_null()), (Some(0), true));
+    }
+
+    #[test]
+    fn test_leaderboard_ranks_highest_first_and_missing_last() {
+        let mut results = vec![
+            result_with_correlation("low", Some(-0.4), 20),
+            result_with_correlation("none", None, 0),
+            result_with_correlation("high", Some(0.7), 20),
+        ];
+        results[0].impactful_trimmed = 5;
+        results[2].impactful_trimmed = 2;
+        let handles = |entries: &[serde_json::Value]| -> Vec<String> {
+            entries.iter().map(|e| e["summary"]["ceo_handle"].as_str().unwrap().to_string()).collect()
+        };
+
+        let by_correlation = leaderboard(&results, LeaderboardMetric::Correlation);
+        assert_eq!(handles(&by_correlation), ["high", "low", "none"]);
+        assert_eq!(by_correlation[0]["rank"], 1);
+        assert!(by_correlation[2]["value"].is_null());
+
+        let by_count = leaderboard(&results, LeaderboardMetric::ImpactfulCount);
+        assert_eq!(handles(&by_count), ["low", "high", "none"]);
+        assert_eq!(by_count[0]["value"], 5.0);
+    }
+}
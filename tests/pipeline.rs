@@ -0,0 +1,93 @@
+//! End-to-end run of the analysis pipeline on fixture tweets and prices
+//!
+//! Loads `tests/fixtures/*.json` instead of calling Twitter or Alpha Vantage,
+//! then runs `analysis::analyze` → `prolog::apply_rules` and the output
+//! renderers, the same sequence the CLI uses.
+
+#![allow(dead_code)]
+
+#[path = "../src/analysis.rs"]
+mod analysis;
+#[path = "../src/jitter.rs"]
+mod jitter;
+#[path = "../src/lexicon.rs"]
+mod lexicon;
+#[path = "../src/models.rs"]
+mod models;
+#[path = "../src/news.rs"]
+mod news;
+#[path = "../src/output.rs"]
+mod output;
+#[path = "../src/prolog.rs"]
+mod prolog;
+#[path = "../src/storage.rs"]
+mod storage;
+
+use models::{AnalysisResult, PricePoint, Tweet};
+use output::OutputRenderer;
+
+/// Pearson r of sentiment vs 1-day change over the fixtures
+const CORRELATION_1D: f64 = 0.9305;
+
+fn load_fixture<T: serde::de::DeserializeOwned>(name: &str) -> T {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn run_pipeline() -> AnalysisResult {
+    let tweets: Vec<Tweet> = load_fixture("tweets.json");
+    let prices: Vec<PricePoint> = load_fixture("prices.json");
+
+    let options = analysis::AnalysisOptions::default();
+    let mut result = analysis::analyze("fixtureceo", "FIXT", tweets, prices, &options, false).unwrap();
+    prolog::apply_rules(&mut result, None, &prolog::FactsFilter::default()).unwrap();
+    result
+}
+
+#[test]
+fn test_pipeline_flags_impactful_tweets() {
+    let result = run_pipeline();
+
+    assert_eq!(result.total_tweets, 5);
+    assert_eq!(result.tweets_with_price_data, 5);
+
+    // The strongly worded tweets before the +5% and -6% days
+    let impactful: Vec<&str> = result.impacts.iter()
+        .filter(|i| i.is_impactful)
+        .map(|i| i.tweet.id.as_str())
+        .collect();
+    assert_eq!(impactful.len(), 2);
+    assert!(impactful.contains(&"1001") && impactful.contains(&"1002"));
+}
+
+#[test]
+fn test_pipeline_correlation() {
+    let result = run_pipeline();
+
+    let correlation = result.correlation_1d.unwrap();
+    assert!((correlation - CORRELATION_1D).abs() < 1e-3, "correlation_1d = {}", correlation);
+    assert!(result.slope_1d.unwrap() > 0.0);
+}
+
+#[test]
+fn test_pipeline_result_round_trips_and_renders() {
+    let result = run_pipeline();
+
+    let json = serde_json::to_string(&result).unwrap();
+    let restored: AnalysisResult = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.impacts.len(), result.impacts.len());
+    assert!((restored.correlation_1d.unwrap() - result.correlation_1d.unwrap()).abs() < 1e-12);
+    assert_eq!(restored.impacts[0].tweet.text, result.impacts[0].tweet.text);
+    assert_eq!(restored.impacts.iter().filter(|i| i.is_impactful).count(), 2);
+
+    // Non-ASCII tweet text must survive every text renderer
+    let mut out = Vec::new();
+    output::ChainRenderer(vec![
+        Box::new(output::CsvRenderer { aggregate: None }),
+        Box::new(output::MarkdownRenderer),
+    ])
+    .render(&result, &mut out)
+    .unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("München"));
+}